@@ -14,24 +14,61 @@
 // You should have received a copy of the GNU General Public License
 // along with ink!.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::{AccountId, AccountIndex, Balance, Hash, NodeRuntimeTypes};
+use crate::{
+    AccountId, AccountIndex, Balance, BasisPoints, BlockNumber, CollectionId, Hash,
+    NodeRuntimeTypes, Signature, TokenBalance,
+};
 use ink_core::env::EnvTypes;
 // use ink_core::storage::Vec;
 use ink_prelude::vec::Vec;
 use pallet_indices::address::Address;
-use scale::{Codec, Decode, Encode};
+use scale::{Codec, Decode, Encode, Input, Output};
 use sp_runtime::traits::Member;
 
-/// Default runtime Call type, a subset of the runtime Call module variants
+/// Module indices of the calls in the runtime
 ///
-/// The codec indices of the  modules *MUST* match those in the concrete runtime.
-#[derive(Encode, Decode)]
+/// They are exported by the test runtime, the reference for the order of modules
+/// in the concrete runtime. Unit tests check them against the encoded runtime calls.
+pub mod indices {
+    include!("../../../test-runtime/src/indices.rs");
+}
+
+/// Default runtime Call type, a subset of the runtime Call module variants
 #[cfg_attr(feature = "std", derive(Clone, PartialEq, Eq))]
 pub enum Call {
-    #[codec(index = "6")]
     Balances(Balances<NodeRuntimeTypes, AccountIndex>),
-    #[codec(index = "2")]
     NFT(NFT<NodeRuntimeTypes>),
+    ERC721(ERC721<NodeRuntimeTypes>),
+}
+
+impl Encode for Call {
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        match self {
+            Call::Balances(call) => {
+                dest.push_byte(indices::BALANCES);
+                call.encode_to(dest);
+            }
+            Call::NFT(call) => {
+                dest.push_byte(indices::NFT);
+                call.encode_to(dest);
+            }
+            Call::ERC721(call) => {
+                dest.push_byte(indices::ERC721);
+                call.encode_to(dest);
+            }
+        }
+    }
+}
+
+impl Decode for Call {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, scale::Error> {
+        match input.read_byte()? {
+            indices::BALANCES => Ok(Call::Balances(Decode::decode(input)?)),
+            indices::NFT => Ok(Call::NFT(Decode::decode(input)?)),
+            indices::ERC721 => Ok(Call::ERC721(Decode::decode(input)?)),
+            _ => Err("Invalid module index of Call".into()),
+        }
+    }
 }
 
 impl From<Balances<NodeRuntimeTypes, AccountIndex>> for Call {
@@ -46,6 +83,12 @@ impl From<NFT<NodeRuntimeTypes>> for Call {
     }
 }
 
+impl From<ERC721<NodeRuntimeTypes>> for Call {
+    fn from(erc721_call: ERC721<NodeRuntimeTypes>) -> Call {
+        Call::ERC721(erc721_call)
+    }
+}
+
 /// Generic Balance Call, could be used with other runtimes
 #[derive(Encode, Decode, Clone, PartialEq, Eq)]
 pub enum Balances<T, AccountIndex>
@@ -67,7 +110,12 @@ where
     ),
 }
 
-/// Generic Balance Call, could be used with other runtimes
+/// Generic NFT registry Call, could be used with other runtimes
+///
+/// The codec indices of the variants *MUST* match the order of calls in `nftregistry::Module`.
+/// `mint` (index 1) is not callable from contracts, so it is not included.
+/// `mint_signed` (index 18) is sent by relayers with a signed request, so it is not included either.
+/// `mint_with_user` (index 23) is not callable from contracts like `mint`.
 #[derive(Encode, Decode, Clone, PartialEq, Eq)]
 pub enum NFT<T>
 where
@@ -75,7 +123,171 @@ where
     T::AccountId: Member + Codec,
 {
     #[allow(non_camel_case_types)]
+    #[codec(index = "0")]
+    new_registry(T::AccountId),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "2")]
     finish_mint(u64, T::Hash, T::AccountId, Vec<u8>),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "3")]
+    transfer_from(T::AccountId, T::AccountId, T::Hash),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "4")]
+    burn(T::Hash),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "5")]
+    approve(T::AccountId, T::Hash),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "6")]
+    set_approval_for_all(T::AccountId, bool),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "7")]
+    new_fungible_registry(T::AccountId, TokenBalance),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "8")]
+    batch_transfer_from(T::AccountId, T::AccountId, Vec<T::Hash>),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "9")]
+    batch_approve(T::AccountId, Vec<T::Hash>),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "10")]
+    batch_burn(Vec<T::Hash>),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "11")]
+    set_registry_royalty(u64, T::AccountId, BasisPoints),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "12")]
+    set_token_royalty(T::Hash, T::AccountId, BasisPoints),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "13")]
+    transfer_with_payment(T::AccountId, T::AccountId, T::Hash, T::Balance),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "14")]
+    propose_swap(
+        Vec<T::Hash>,
+        T::Balance,
+        Vec<T::Hash>,
+        T::Balance,
+        T::AccountId,
+        T::BlockNumber,
+    ),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "15")]
+    accept_swap(u64),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "16")]
+    cancel_swap(u64),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "17")]
+    set_sponsor_allowance(T::AccountId, u32, T::Balance),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "19")]
+    set_co_owners(T::Hash, Vec<T::AccountId>, u32),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "20")]
+    propose_action(T::Hash, CoOwnerAction<T::AccountId>, T::BlockNumber),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "21")]
+    confirm_proposal(u64),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "22")]
+    cancel_proposal(u64),
+}
+
+/// Action on a co-owned token, must keep the same encoding as `CoOwnerAction` in `nftregistry`
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub enum CoOwnerAction<AccountId> {
+    TransferFrom(AccountId),
+    Burn,
+    Approve(AccountId),
+}
+
+/// Generic ERC721 Call, could be used with other runtimes
+///
+/// The codec indices of the variants *MUST* match the order of calls in `erc721::Module`.
+/// `set_collection_metadata` (index 6) requires root origin, so it is not included.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub enum ERC721<T>
+where
+    T: EnvTypes,
+    T::AccountId: Member + Codec,
+{
+    #[allow(non_camel_case_types)]
+    #[codec(index = "0")]
+    mint(CollectionId, T::Hash),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "1")]
+    burn(CollectionId, T::Hash),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "2")]
+    approve(CollectionId, T::AccountId, T::Hash),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "3")]
+    set_approval_for_all(CollectionId, T::AccountId, bool),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "4")]
+    transfer_from(CollectionId, T::AccountId, T::AccountId, T::Hash),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "5")]
+    create_token(CollectionId),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "7")]
+    set_token_uri(CollectionId, T::Hash, Vec<u8>),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "8")]
+    create_collection(),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "9")]
+    destroy_collection(CollectionId),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "10")]
+    batch_transfer_from(CollectionId, T::AccountId, T::AccountId, Vec<T::Hash>),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "11")]
+    batch_approve(CollectionId, T::AccountId, Vec<T::Hash>),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "12")]
+    batch_burn(CollectionId, Vec<T::Hash>),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "13")]
+    safe_transfer_from(CollectionId, T::AccountId, T::AccountId, T::Hash, Vec<u8>),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "14")]
+    approve_with_limit(
+        CollectionId,
+        T::AccountId,
+        T::Hash,
+        Option<T::BlockNumber>,
+        Option<u32>,
+    ),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "15")]
+    set_approval_for_all_with_limit(
+        CollectionId,
+        T::AccountId,
+        Option<T::BlockNumber>,
+        Option<u32>,
+    ),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "16")]
+    permit(
+        CollectionId,
+        T::AccountId,
+        T::AccountId,
+        T::Hash,
+        T::BlockNumber,
+        u64,
+        Signature,
+    ),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "17")]
+    set_user(CollectionId, T::Hash, T::AccountId, T::BlockNumber),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "18")]
+    nest(CollectionId, T::Hash, T::Hash),
+    #[allow(non_camel_case_types)]
+    #[codec(index = "19")]
+    unnest(CollectionId, T::Hash),
 }
 
 /// Construct a `Balances::transfer` call
@@ -83,18 +295,348 @@ pub fn transfer_balance(account: AccountId, balance: Balance) -> Call {
     Balances::<NodeRuntimeTypes, AccountIndex>::transfer(account.into(), balance).into()
 }
 
+/// Construct a `NFT::new_registry` call
+pub fn new_registry(validation_fn: AccountId) -> Call {
+    NFT::<NodeRuntimeTypes>::new_registry(validation_fn).into()
+}
+
+/// Construct a `NFT::finish_mint` call
 pub fn finish_mint(uid: u64, token_id: Hash, account: AccountId, metadata: Vec<u8>) -> Call {
     NFT::<NodeRuntimeTypes>::finish_mint(uid, token_id, account, metadata).into()
 }
 
+/// Construct a `NFT::transfer_from` call
+pub fn transfer_from(from: AccountId, to: AccountId, token_id: Hash) -> Call {
+    NFT::<NodeRuntimeTypes>::transfer_from(from, to, token_id).into()
+}
+
+/// Construct a `NFT::burn` call
+pub fn burn(token_id: Hash) -> Call {
+    NFT::<NodeRuntimeTypes>::burn(token_id).into()
+}
+
+/// Construct a `NFT::approve` call
+pub fn approve(to: AccountId, token_id: Hash) -> Call {
+    NFT::<NodeRuntimeTypes>::approve(to, token_id).into()
+}
+
+/// Construct a `NFT::set_approval_for_all` call
+pub fn set_approval_for_all(to: AccountId, approved: bool) -> Call {
+    NFT::<NodeRuntimeTypes>::set_approval_for_all(to, approved).into()
+}
+
+/// Construct a `NFT::new_fungible_registry` call
+pub fn new_fungible_registry(validation_fn: AccountId, shares: TokenBalance) -> Call {
+    NFT::<NodeRuntimeTypes>::new_fungible_registry(validation_fn, shares).into()
+}
+
+/// Construct a `NFT::batch_transfer_from` call
+pub fn batch_transfer_from(from: AccountId, to: AccountId, token_ids: Vec<Hash>) -> Call {
+    NFT::<NodeRuntimeTypes>::batch_transfer_from(from, to, token_ids).into()
+}
+
+/// Construct a `NFT::batch_approve` call
+pub fn batch_approve(to: AccountId, token_ids: Vec<Hash>) -> Call {
+    NFT::<NodeRuntimeTypes>::batch_approve(to, token_ids).into()
+}
+
+/// Construct a `NFT::batch_burn` call
+pub fn batch_burn(token_ids: Vec<Hash>) -> Call {
+    NFT::<NodeRuntimeTypes>::batch_burn(token_ids).into()
+}
+
+/// Construct a `NFT::set_registry_royalty` call
+pub fn set_registry_royalty(uid: u64, recipient: AccountId, rate: BasisPoints) -> Call {
+    NFT::<NodeRuntimeTypes>::set_registry_royalty(uid, recipient, rate).into()
+}
+
+/// Construct a `NFT::set_token_royalty` call
+pub fn set_token_royalty(token_id: Hash, recipient: AccountId, rate: BasisPoints) -> Call {
+    NFT::<NodeRuntimeTypes>::set_token_royalty(token_id, recipient, rate).into()
+}
+
+/// Construct a `NFT::transfer_with_payment` call
+pub fn transfer_with_payment(
+    from: AccountId,
+    to: AccountId,
+    token_id: Hash,
+    price: Balance,
+) -> Call {
+    NFT::<NodeRuntimeTypes>::transfer_with_payment(from, to, token_id, price).into()
+}
+
+/// Construct a `NFT::propose_swap` call
+pub fn propose_swap(
+    offer_tokens: Vec<Hash>,
+    offer_amount: Balance,
+    want_tokens: Vec<Hash>,
+    want_amount: Balance,
+    counterparty: AccountId,
+    expiry: BlockNumber,
+) -> Call {
+    NFT::<NodeRuntimeTypes>::propose_swap(
+        offer_tokens,
+        offer_amount,
+        want_tokens,
+        want_amount,
+        counterparty,
+        expiry,
+    )
+    .into()
+}
+
+/// Construct a `NFT::accept_swap` call
+pub fn accept_swap(swap_id: u64) -> Call {
+    NFT::<NodeRuntimeTypes>::accept_swap(swap_id).into()
+}
+
+/// Construct a `NFT::cancel_swap` call
+pub fn cancel_swap(swap_id: u64) -> Call {
+    NFT::<NodeRuntimeTypes>::cancel_swap(swap_id).into()
+}
+
+/// Construct a `NFT::set_sponsor_allowance` call
+pub fn set_sponsor_allowance(user: AccountId, mints: u32, amount: Balance) -> Call {
+    NFT::<NodeRuntimeTypes>::set_sponsor_allowance(user, mints, amount).into()
+}
+
+/// Construct a `NFT::set_co_owners` call
+pub fn set_co_owners(token_id: Hash, owners: Vec<AccountId>, threshold: u32) -> Call {
+    NFT::<NodeRuntimeTypes>::set_co_owners(token_id, owners, threshold).into()
+}
+
+/// Construct a `NFT::propose_action` call
+pub fn propose_action(
+    token_id: Hash,
+    action: CoOwnerAction<AccountId>,
+    expiry: BlockNumber,
+) -> Call {
+    NFT::<NodeRuntimeTypes>::propose_action(token_id, action, expiry).into()
+}
+
+/// Construct a `NFT::confirm_proposal` call
+pub fn confirm_proposal(proposal_id: u64) -> Call {
+    NFT::<NodeRuntimeTypes>::confirm_proposal(proposal_id).into()
+}
+
+/// Construct a `NFT::cancel_proposal` call
+pub fn cancel_proposal(proposal_id: u64) -> Call {
+    NFT::<NodeRuntimeTypes>::cancel_proposal(proposal_id).into()
+}
+
+/// Construct a `ERC721::mint` call
+pub fn erc721_mint(collection_id: CollectionId, token_id: Hash) -> Call {
+    ERC721::<NodeRuntimeTypes>::mint(collection_id, token_id).into()
+}
+
+/// Construct a `ERC721::burn` call
+pub fn erc721_burn(collection_id: CollectionId, token_id: Hash) -> Call {
+    ERC721::<NodeRuntimeTypes>::burn(collection_id, token_id).into()
+}
+
+/// Construct a `ERC721::approve` call
+pub fn erc721_approve(collection_id: CollectionId, to: AccountId, token_id: Hash) -> Call {
+    ERC721::<NodeRuntimeTypes>::approve(collection_id, to, token_id).into()
+}
+
+/// Construct a `ERC721::set_approval_for_all` call
+pub fn erc721_set_approval_for_all(
+    collection_id: CollectionId,
+    to: AccountId,
+    approved: bool,
+) -> Call {
+    ERC721::<NodeRuntimeTypes>::set_approval_for_all(collection_id, to, approved).into()
+}
+
+/// Construct a `ERC721::transfer_from` call
+pub fn erc721_transfer_from(
+    collection_id: CollectionId,
+    from: AccountId,
+    to: AccountId,
+    token_id: Hash,
+) -> Call {
+    ERC721::<NodeRuntimeTypes>::transfer_from(collection_id, from, to, token_id).into()
+}
+
+/// Construct a `ERC721::create_token` call
+pub fn erc721_create_token(collection_id: CollectionId) -> Call {
+    ERC721::<NodeRuntimeTypes>::create_token(collection_id).into()
+}
+
+/// Construct a `ERC721::set_token_uri` call
+pub fn erc721_set_token_uri(collection_id: CollectionId, token_id: Hash, uri: Vec<u8>) -> Call {
+    ERC721::<NodeRuntimeTypes>::set_token_uri(collection_id, token_id, uri).into()
+}
+
+/// Construct a `ERC721::create_collection` call
+pub fn erc721_create_collection() -> Call {
+    ERC721::<NodeRuntimeTypes>::create_collection().into()
+}
+
+/// Construct a `ERC721::destroy_collection` call
+pub fn erc721_destroy_collection(collection_id: CollectionId) -> Call {
+    ERC721::<NodeRuntimeTypes>::destroy_collection(collection_id).into()
+}
+
+/// Construct a `ERC721::batch_transfer_from` call
+pub fn erc721_batch_transfer_from(
+    collection_id: CollectionId,
+    from: AccountId,
+    to: AccountId,
+    token_ids: Vec<Hash>,
+) -> Call {
+    ERC721::<NodeRuntimeTypes>::batch_transfer_from(collection_id, from, to, token_ids).into()
+}
+
+/// Construct a `ERC721::batch_approve` call
+pub fn erc721_batch_approve(
+    collection_id: CollectionId,
+    to: AccountId,
+    token_ids: Vec<Hash>,
+) -> Call {
+    ERC721::<NodeRuntimeTypes>::batch_approve(collection_id, to, token_ids).into()
+}
+
+/// Construct a `ERC721::batch_burn` call
+pub fn erc721_batch_burn(collection_id: CollectionId, token_ids: Vec<Hash>) -> Call {
+    ERC721::<NodeRuntimeTypes>::batch_burn(collection_id, token_ids).into()
+}
+
+/// Construct a `ERC721::safe_transfer_from` call
+pub fn erc721_safe_transfer_from(
+    collection_id: CollectionId,
+    from: AccountId,
+    to: AccountId,
+    token_id: Hash,
+    data: Vec<u8>,
+) -> Call {
+    ERC721::<NodeRuntimeTypes>::safe_transfer_from(collection_id, from, to, token_id, data).into()
+}
+
+/// Construct a `ERC721::approve_with_limit` call
+pub fn erc721_approve_with_limit(
+    collection_id: CollectionId,
+    to: AccountId,
+    token_id: Hash,
+    expiry: Option<BlockNumber>,
+    uses: Option<u32>,
+) -> Call {
+    ERC721::<NodeRuntimeTypes>::approve_with_limit(collection_id, to, token_id, expiry, uses).into()
+}
+
+/// Construct a `ERC721::set_approval_for_all_with_limit` call
+pub fn erc721_set_approval_for_all_with_limit(
+    collection_id: CollectionId,
+    to: AccountId,
+    expiry: Option<BlockNumber>,
+    uses: Option<u32>,
+) -> Call {
+    ERC721::<NodeRuntimeTypes>::set_approval_for_all_with_limit(collection_id, to, expiry, uses)
+        .into()
+}
+
+/// Construct a `ERC721::permit` call
+pub fn erc721_permit(
+    collection_id: CollectionId,
+    owner: AccountId,
+    spender: AccountId,
+    token_id: Hash,
+    deadline: BlockNumber,
+    nonce: u64,
+    signature: Signature,
+) -> Call {
+    ERC721::<NodeRuntimeTypes>::permit(
+        collection_id,
+        owner,
+        spender,
+        token_id,
+        deadline,
+        nonce,
+        signature,
+    )
+    .into()
+}
+
+/// Construct a `ERC721::set_user` call
+pub fn erc721_set_user(
+    collection_id: CollectionId,
+    token_id: Hash,
+    user: AccountId,
+    expires: BlockNumber,
+) -> Call {
+    ERC721::<NodeRuntimeTypes>::set_user(collection_id, token_id, user, expires).into()
+}
+
+/// Construct a `ERC721::nest` call
+pub fn erc721_nest(collection_id: CollectionId, child: Hash, parent: Hash) -> Call {
+    ERC721::<NodeRuntimeTypes>::nest(collection_id, child, parent).into()
+}
+
+/// Construct a `ERC721::unnest` call
+pub fn erc721_unnest(collection_id: CollectionId, child: Hash) -> Call {
+    ERC721::<NodeRuntimeTypes>::unnest(collection_id, child).into()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Call;
-    use crate::{calls, AccountIndex, NodeRuntimeTypes};
+    use super::{indices, Call};
+    use crate::{calls, AccountId, AccountIndex, Hash, NodeRuntimeTypes, Signature};
 
+    use nft_test_runtime::{self as test_runtime, CoOwnerAction, ERC721Call, NftRegistryCall};
     use node_runtime::{self, Runtime};
     use pallet_indices::address;
     use scale::{Decode, Encode};
+    use sp_core::{crypto::AccountId32, sr25519, H256};
+
+    // Check a call built by contract has the same encoding as the test runtime call,
+    // and both can be decoded to each other
+    fn assert_encoding_equivalence(contract_call: Call, runtime_call: test_runtime::Call) {
+        let contract_call_encoded = contract_call.encode();
+        let runtime_call_encoded = runtime_call.encode();
+
+        assert_eq!(runtime_call_encoded, contract_call_encoded);
+
+        let runtime_call_decoded: test_runtime::Call =
+            Decode::decode(&mut contract_call_encoded.as_slice())
+                .expect("Contract call decodes to runtime type");
+        let runtime_call_encoded = runtime_call_decoded.encode();
+        let contract_call_decoded: Call = Decode::decode(&mut runtime_call_encoded.as_slice())
+            .expect("Runtime call decodes back to contract type");
+        assert!(contract_call == contract_call_decoded);
+    }
+
+    fn accounts(byte: u8) -> (AccountId, AccountId32) {
+        let account: AccountId32 = [byte; 32].into();
+        (account.clone().into(), account)
+    }
+
+    fn token_ids(byte: u8) -> (Hash, H256) {
+        ([byte; 32].into(), [byte; 32].into())
+    }
+
+    fn batch_token_ids() -> (Vec<Hash>, Vec<H256>) {
+        (1..4).map(token_ids).unzip()
+    }
+
+    const COLLECTION_ID: u32 = 7;
+
+    #[test]
+    fn module_indices_match_test_runtime() {
+        let (_, runtime_account) = accounts(1);
+        let (_, runtime_token_id) = token_ids(2);
+
+        let balances_call = test_runtime::Call::Balances(test_runtime::BalancesCall::transfer(
+            runtime_account.clone(),
+            10_000,
+        ));
+        let nft_call = test_runtime::Call::NftRegistry(NftRegistryCall::burn(runtime_token_id));
+        let erc721_call =
+            test_runtime::Call::ERC721(ERC721Call::burn(COLLECTION_ID, runtime_token_id));
+
+        assert_eq!(test_runtime::module_index(&balances_call), indices::BALANCES);
+        assert_eq!(test_runtime::module_index(&nft_call), indices::NFT);
+        assert_eq!(test_runtime::module_index(&erc721_call), indices::ERC721);
+    }
 
     #[test]
     fn call_balance_transfer() {
@@ -123,4 +665,610 @@ mod tests {
             .expect("Balances transfer call decodes back to contract type");
         assert!(contract_call == contract_call_decoded);
     }
+
+    #[test]
+    fn call_nft_new_registry() {
+        let (contract_account, runtime_account) = accounts(1);
+
+        assert_encoding_equivalence(
+            calls::new_registry(contract_account),
+            test_runtime::Call::NftRegistry(NftRegistryCall::new_registry(runtime_account)),
+        );
+    }
+
+    #[test]
+    fn call_nft_finish_mint() {
+        let (contract_account, runtime_account) = accounts(1);
+        let (contract_token_id, runtime_token_id) = token_ids(2);
+        let metadata = vec![b'x'; 10];
+
+        assert_encoding_equivalence(
+            calls::finish_mint(1, contract_token_id, contract_account, metadata.clone()),
+            test_runtime::Call::NftRegistry(NftRegistryCall::finish_mint(
+                1,
+                runtime_token_id,
+                runtime_account,
+                metadata,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_nft_transfer_from() {
+        let (contract_from, runtime_from) = accounts(1);
+        let (contract_to, runtime_to) = accounts(2);
+        let (contract_token_id, runtime_token_id) = token_ids(3);
+
+        assert_encoding_equivalence(
+            calls::transfer_from(contract_from, contract_to, contract_token_id),
+            test_runtime::Call::NftRegistry(NftRegistryCall::transfer_from(
+                runtime_from,
+                runtime_to,
+                runtime_token_id,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_nft_burn() {
+        let (contract_token_id, runtime_token_id) = token_ids(1);
+
+        assert_encoding_equivalence(
+            calls::burn(contract_token_id),
+            test_runtime::Call::NftRegistry(NftRegistryCall::burn(runtime_token_id)),
+        );
+    }
+
+    #[test]
+    fn call_nft_approve() {
+        let (contract_account, runtime_account) = accounts(1);
+        let (contract_token_id, runtime_token_id) = token_ids(2);
+
+        assert_encoding_equivalence(
+            calls::approve(contract_account, contract_token_id),
+            test_runtime::Call::NftRegistry(NftRegistryCall::approve(
+                runtime_account,
+                runtime_token_id,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_nft_set_approval_for_all() {
+        let (contract_account, runtime_account) = accounts(1);
+
+        assert_encoding_equivalence(
+            calls::set_approval_for_all(contract_account, true),
+            test_runtime::Call::NftRegistry(NftRegistryCall::set_approval_for_all(
+                runtime_account,
+                true,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_nft_new_fungible_registry() {
+        let (contract_account, runtime_account) = accounts(1);
+
+        assert_encoding_equivalence(
+            calls::new_fungible_registry(contract_account, 100),
+            test_runtime::Call::NftRegistry(NftRegistryCall::new_fungible_registry(
+                runtime_account,
+                100,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_erc721_mint() {
+        let (contract_token_id, runtime_token_id) = token_ids(1);
+
+        assert_encoding_equivalence(
+            calls::erc721_mint(COLLECTION_ID, contract_token_id),
+            test_runtime::Call::ERC721(ERC721Call::mint(COLLECTION_ID, runtime_token_id)),
+        );
+    }
+
+    #[test]
+    fn call_erc721_burn() {
+        let (contract_token_id, runtime_token_id) = token_ids(1);
+
+        assert_encoding_equivalence(
+            calls::erc721_burn(COLLECTION_ID, contract_token_id),
+            test_runtime::Call::ERC721(ERC721Call::burn(COLLECTION_ID, runtime_token_id)),
+        );
+    }
+
+    #[test]
+    fn call_erc721_approve() {
+        let (contract_account, runtime_account) = accounts(1);
+        let (contract_token_id, runtime_token_id) = token_ids(2);
+
+        assert_encoding_equivalence(
+            calls::erc721_approve(COLLECTION_ID, contract_account, contract_token_id),
+            test_runtime::Call::ERC721(ERC721Call::approve(
+                COLLECTION_ID,
+                runtime_account,
+                runtime_token_id,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_erc721_set_approval_for_all() {
+        let (contract_account, runtime_account) = accounts(1);
+
+        assert_encoding_equivalence(
+            calls::erc721_set_approval_for_all(COLLECTION_ID, contract_account, false),
+            test_runtime::Call::ERC721(ERC721Call::set_approval_for_all(
+                COLLECTION_ID,
+                runtime_account,
+                false,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_erc721_transfer_from() {
+        let (contract_from, runtime_from) = accounts(1);
+        let (contract_to, runtime_to) = accounts(2);
+        let (contract_token_id, runtime_token_id) = token_ids(3);
+
+        assert_encoding_equivalence(
+            calls::erc721_transfer_from(
+                COLLECTION_ID,
+                contract_from,
+                contract_to,
+                contract_token_id,
+            ),
+            test_runtime::Call::ERC721(ERC721Call::transfer_from(
+                COLLECTION_ID,
+                runtime_from,
+                runtime_to,
+                runtime_token_id,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_erc721_create_token() {
+        assert_encoding_equivalence(
+            calls::erc721_create_token(COLLECTION_ID),
+            test_runtime::Call::ERC721(ERC721Call::create_token(COLLECTION_ID)),
+        );
+    }
+
+    #[test]
+    fn call_erc721_set_token_uri() {
+        let (contract_token_id, runtime_token_id) = token_ids(1);
+
+        assert_encoding_equivalence(
+            calls::erc721_set_token_uri(COLLECTION_ID, contract_token_id, b"1.json".to_vec()),
+            test_runtime::Call::ERC721(ERC721Call::set_token_uri(
+                COLLECTION_ID,
+                runtime_token_id,
+                b"1.json".to_vec(),
+            )),
+        );
+    }
+
+    #[test]
+    fn call_erc721_create_collection() {
+        assert_encoding_equivalence(
+            calls::erc721_create_collection(),
+            test_runtime::Call::ERC721(ERC721Call::create_collection()),
+        );
+    }
+
+    #[test]
+    fn call_erc721_destroy_collection() {
+        assert_encoding_equivalence(
+            calls::erc721_destroy_collection(COLLECTION_ID),
+            test_runtime::Call::ERC721(ERC721Call::destroy_collection(COLLECTION_ID)),
+        );
+    }
+
+    #[test]
+    fn call_nft_batch_transfer_from() {
+        let (from_contract, from_runtime) = accounts(1);
+        let (to_contract, to_runtime) = accounts(2);
+        let (contract_token_ids, runtime_token_ids) = batch_token_ids();
+
+        assert_encoding_equivalence(
+            calls::batch_transfer_from(from_contract, to_contract, contract_token_ids),
+            test_runtime::Call::NftRegistry(NftRegistryCall::batch_transfer_from(
+                from_runtime,
+                to_runtime,
+                runtime_token_ids,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_nft_batch_approve() {
+        let (contract_account, runtime_account) = accounts(1);
+        let (contract_token_ids, runtime_token_ids) = batch_token_ids();
+
+        assert_encoding_equivalence(
+            calls::batch_approve(contract_account, contract_token_ids),
+            test_runtime::Call::NftRegistry(NftRegistryCall::batch_approve(
+                runtime_account,
+                runtime_token_ids,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_nft_batch_burn() {
+        let (contract_token_ids, runtime_token_ids) = batch_token_ids();
+
+        assert_encoding_equivalence(
+            calls::batch_burn(contract_token_ids),
+            test_runtime::Call::NftRegistry(NftRegistryCall::batch_burn(runtime_token_ids)),
+        );
+    }
+
+    #[test]
+    fn call_nft_set_registry_royalty() {
+        let (contract_account, runtime_account) = accounts(1);
+
+        assert_encoding_equivalence(
+            calls::set_registry_royalty(1, contract_account, 250),
+            test_runtime::Call::NftRegistry(NftRegistryCall::set_registry_royalty(
+                1,
+                runtime_account,
+                250,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_nft_set_token_royalty() {
+        let (contract_account, runtime_account) = accounts(1);
+        let (contract_token_id, runtime_token_id) = token_ids(2);
+
+        assert_encoding_equivalence(
+            calls::set_token_royalty(contract_token_id, contract_account, 250),
+            test_runtime::Call::NftRegistry(NftRegistryCall::set_token_royalty(
+                runtime_token_id,
+                runtime_account,
+                250,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_nft_transfer_with_payment() {
+        let (contract_from, runtime_from) = accounts(1);
+        let (contract_to, runtime_to) = accounts(2);
+        let (contract_token_id, runtime_token_id) = token_ids(3);
+
+        assert_encoding_equivalence(
+            calls::transfer_with_payment(contract_from, contract_to, contract_token_id, 10_000),
+            test_runtime::Call::NftRegistry(NftRegistryCall::transfer_with_payment(
+                runtime_from,
+                runtime_to,
+                runtime_token_id,
+                10_000,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_nft_propose_swap() {
+        let (contract_account, runtime_account) = accounts(1);
+        let (contract_token_ids, runtime_token_ids) = batch_token_ids();
+        let (contract_token_id, runtime_token_id) = token_ids(4);
+
+        assert_encoding_equivalence(
+            calls::propose_swap(
+                contract_token_ids,
+                100,
+                vec![contract_token_id],
+                0,
+                contract_account,
+                10,
+            ),
+            test_runtime::Call::NftRegistry(NftRegistryCall::propose_swap(
+                runtime_token_ids,
+                100,
+                vec![runtime_token_id],
+                0,
+                runtime_account,
+                10,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_nft_accept_swap() {
+        assert_encoding_equivalence(
+            calls::accept_swap(1),
+            test_runtime::Call::NftRegistry(NftRegistryCall::accept_swap(1)),
+        );
+    }
+
+    #[test]
+    fn call_nft_cancel_swap() {
+        assert_encoding_equivalence(
+            calls::cancel_swap(1),
+            test_runtime::Call::NftRegistry(NftRegistryCall::cancel_swap(1)),
+        );
+    }
+
+    #[test]
+    fn call_nft_set_co_owners() {
+        let (contract_token_id, runtime_token_id) = token_ids(1);
+        let (contract_alice, runtime_alice) = accounts(1);
+        let (contract_bob, runtime_bob) = accounts(2);
+
+        assert_encoding_equivalence(
+            calls::set_co_owners(contract_token_id, vec![contract_alice, contract_bob], 2),
+            test_runtime::Call::NftRegistry(NftRegistryCall::set_co_owners(
+                runtime_token_id,
+                vec![runtime_alice, runtime_bob],
+                2,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_nft_propose_action() {
+        let (contract_token_id, runtime_token_id) = token_ids(1);
+        let (contract_account, runtime_account) = accounts(1);
+
+        assert_encoding_equivalence(
+            calls::propose_action(
+                contract_token_id,
+                calls::CoOwnerAction::TransferFrom(contract_account),
+                10,
+            ),
+            test_runtime::Call::NftRegistry(NftRegistryCall::propose_action(
+                runtime_token_id,
+                CoOwnerAction::TransferFrom(runtime_account),
+                10,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_nft_propose_burn_and_approve() {
+        let (contract_token_id, runtime_token_id) = token_ids(1);
+        let (contract_account, runtime_account) = accounts(1);
+
+        assert_encoding_equivalence(
+            calls::propose_action(contract_token_id, calls::CoOwnerAction::Burn, 10),
+            test_runtime::Call::NftRegistry(NftRegistryCall::propose_action(
+                runtime_token_id,
+                CoOwnerAction::Burn,
+                10,
+            )),
+        );
+        assert_encoding_equivalence(
+            calls::propose_action(
+                contract_token_id,
+                calls::CoOwnerAction::Approve(contract_account),
+                10,
+            ),
+            test_runtime::Call::NftRegistry(NftRegistryCall::propose_action(
+                runtime_token_id,
+                CoOwnerAction::Approve(runtime_account),
+                10,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_nft_confirm_proposal() {
+        assert_encoding_equivalence(
+            calls::confirm_proposal(1),
+            test_runtime::Call::NftRegistry(NftRegistryCall::confirm_proposal(1)),
+        );
+    }
+
+    #[test]
+    fn call_nft_cancel_proposal() {
+        assert_encoding_equivalence(
+            calls::cancel_proposal(1),
+            test_runtime::Call::NftRegistry(NftRegistryCall::cancel_proposal(1)),
+        );
+    }
+
+    #[test]
+    fn call_nft_set_sponsor_allowance() {
+        let (contract_account, runtime_account) = accounts(1);
+
+        assert_encoding_equivalence(
+            calls::set_sponsor_allowance(contract_account, 3, 10_000),
+            test_runtime::Call::NftRegistry(NftRegistryCall::set_sponsor_allowance(
+                runtime_account,
+                3,
+                10_000,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_erc721_batch_transfer_from() {
+        let (from_contract, from_runtime) = accounts(1);
+        let (to_contract, to_runtime) = accounts(2);
+        let (contract_token_ids, runtime_token_ids) = batch_token_ids();
+
+        assert_encoding_equivalence(
+            calls::erc721_batch_transfer_from(
+                COLLECTION_ID,
+                from_contract,
+                to_contract,
+                contract_token_ids,
+            ),
+            test_runtime::Call::ERC721(ERC721Call::batch_transfer_from(
+                COLLECTION_ID,
+                from_runtime,
+                to_runtime,
+                runtime_token_ids,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_erc721_batch_approve() {
+        let (contract_account, runtime_account) = accounts(1);
+        let (contract_token_ids, runtime_token_ids) = batch_token_ids();
+
+        assert_encoding_equivalence(
+            calls::erc721_batch_approve(COLLECTION_ID, contract_account, contract_token_ids),
+            test_runtime::Call::ERC721(ERC721Call::batch_approve(
+                COLLECTION_ID,
+                runtime_account,
+                runtime_token_ids,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_erc721_batch_burn() {
+        let (contract_token_ids, runtime_token_ids) = batch_token_ids();
+
+        assert_encoding_equivalence(
+            calls::erc721_batch_burn(COLLECTION_ID, contract_token_ids),
+            test_runtime::Call::ERC721(ERC721Call::batch_burn(COLLECTION_ID, runtime_token_ids)),
+        );
+    }
+
+    #[test]
+    fn call_erc721_safe_transfer_from() {
+        let (from_contract, from_runtime) = accounts(1);
+        let (to_contract, to_runtime) = accounts(2);
+        let (contract_token_id, runtime_token_id) = token_ids(1);
+
+        assert_encoding_equivalence(
+            calls::erc721_safe_transfer_from(
+                COLLECTION_ID,
+                from_contract,
+                to_contract,
+                contract_token_id,
+                b"data".to_vec(),
+            ),
+            test_runtime::Call::ERC721(ERC721Call::safe_transfer_from(
+                COLLECTION_ID,
+                from_runtime,
+                to_runtime,
+                runtime_token_id,
+                b"data".to_vec(),
+            )),
+        );
+    }
+
+    #[test]
+    fn call_erc721_approve_with_limit() {
+        let (contract_account, runtime_account) = accounts(1);
+        let (contract_token_id, runtime_token_id) = token_ids(1);
+
+        assert_encoding_equivalence(
+            calls::erc721_approve_with_limit(
+                COLLECTION_ID,
+                contract_account,
+                contract_token_id,
+                Some(10),
+                None,
+            ),
+            test_runtime::Call::ERC721(ERC721Call::approve_with_limit(
+                COLLECTION_ID,
+                runtime_account,
+                runtime_token_id,
+                Some(10),
+                None,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_erc721_set_approval_for_all_with_limit() {
+        let (contract_account, runtime_account) = accounts(1);
+
+        assert_encoding_equivalence(
+            calls::erc721_set_approval_for_all_with_limit(
+                COLLECTION_ID,
+                contract_account,
+                None,
+                Some(3),
+            ),
+            test_runtime::Call::ERC721(ERC721Call::set_approval_for_all_with_limit(
+                COLLECTION_ID,
+                runtime_account,
+                None,
+                Some(3),
+            )),
+        );
+    }
+
+    #[test]
+    fn call_erc721_permit() {
+        let (owner_contract, owner_runtime) = accounts(1);
+        let (spender_contract, spender_runtime) = accounts(2);
+        let (contract_token_id, runtime_token_id) = token_ids(1);
+        let signature = Signature::Sr25519(sr25519::Signature::from_raw([7; 64]));
+
+        assert_encoding_equivalence(
+            calls::erc721_permit(
+                COLLECTION_ID,
+                owner_contract,
+                spender_contract,
+                contract_token_id,
+                10,
+                0,
+                signature.clone(),
+            ),
+            test_runtime::Call::ERC721(ERC721Call::permit(
+                COLLECTION_ID,
+                owner_runtime,
+                spender_runtime,
+                runtime_token_id,
+                10,
+                0,
+                signature,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_erc721_set_user() {
+        let (user_contract, user_runtime) = accounts(1);
+        let (contract_token_id, runtime_token_id) = token_ids(1);
+
+        assert_encoding_equivalence(
+            calls::erc721_set_user(COLLECTION_ID, contract_token_id, user_contract, 100),
+            test_runtime::Call::ERC721(ERC721Call::set_user(
+                COLLECTION_ID,
+                runtime_token_id,
+                user_runtime,
+                100,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_erc721_nest() {
+        let (contract_child, runtime_child) = token_ids(1);
+        let (contract_parent, runtime_parent) = token_ids(2);
+
+        assert_encoding_equivalence(
+            calls::erc721_nest(COLLECTION_ID, contract_child, contract_parent),
+            test_runtime::Call::ERC721(ERC721Call::nest(
+                COLLECTION_ID,
+                runtime_child,
+                runtime_parent,
+            )),
+        );
+    }
+
+    #[test]
+    fn call_erc721_unnest() {
+        let (contract_child, runtime_child) = token_ids(1);
+
+        assert_encoding_equivalence(
+            calls::erc721_unnest(COLLECTION_ID, contract_child),
+            test_runtime::Call::ERC721(ERC721Call::unnest(COLLECTION_ID, runtime_child)),
+        );
+    }
 }
@@ -90,6 +90,18 @@ pub type BlockNumber = u64;
 /// The default SRML AccountIndex type.
 pub type AccountIndex = u32;
 
+/// The ERC721 CollectionId type of the runtime.
+pub type CollectionId = u32;
+
+/// The ERC1155 TokenBalance type of the runtime.
+pub type TokenBalance = u128;
+
+/// The NFT registry royalty rate type of the runtime, in basis points.
+pub type BasisPoints = u16;
+
+/// The signature type of ERC721 permits, the same with extrinsics of the runtime.
+pub type Signature = sp_runtime::MultiSignature;
+
 /// The default timestamp type.
 pub type Timestamp = u64;
 
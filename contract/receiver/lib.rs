@@ -0,0 +1,131 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract(version = "0.1.0", env = NodeRuntimeTypes)]
+
+mod receiver {
+    use ink_core::env;
+    use ink_core::storage;
+    use ink_prelude::vec::Vec;
+    use ink_prelude::*;
+    use ink_types_node_runtime::{CollectionId, NodeRuntimeTypes};
+
+    /// Value returned to accept a token, must keep the same value as
+    /// `ERC721_RECEIVED` in the ERC721 pallet.
+    const ERC721_RECEIVED: [u8; 4] = [0x15, 0x0b, 0x7a, 0x02];
+
+    /// Example contract receiving tokens sent by `safe_transfer_from` of the
+    /// ERC721 pallet. The owner decides if the contract accepts tokens.
+    #[ink(storage)]
+    struct Receiver {
+        /// Account instantiated the contract, the only one can change settings
+        owner: storage::Value<AccountId>,
+
+        /// If tokens are accepted
+        accepting: storage::Value<bool>,
+
+        /// Count of tokens accepted
+        received: storage::Value<u32>,
+    }
+
+    impl Receiver {
+        #[ink(constructor)]
+        fn new(&mut self) {
+            self.owner.set(self.env().caller());
+            self.accepting.set(true);
+            self.received.set(0);
+        }
+
+        /// Called by the ERC721 pallet after a token is transferred to the contract.
+        /// Returns `ERC721_RECEIVED` to accept the token, otherwise the transfer is reverted.
+        #[ink(message)]
+        fn on_erc721_received(
+            &mut self,
+            collection_id: CollectionId,
+            operator: AccountId,
+            from: AccountId,
+            token_id: Hash,
+            data: Vec<u8>,
+        ) -> [u8; 4] {
+            if !*self.accepting {
+                env::println(&format!(
+                    "token {:?} of collection {} rejected",
+                    token_id, collection_id
+                ));
+                return [0; 4];
+            }
+
+            // report result to console
+            // NOTE: println should only be used on a development chain
+            env::println(&format!(
+                "token {:?} of collection {} received from {:?} by {:?} with {} bytes of data",
+                token_id,
+                collection_id,
+                from,
+                operator,
+                data.len()
+            ));
+
+            *self.received += 1;
+
+            ERC721_RECEIVED
+        }
+
+        /// Sets if tokens are accepted
+        #[ink(message)]
+        fn set_accepting(&mut self, accepting: bool) -> bool {
+            if self.env().caller() != *self.owner {
+                return false;
+            }
+
+            self.accepting.set(accepting);
+
+            true
+        }
+
+        /// Returns count of tokens accepted
+        #[ink(message)]
+        fn received(&self) -> u32 {
+            *self.received
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use sp_core::crypto::AccountId32;
+
+        fn account(byte: u8) -> AccountId {
+            let account: AccountId32 = [byte; 32].into();
+            account.into()
+        }
+
+        fn token_id(byte: u8) -> Hash {
+            [byte; 32].into()
+        }
+
+        #[test]
+        fn accepts_tokens() {
+            let mut receiver = Receiver::new();
+
+            assert_eq!(
+                receiver.on_erc721_received(0, account(1), account(1), token_id(1), vec![]),
+                ERC721_RECEIVED
+            );
+            assert_eq!(receiver.received(), 1);
+        }
+
+        #[test]
+        fn rejects_tokens_when_not_accepting() {
+            let mut receiver = Receiver::new();
+            assert!(receiver.set_accepting(false));
+
+            assert_ne!(
+                receiver.on_erc721_received(0, account(1), account(1), token_id(1), vec![]),
+                ERC721_RECEIVED
+            );
+            assert_eq!(receiver.received(), 0);
+        }
+    }
+}
@@ -12,64 +12,264 @@ mod calls {
     use ink_types_node_runtime::{calls as runtime_calls, NodeRuntimeTypes};
     use scale::{Decode, Encode};
 
+    /// Parameters sent by the NFT registry pallet, must keep the same layout as
+    /// `ContractParameter` in the pallet.
     #[derive(Encode, Decode)]
     struct ContractParameter<Hash, AccountId> {
         uid: u64,
         token_id: Hash,
         token_owner: AccountId,
         metadata: Vec<u8>,
+        // Leaves of the proofs the pallet verified against the anchored document
         proof_leaves: Vec<Hash>,
+        /// User the token is minted with, the account holding its usage rights
+        token_user: Option<AccountId>,
+        /// Tokens the pallet minted to the token owner in this registry
+        owner_minted: u32,
     }
 
-    #[derive(Encode, Decode)]
-    struct StaticProof<Hash> {
-        basic_data_root: Hash,
-        zk_data_root: Hash,
-        signature_root: Hash,
-    }
+    /// Reasons why a mint request is rejected by the contract
+    #[derive(Debug, PartialEq, Eq)]
+    enum Rejection {
+        // Parameters can not be decoded
+        InvalidParameters,
 
-    #[derive(Encode, Decode)]
-    pub struct Proof {
-        leaf_hash: Hash,
-        sorted_hashes: Vec<Hash>,
+        // A required property leaf is not in the proofs
+        MissingRequiredLeaf,
+
+        // Token owner is not in the allowed list
+        TokenOwnerNotAllowed,
+
+        // Metadata is empty or longer than the maximum
+        InvalidMetadataLength,
+
+        // Mint deadline block already passed
+        MintDeadlinePassed,
+
+        // Token owner already minted as many tokens as the quota
+        MintQuotaExceeded,
     }
 
-    /// This simple dummy contract dispatches substrate runtime calls
+    /// Reference validation contract, checks the mint parameters against the
+    /// rule set stored in contract state and then dispatches `finish_mint`.
+    /// Rules are configured by the contract owner, then the same contract code
+    /// can be instantiated for many registries.
     #[ink(storage)]
     struct Calls {
-        value: storage::Value<bool>,
+        /// Account instantiated the contract, the only one can change rules
+        owner: storage::Value<AccountId>,
+
+        /// Property leaves must be proved in each mint
+        required_leaves: storage::Vec<Hash>,
+
+        /// Accounts allowed to own the minted token, empty means anyone
+        allowed_owners: storage::HashMap<AccountId, bool>,
+
+        /// Count of allowed owners
+        allowed_owners_count: storage::Value<u32>,
+
+        /// Maximum length of token metadata, None means no limit
+        max_metadata_length: storage::Value<Option<u32>>,
+
+        /// Last block a token can be minted, None means no deadline
+        mint_deadline: storage::Value<Option<BlockNumber>>,
+
+        /// Maximum tokens minted per token owner in a registry, None means no quota.
+        /// Counted by the pallet, so only mints finished by `finish_mint` count.
+        mint_quota: storage::Value<Option<u32>>,
     }
 
     impl Calls {
         #[ink(constructor)]
-        fn new(&mut self) {}
+        fn new(&mut self) {
+            self.owner.set(self.env().caller());
+            self.allowed_owners_count.set(0);
+            self.max_metadata_length.set(None);
+            self.mint_deadline.set(None);
+            self.mint_quota.set(None);
+        }
+
+        /// Validates the parameters and dispatches `finish_mint` to the NFT module
+        /// if all rules pass. Returns if `finish_mint` was queued, it runs after the
+        /// contract returns and the mint can still fail in the pallet.
+        #[ink(message)]
+        fn validate(&mut self, parameters: Vec<u8>) -> bool {
+            let decoded = match ContractParameter::<Hash, AccountId>::decode(&mut &parameters[..]) {
+                Ok(decoded) => decoded,
+                Err(_) => {
+                    env::println(&format!("mint rejected {:?}", Rejection::InvalidParameters));
+                    return false;
+                }
+            };
+
+            if let Err(rejection) = self.check(&decoded, self.env().block_number()) {
+                env::println(&format!("mint rejected {:?}", rejection));
+                return false;
+            }
+
+            let mint_call = runtime_calls::finish_mint(
+                decoded.uid,
+                decoded.token_id,
+                decoded.token_owner,
+                decoded.metadata,
+            );
+
+            // dispatch the call to the runtime
+            let result = self.env().invoke_runtime(&mint_call);
+
+            // report result to console
+            // NOTE: println should only be used on a development chain
+            env::println(&format!("Finish mint invoke_runtime result {:?}", result));
+
+            result.is_ok()
+        }
+
+        /// Returns the account instantiated the contract
+        #[ink(message)]
+        fn owner(&self) -> AccountId {
+            self.owner.get().clone()
+        }
+
+        /// Requires a property leaf to be proved in each mint
+        #[ink(message)]
+        fn add_required_leaf(&mut self, leaf: Hash) -> bool {
+            if !self.is_owner() {
+                return false;
+            }
+
+            if !self.required_leaves.iter().any(|required| *required == leaf) {
+                self.required_leaves.push(leaf);
+            }
+
+            true
+        }
+
+        /// Removes a required property leaf
+        #[ink(message)]
+        fn remove_required_leaf(&mut self, leaf: Hash) -> bool {
+            if !self.is_owner() {
+                return false;
+            }
+
+            if let Some(index) = self.required_leaves.iter().position(|required| *required == leaf) {
+                self.required_leaves.swap_remove(index as u32);
+            }
+
+            true
+        }
+
+        /// Adds an account to the allowed token owners
+        #[ink(message)]
+        fn allow_owner(&mut self, account: AccountId) -> bool {
+            if !self.is_owner() {
+                return false;
+            }
+
+            if self.allowed_owners.insert(account, true).is_none() {
+                *self.allowed_owners_count += 1;
+            }
+
+            true
+        }
+
+        /// Removes an account from the allowed token owners
+        #[ink(message)]
+        fn disallow_owner(&mut self, account: AccountId) -> bool {
+            if !self.is_owner() {
+                return false;
+            }
+
+            if self.allowed_owners.remove(&account).is_some() {
+                *self.allowed_owners_count -= 1;
+            }
+
+            true
+        }
+
+        /// Sets the maximum metadata length
+        #[ink(message)]
+        fn set_max_metadata_length(&mut self, max_metadata_length: Option<u32>) -> bool {
+            if !self.is_owner() {
+                return false;
+            }
+
+            self.max_metadata_length.set(max_metadata_length);
+
+            true
+        }
+
+        /// Sets the last block a token can be minted
+        #[ink(message)]
+        fn set_mint_deadline(&mut self, mint_deadline: Option<BlockNumber>) -> bool {
+            if !self.is_owner() {
+                return false;
+            }
+
+            self.mint_deadline.set(mint_deadline);
+
+            true
+        }
 
-        // Dispatches a validate call to the NFT module
+        /// Sets the maximum tokens minted per token owner
         #[ink(message)]
-        fn validate(&self, parameters: Vec<u8>) {
-            let decoded =
-                ContractParameter::<Hash, AccountId>::decode(&mut &parameters[..]).unwrap();
-
-            if decoded.uid > 0 {
-                let mint_call = runtime_calls::finish_mint(
-                    decoded.uid,
-                    decoded.token_id,
-                    decoded.token_owner,
-                    decoded.metadata,
-                );
-
-                // dispatch the call to the runtime
-                let result = self.env().invoke_runtime(&mint_call);
-
-                // report result to console
-                // NOTE: println should only be used on a development chain
-                env::println(&format!(
-                    "Balance transfer invoke_runtime result {:?}",
-                    result
-                ));
-            } else {
-                env::println("uid is invalid.");
+        fn set_mint_quota(&mut self, mint_quota: Option<u32>) -> bool {
+            if !self.is_owner() {
+                return false;
+            }
+
+            self.mint_quota.set(mint_quota);
+
+            true
+        }
+
+        // Check if caller is the contract owner
+        fn is_owner(&self) -> bool {
+            self.env().caller() == *self.owner
+        }
+
+        // Check the decoded parameters against all rules at a block
+        fn check(
+            &self,
+            parameters: &ContractParameter<Hash, AccountId>,
+            block_number: BlockNumber,
+        ) -> Result<(), Rejection> {
+            // All required leaves must be proved
+            if !self
+                .required_leaves
+                .iter()
+                .all(|leaf| parameters.proof_leaves.contains(leaf))
+            {
+                return Err(Rejection::MissingRequiredLeaf);
+            }
+
+            // Token owner must be allowed if the list is not empty
+            if *self.allowed_owners_count > 0
+                && self.allowed_owners.get(&parameters.token_owner).is_none()
+            {
+                return Err(Rejection::TokenOwnerNotAllowed);
+            }
+
+            let too_long = match *self.max_metadata_length {
+                Some(max_metadata_length) => parameters.metadata.len() > max_metadata_length as usize,
+                None => false,
+            };
+            if parameters.metadata.is_empty() || too_long {
+                return Err(Rejection::InvalidMetadataLength);
             }
+
+            if let Some(mint_deadline) = *self.mint_deadline {
+                if block_number > mint_deadline {
+                    return Err(Rejection::MintDeadlinePassed);
+                }
+            }
+
+            if let Some(mint_quota) = *self.mint_quota {
+                if parameters.owner_minted >= mint_quota {
+                    return Err(Rejection::MintQuotaExceeded);
+                }
+            }
+
+            Ok(())
         }
     }
 
@@ -77,21 +277,140 @@ mod calls {
     mod tests {
         use super::*;
         use sp_core::crypto::AccountId32;
-        use sp_keyring::AccountKeyring;
+
+        fn account(byte: u8) -> AccountId {
+            let account: AccountId32 = [byte; 32].into();
+            account.into()
+        }
+
+        fn leaf(byte: u8) -> Hash {
+            [byte; 32].into()
+        }
+
+        fn parameters(
+            uid: u64,
+            token_owner: AccountId,
+            metadata: Vec<u8>,
+            proof_leaves: Vec<Hash>,
+        ) -> ContractParameter<Hash, AccountId> {
+            ContractParameter::<Hash, AccountId> {
+                uid,
+                token_id: [0 as u8; 32].into(),
+                token_owner,
+                metadata,
+                proof_leaves,
+                token_user: None,
+                owner_minted: 0,
+            }
+        }
 
         #[test]
-        fn dispatches_validate_call() {
+        fn accepts_valid_parameters() {
+            let mut calls = Calls::new();
+            assert!(calls.add_required_leaf(leaf(1)));
+            assert!(calls.allow_owner(account(1)));
+            assert!(calls.set_max_metadata_length(Some(16)));
+            let parameters = parameters(1, account(1), vec![b'x'; 10], vec![leaf(1), leaf(2)]);
+
+            assert_eq!(calls.check(&parameters, 0), Ok(()));
+        }
+
+        #[test]
+        fn accepts_any_owner_without_rules() {
             let calls = Calls::new();
-            let token_owner: AccountId32 = [0 as u8; 32].into();
-            let parameters = ContractParameter::<Hash, AccountId> {
-                uid: 0,
-                token_id: [0 as u8; 32].into(),
-                token_owner: token_owner.into(),
-                metadata: vec![],
-                proof_leaves: vec![],
-            };
+            let parameters = parameters(1, account(2), vec![b'x'; 10], vec![leaf(1)]);
+
+            assert_eq!(calls.check(&parameters, 0), Ok(()));
+        }
+
+        #[test]
+        fn rejects_missing_required_leaf() {
+            let mut calls = Calls::new();
+            assert!(calls.add_required_leaf(leaf(1)));
+            assert!(calls.add_required_leaf(leaf(3)));
+            let parameters = parameters(1, account(1), vec![b'x'; 10], vec![leaf(1), leaf(2)]);
+
+            assert_eq!(
+                calls.check(&parameters, 0),
+                Err(Rejection::MissingRequiredLeaf)
+            );
+            assert!(!calls.validate(parameters.encode()));
+
+            // Removed leaf is not required any more
+            assert!(calls.remove_required_leaf(leaf(3)));
+            assert_eq!(calls.check(&parameters, 0), Ok(()));
+        }
+
+        #[test]
+        fn rejects_token_owner_not_allowed() {
+            let mut calls = Calls::new();
+            assert!(calls.allow_owner(account(1)));
+            let parameters = parameters(1, account(2), vec![b'x'; 10], vec![leaf(1)]);
+
+            assert_eq!(
+                calls.check(&parameters, 0),
+                Err(Rejection::TokenOwnerNotAllowed)
+            );
+            assert!(!calls.validate(parameters.encode()));
+
+            // Empty allowed list means anyone
+            assert!(calls.disallow_owner(account(1)));
+            assert_eq!(calls.check(&parameters, 0), Ok(()));
+        }
+
+        #[test]
+        fn rejects_invalid_metadata_length() {
+            let mut calls = Calls::new();
+            assert!(calls.set_max_metadata_length(Some(16)));
+
+            let empty = parameters(1, account(1), vec![], vec![leaf(1)]);
+            assert_eq!(
+                calls.check(&empty, 0),
+                Err(Rejection::InvalidMetadataLength)
+            );
+
+            let too_long = parameters(1, account(1), vec![b'x'; 17], vec![leaf(1)]);
+            assert_eq!(
+                calls.check(&too_long, 0),
+                Err(Rejection::InvalidMetadataLength)
+            );
+            assert!(!calls.validate(too_long.encode()));
+        }
+
+        #[test]
+        fn rejects_after_mint_deadline() {
+            let mut calls = Calls::new();
+            assert!(calls.set_mint_deadline(Some(10)));
+            let parameters = parameters(1, account(1), vec![b'x'; 10], vec![leaf(1)]);
+
+            assert_eq!(calls.check(&parameters, 10), Ok(()));
+            assert_eq!(
+                calls.check(&parameters, 11),
+                Err(Rejection::MintDeadlinePassed)
+            );
+        }
+
+        #[test]
+        fn rejects_over_mint_quota() {
+            let mut calls = Calls::new();
+            assert!(calls.set_mint_quota(Some(1)));
+            let mut parameters = parameters(1, account(1), vec![b'x'; 10], vec![leaf(1)]);
+
+            assert_eq!(calls.check(&parameters, 0), Ok(()));
+
+            // Pallet counts the mints finished for the owner in the registry
+            parameters.owner_minted = 1;
+            assert_eq!(
+                calls.check(&parameters, 0),
+                Err(Rejection::MintQuotaExceeded)
+            );
+        }
+
+        #[test]
+        fn rejects_malformed_parameters() {
+            let mut calls = Calls::new();
 
-            let _result = calls.validate(parameters.encode());
+            assert!(!calls.validate(vec![1, 2, 3]));
         }
     }
 }
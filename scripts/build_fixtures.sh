@@ -0,0 +1,14 @@
+#!/usr/bin/env bash
+# Build the ink! contracts used by end-to-end tests and copy the Wasm into fixtures/.
+# Requires cargo-contract and a nightly toolchain with the wasm32-unknown-unknown target.
+set -e
+
+ROOT="$(cd "$(dirname "$0")/.." && pwd)"
+
+cd "$ROOT/contract/validation"
+cargo contract build
+cp target/contract.wasm "$ROOT/fixtures/validation.wasm"
+
+cd "$ROOT/contract/receiver"
+cargo contract build
+cp target/receiver.wasm "$ROOT/fixtures/receiver.wasm"
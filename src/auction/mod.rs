@@ -0,0 +1,407 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+use sp_runtime::traits::{AccountIdConversion, Saturating, UniqueSaturatedInto, Zero};
+use sp_runtime::{ModuleId, Perbill};
+use sp_std::prelude::*;
+use support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::DispatchResult,
+    traits::{Get, ReservableCurrency},
+};
+use system::ensure_signed;
+
+use crate::{erc721, BalanceOf};
+
+// Encoding library
+use codec::{Decode, Encode};
+
+#[cfg(test)]
+mod tests;
+
+// Escrow account holding the auctioned tokens
+const MODULE_ID: ModuleId = ModuleId(*b"nft/auct");
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum AuctionKind {
+    // Highest bid at the end block wins if it reaches the reserve
+    English,
+
+    // Price decays from start price to reserve, first bid at the price wins
+    Dutch,
+}
+
+// Auction of a token escrowed until settlement
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct AuctionInfo<AccountId, Balance, BlockNumber> {
+    pub seller: AccountId,
+    pub kind: AuctionKind,
+    pub start_price: Balance,
+    pub reserve: Balance,
+    pub start_block: BlockNumber,
+    pub end_block: BlockNumber,
+    // Highest bidder of an English auction and its reserved bid
+    pub highest_bid: Option<(AccountId, Balance)>,
+}
+
+type AuctionOf<T> =
+    AuctionInfo<<T as system::Trait>::AccountId, BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+
+pub trait Trait: crate::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+    /// A bid this number of blocks before the end of an English auction extends it
+    /// to this number of blocks after the bid.
+    type ExtensionPeriod: Get<Self::BlockNumber>;
+
+    /// Maximum number of auctions ending at a block, all of them are settled when it is finalized.
+    type MaxAuctionsEnding: Get<u32>;
+}
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        // Token not auctioned
+        NotAuctioned,
+
+        // Start price must be more than zero
+        ZeroPrice,
+
+        // Reserve of a Dutch auction is more than its start price
+        ReserveAboveStartPrice,
+
+        // End block already passed
+        InvalidEndBlock,
+
+        // Auction already ended
+        AuctionEnded,
+
+        // Bid is lower than the start price, the highest bid or the current price
+        BidTooLow,
+
+        // Seller can not bid on own token
+        SellerCannotBid,
+
+        // More than MaxAuctionsEnding auctions would end at the end block
+        TooManyAuctionsEnding,
+
+        // Auction not ended yet
+        AuctionNotEnded,
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+        where
+        <T as system::Trait>::AccountId,
+        <T as system::Trait>::Hash,
+        <T as system::Trait>::BlockNumber,
+        Balance = BalanceOf<T> {
+        // Seller escrowed token in an auction with start price and reserve until end block
+        AuctionCreated(AccountId, Hash, AuctionKind, Balance, Balance, BlockNumber),
+
+        // Bidder bid amount on token
+        Bid(AccountId, Hash, Balance),
+
+        // Bid near the end extended English auction to the new end block
+        AuctionExtended(Hash, BlockNumber),
+
+        // Token sold to winner for price
+        AuctionSettled(Hash, AccountId, Balance),
+
+        // Token returned to seller without a winning bid
+        AuctionUnsold(Hash),
+
+        // Token could not be returned to seller, the ended auction is kept to be settled again
+        SettlementFailed(Hash),
+    }
+);
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Auction {
+        // Auction of each escrowed token
+        pub Auctions get(auctions): map T::Hash => Option<AuctionOf<T>>;
+
+        // Auctions settled at the end of each block
+        pub AuctionsEndingAt get(auctions_ending_at): map T::BlockNumber => Vec<T::Hash>;
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin  {
+        fn deposit_event() = default;
+
+        // Escrow owned token in an English or Dutch auction until end block
+        fn create_auction(
+            origin,
+            token_id: T::Hash,
+            kind: AuctionKind,
+            start_price: BalanceOf<T>,
+            reserve: BalanceOf<T>,
+            end_block: T::BlockNumber
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_create_auction(&sender, &token_id, kind, start_price, reserve, end_block)?;
+
+            Ok(())
+        }
+
+        // Bid on an auction, an English bid is reserved until outbid or settled,
+        // a Dutch bid at the current price buys the token
+        fn bid(origin, token_id: T::Hash, amount: BalanceOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_bid(&sender, &token_id, amount)?;
+
+            Ok(())
+        }
+
+        // Settle an ended auction whose settlement failed, by any account
+        fn settle_auction(origin, token_id: T::Hash) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let auction = Self::auctions(&token_id).ok_or(Error::<T>::NotAuctioned)?;
+            if <system::Module<T>>::block_number() < auction.end_block {
+                return Err(Error::<T>::AuctionNotEnded.into());
+            }
+
+            Self::settle(&token_id)?;
+
+            Ok(())
+        }
+
+        // Settle auctions ending at this block, at most MaxAuctionsEnding
+        fn on_finalize(n: T::BlockNumber) {
+            for token_id in <AuctionsEndingAt<T>>::take(n) {
+                let _ = Self::settle(&token_id);
+            }
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    // Account of the escrow
+    pub fn account_id() -> T::AccountId {
+        MODULE_ID.into_account()
+    }
+
+    // Move token from seller to escrow and start auction
+    pub fn _create_auction(
+        seller: &T::AccountId,
+        token_id: &T::Hash,
+        kind: AuctionKind,
+        start_price: BalanceOf<T>,
+        reserve: BalanceOf<T>,
+        end_block: T::BlockNumber,
+    ) -> DispatchResult {
+        if start_price.is_zero() {
+            return Err(Error::<T>::ZeroPrice.into());
+        }
+
+        if kind == AuctionKind::Dutch && reserve > start_price {
+            return Err(Error::<T>::ReserveAboveStartPrice.into());
+        }
+
+        let now = <system::Module<T>>::block_number();
+        if end_block <= now {
+            return Err(Error::<T>::InvalidEndBlock.into());
+        }
+
+        Self::ensure_can_end_at(end_block)?;
+
+        // Only owner can move token to escrow, fails if token is locked
+        let escrow = Self::account_id();
+        <erc721::Module<T>>::_transfer_from(seller, seller, &escrow, token_id)?;
+
+        <Auctions<T>>::insert(
+            token_id,
+            AuctionInfo {
+                seller: seller.clone(),
+                kind,
+                start_price,
+                reserve,
+                start_block: now,
+                end_block,
+                highest_bid: None,
+            },
+        );
+        <AuctionsEndingAt<T>>::mutate(end_block, |tokens| tokens.push(*token_id));
+
+        Self::deposit_event(RawEvent::AuctionCreated(
+            seller.clone(),
+            *token_id,
+            kind,
+            start_price,
+            reserve,
+            end_block,
+        ));
+
+        Ok(())
+    }
+
+    pub fn _bid(bidder: &T::AccountId, token_id: &T::Hash, amount: BalanceOf<T>) -> DispatchResult {
+        let auction = Self::auctions(token_id).ok_or(Error::<T>::NotAuctioned)?;
+
+        if auction.seller == *bidder {
+            return Err(Error::<T>::SellerCannotBid.into());
+        }
+
+        if <system::Module<T>>::block_number() >= auction.end_block {
+            return Err(Error::<T>::AuctionEnded.into());
+        }
+
+        match auction.kind {
+            AuctionKind::English => Self::english_bid(bidder, token_id, auction, amount),
+            AuctionKind::Dutch => Self::dutch_bid(bidder, token_id, auction, amount),
+        }
+    }
+
+    // Current price of a Dutch auction, decays linearly to the reserve at the end block
+    pub fn current_price(auction: &AuctionOf<T>) -> BalanceOf<T> {
+        let now = <system::Module<T>>::block_number();
+        if now >= auction.end_block {
+            return auction.reserve;
+        }
+
+        let remaining: u32 = (auction.end_block - now).unique_saturated_into();
+        let duration: u32 = (auction.end_block - auction.start_block).unique_saturated_into();
+        let decay = Perbill::from_rational_approximation(remaining, duration)
+            * auction.start_price.saturating_sub(auction.reserve);
+
+        auction.reserve.saturating_add(decay)
+    }
+
+    // Reserve the new highest bid and refund the previous one
+    fn english_bid(
+        bidder: &T::AccountId,
+        token_id: &T::Hash,
+        mut auction: AuctionOf<T>,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        match &auction.highest_bid {
+            Some((_, highest)) if amount <= *highest => {
+                return Err(Error::<T>::BidTooLow.into());
+            }
+            None if amount < auction.start_price => {
+                return Err(Error::<T>::BidTooLow.into());
+            }
+            _ => (),
+        }
+
+        // Anti-sniping, a late bid extends the auction
+        let now = <system::Module<T>>::block_number();
+        let extended_end = now.saturating_add(T::ExtensionPeriod::get());
+        let extended = extended_end > auction.end_block;
+        if extended {
+            Self::ensure_can_end_at(extended_end)?;
+        }
+
+        // Reserve the new bid before refunding, a raised bid reserves the difference
+        match &auction.highest_bid {
+            Some((highest_bidder, highest)) if highest_bidder == bidder => {
+                <T as crate::Trait>::Currency::reserve(bidder, amount - *highest)?;
+            }
+            Some((highest_bidder, highest)) => {
+                <T as crate::Trait>::Currency::reserve(bidder, amount)?;
+                <T as crate::Trait>::Currency::unreserve(highest_bidder, *highest);
+            }
+            None => <T as crate::Trait>::Currency::reserve(bidder, amount)?,
+        }
+        auction.highest_bid = Some((bidder.clone(), amount));
+
+        if extended {
+            <AuctionsEndingAt<T>>::mutate(auction.end_block, |tokens| {
+                tokens.retain(|t| t != token_id)
+            });
+            <AuctionsEndingAt<T>>::mutate(extended_end, |tokens| tokens.push(*token_id));
+            auction.end_block = extended_end;
+
+            Self::deposit_event(RawEvent::AuctionExtended(*token_id, extended_end));
+        }
+
+        <Auctions<T>>::insert(token_id, auction);
+
+        Self::deposit_event(RawEvent::Bid(bidder.clone(), *token_id, amount));
+
+        Ok(())
+    }
+
+    // Buy the token at the current price, if the bid reaches it
+    fn dutch_bid(
+        bidder: &T::AccountId,
+        token_id: &T::Hash,
+        auction: AuctionOf<T>,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        let price = Self::current_price(&auction);
+        if amount < price {
+            return Err(Error::<T>::BidTooLow.into());
+        }
+
+        // No transactional storage, check the release before moving currency
+        let escrow = Self::account_id();
+        <erc721::Module<T>>::ensure_can_transfer(&escrow, &escrow, bidder, token_id)?;
+
+        <crate::Module<T>>::pay_with_royalty(bidder, &auction.seller, token_id, price)?;
+
+        <Auctions<T>>::remove(token_id);
+        <AuctionsEndingAt<T>>::mutate(auction.end_block, |tokens| tokens.retain(|t| t != token_id));
+        Self::release(token_id, bidder)?;
+
+        Self::deposit_event(RawEvent::Bid(bidder.clone(), *token_id, price));
+        Self::deposit_event(RawEvent::AuctionSettled(*token_id, bidder.clone(), price));
+
+        Ok(())
+    }
+
+    // Pay the highest bid reaching the reserve and release token to its bidder,
+    // otherwise return token to seller. The auction is kept without its bid if
+    // the token can not be returned, to be settled again by settle_auction.
+    fn settle(token_id: &T::Hash) -> DispatchResult {
+        let mut auction = Self::auctions(token_id).ok_or(Error::<T>::NotAuctioned)?;
+
+        if let Some((bidder, amount)) = auction.highest_bid.take() {
+            <T as crate::Trait>::Currency::unreserve(&bidder, amount);
+
+            // No transactional storage, check the release before moving currency
+            let escrow = Self::account_id();
+            if amount >= auction.reserve
+                && <erc721::Module<T>>::ensure_can_transfer(&escrow, &escrow, &bidder, token_id)
+                    .is_ok()
+                && <crate::Module<T>>::pay_with_royalty(&bidder, &auction.seller, token_id, amount)
+                    .is_ok()
+            {
+                <Auctions<T>>::remove(token_id);
+                Self::release(token_id, &bidder)?;
+                Self::deposit_event(RawEvent::AuctionSettled(*token_id, bidder, amount));
+                return Ok(());
+            }
+        }
+
+        if let Err(e) = Self::release(token_id, &auction.seller) {
+            <Auctions<T>>::insert(token_id, auction);
+            Self::deposit_event(RawEvent::SettlementFailed(*token_id));
+            return Err(e);
+        }
+
+        <Auctions<T>>::remove(token_id);
+        Self::deposit_event(RawEvent::AuctionUnsold(*token_id));
+
+        Ok(())
+    }
+
+    // Ensure one more auction can end at the block
+    fn ensure_can_end_at(end_block: T::BlockNumber) -> DispatchResult {
+        if Self::auctions_ending_at(end_block).len() >= T::MaxAuctionsEnding::get() as usize {
+            return Err(Error::<T>::TooManyAuctionsEnding.into());
+        }
+
+        Ok(())
+    }
+
+    // Move token from escrow to account
+    fn release(token_id: &T::Hash, to: &T::AccountId) -> DispatchResult {
+        let escrow = Self::account_id();
+        <erc721::Module<T>>::_transfer_from(&escrow, &escrow, to, token_id)
+    }
+}
@@ -0,0 +1,352 @@
+#![cfg(test)]
+
+use super::*;
+use crate::mock::*;
+use sp_core::H256;
+use sp_runtime::traits::OnFinalize;
+use support::assert_ok;
+
+const START_PRICE: u64 = 1_000;
+const RESERVE: u64 = 2_000;
+const END_BLOCK: u64 = 20;
+
+fn create_auction_test(
+    seller: u64,
+    token_id: H256,
+    kind: AuctionKind,
+    start_price: u64,
+    reserve: u64,
+    result: DispatchResult,
+) {
+    assert_eq!(
+        Auction::create_auction(
+            Origin::signed(seller),
+            token_id,
+            kind,
+            start_price,
+            reserve,
+            END_BLOCK
+        ),
+        result
+    );
+
+    if result.is_ok() {
+        assert_eq!(ERC721::owner_of(token_id), Some(Auction::account_id()));
+        assert!(Auction::auctions_ending_at(END_BLOCK).contains(&token_id));
+    }
+}
+
+// Run to the end of a block, settling its auctions
+fn finalize(n: u64) {
+    <system::Module<NftRegistryTest>>::set_block_number(n);
+    Auction::on_finalize(n);
+}
+
+#[test]
+fn create_auction_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = mint_test(ALICE, 1);
+
+        create_auction_test(
+            BOB,
+            token_id,
+            AuctionKind::English,
+            START_PRICE,
+            RESERVE,
+            Err(erc721::Error::<NftRegistryTest>::NotTokenOwner.into()),
+        );
+        create_auction_test(
+            ALICE,
+            token_id,
+            AuctionKind::English,
+            0,
+            RESERVE,
+            Err(Error::<NftRegistryTest>::ZeroPrice.into()),
+        );
+        create_auction_test(
+            ALICE,
+            token_id,
+            AuctionKind::Dutch,
+            START_PRICE,
+            START_PRICE + 1,
+            Err(Error::<NftRegistryTest>::ReserveAboveStartPrice.into()),
+        );
+
+        <system::Module<NftRegistryTest>>::set_block_number(END_BLOCK);
+        create_auction_test(
+            ALICE,
+            token_id,
+            AuctionKind::English,
+            START_PRICE,
+            RESERVE,
+            Err(Error::<NftRegistryTest>::InvalidEndBlock.into()),
+        );
+        assert_eq!(ERC721::owner_of(token_id), Some(ALICE));
+    });
+}
+
+#[test]
+fn english_auction_settles_to_highest_bidder() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = mint_test(ALICE, 1);
+        create_account_test(BOB);
+        create_account_test(CHARLIE);
+        create_auction_test(
+            ALICE,
+            token_id,
+            AuctionKind::English,
+            START_PRICE,
+            RESERVE,
+            Ok(()),
+        );
+
+        assert_eq!(
+            Auction::bid(Origin::signed(ALICE), token_id, RESERVE),
+            Err(Error::<NftRegistryTest>::SellerCannotBid.into())
+        );
+        assert_eq!(
+            Auction::bid(Origin::signed(BOB), token_id, START_PRICE - 1),
+            Err(Error::<NftRegistryTest>::BidTooLow.into())
+        );
+        assert_ok!(Auction::bid(Origin::signed(BOB), token_id, START_PRICE));
+        assert_eq!(Balances::reserved_balance(BOB), START_PRICE);
+
+        // Higher bid refunds the previous bidder
+        assert_eq!(
+            Auction::bid(Origin::signed(CHARLIE), token_id, START_PRICE),
+            Err(Error::<NftRegistryTest>::BidTooLow.into())
+        );
+        assert_ok!(Auction::bid(Origin::signed(CHARLIE), token_id, RESERVE));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::reserved_balance(CHARLIE), RESERVE);
+
+        // Raised bid reserves the difference
+        assert_ok!(Auction::bid(
+            Origin::signed(CHARLIE),
+            token_id,
+            RESERVE + 500
+        ));
+        assert_eq!(Balances::reserved_balance(CHARLIE), RESERVE + 500);
+
+        let seller_balance = Balances::free_balance(ALICE);
+        let buyer_balance = Balances::free_balance(CHARLIE);
+
+        finalize(END_BLOCK);
+        assert_eq!(ERC721::owner_of(token_id), Some(CHARLIE));
+        assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+        assert_eq!(Balances::free_balance(CHARLIE), buyer_balance);
+        assert_eq!(
+            Balances::free_balance(ALICE),
+            seller_balance + RESERVE + 500
+        );
+        assert_eq!(Auction::auctions(token_id), None);
+
+        assert_eq!(
+            Auction::bid(Origin::signed(BOB), token_id, RESERVE * 2),
+            Err(Error::<NftRegistryTest>::NotAuctioned.into())
+        );
+    });
+}
+
+#[test]
+fn english_auction_below_reserve_returns_token() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = mint_test(ALICE, 1);
+        create_account_test(BOB);
+        create_auction_test(
+            ALICE,
+            token_id,
+            AuctionKind::English,
+            START_PRICE,
+            RESERVE,
+            Ok(()),
+        );
+
+        let bidder_balance = Balances::free_balance(BOB);
+        assert_ok!(Auction::bid(Origin::signed(BOB), token_id, START_PRICE));
+
+        finalize(END_BLOCK);
+        assert_eq!(ERC721::owner_of(token_id), Some(ALICE));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(BOB), bidder_balance);
+        assert!(<system::Module<NftRegistryTest>>::events()
+            .iter()
+            .find(|e| match e.event {
+                MetaEvent::auction(RawEvent::AuctionUnsold(_)) => true,
+                _ => false,
+            })
+            .is_some());
+    });
+}
+
+#[test]
+fn late_bid_extends_english_auction() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = mint_test(ALICE, 1);
+        create_account_test(BOB);
+        create_auction_test(
+            ALICE,
+            token_id,
+            AuctionKind::English,
+            START_PRICE,
+            RESERVE,
+            Ok(()),
+        );
+
+        // Bid before the extension period keeps the end block
+        <system::Module<NftRegistryTest>>::set_block_number(END_BLOCK - ExtensionPeriod::get());
+        assert_ok!(Auction::bid(Origin::signed(BOB), token_id, START_PRICE));
+        assert_eq!(Auction::auctions(token_id).unwrap().end_block, END_BLOCK);
+
+        // Bid in the extension period extends the auction after the bid
+        let now = END_BLOCK - 2;
+        let extended_end = now + ExtensionPeriod::get();
+        <system::Module<NftRegistryTest>>::set_block_number(now);
+        assert_ok!(Auction::bid(Origin::signed(BOB), token_id, RESERVE));
+        assert_eq!(Auction::auctions(token_id).unwrap().end_block, extended_end);
+        assert!(Auction::auctions_ending_at(END_BLOCK).is_empty());
+        assert!(Auction::auctions_ending_at(extended_end).contains(&token_id));
+
+        finalize(END_BLOCK);
+        assert_eq!(ERC721::owner_of(token_id), Some(Auction::account_id()));
+
+        finalize(extended_end);
+        assert_eq!(ERC721::owner_of(token_id), Some(BOB));
+    });
+}
+
+#[test]
+fn dutch_price_decays_to_reserve() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = mint_test(ALICE, 1);
+        create_account_test(BOB);
+        create_auction_test(
+            ALICE,
+            token_id,
+            AuctionKind::Dutch,
+            RESERVE,
+            START_PRICE,
+            Ok(()),
+        );
+
+        let auction = Auction::auctions(token_id).unwrap();
+        assert_eq!(Auction::current_price(&auction), RESERVE);
+
+        // Half way to the end, the price is half way to the reserve
+        <system::Module<NftRegistryTest>>::set_block_number(END_BLOCK / 2);
+        let price = (RESERVE + START_PRICE) / 2;
+        assert_eq!(Auction::current_price(&auction), price);
+
+        assert_eq!(
+            Auction::bid(Origin::signed(BOB), token_id, price - 1),
+            Err(Error::<NftRegistryTest>::BidTooLow.into())
+        );
+
+        // First bid at the price buys the token, paying the current price
+        let seller_balance = Balances::free_balance(ALICE);
+        let buyer_balance = Balances::free_balance(BOB);
+        assert_ok!(Auction::bid(Origin::signed(BOB), token_id, RESERVE));
+        assert_eq!(ERC721::owner_of(token_id), Some(BOB));
+        assert_eq!(Balances::free_balance(ALICE), seller_balance + price);
+        assert_eq!(Balances::free_balance(BOB), buyer_balance - price);
+        assert_eq!(Auction::auctions(token_id), None);
+        assert!(Auction::auctions_ending_at(END_BLOCK).is_empty());
+    });
+}
+
+#[test]
+fn dutch_auction_without_bid_returns_token() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = mint_test(ALICE, 1);
+        create_account_test(BOB);
+        create_auction_test(
+            ALICE,
+            token_id,
+            AuctionKind::Dutch,
+            RESERVE,
+            START_PRICE,
+            Ok(()),
+        );
+
+        finalize(END_BLOCK);
+        assert_eq!(ERC721::owner_of(token_id), Some(ALICE));
+        assert_eq!(
+            Auction::bid(Origin::signed(BOB), token_id, RESERVE),
+            Err(Error::<NftRegistryTest>::NotAuctioned.into())
+        );
+    });
+}
+
+#[test]
+fn failed_settlement_keeps_auction() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = mint_test(ALICE, 1);
+        create_account_test(BOB);
+        create_auction_test(
+            ALICE,
+            token_id,
+            AuctionKind::English,
+            START_PRICE,
+            RESERVE,
+            Ok(()),
+        );
+
+        assert_eq!(
+            Auction::settle_auction(Origin::signed(BOB), token_id),
+            Err(Error::<NftRegistryTest>::AuctionNotEnded.into())
+        );
+
+        // Escrowed token can not be released, auction is kept without its bid
+        let bidder_balance = Balances::free_balance(BOB);
+        assert_ok!(Auction::bid(Origin::signed(BOB), token_id, RESERVE));
+        assert_ok!(ERC721::_lock(&token_id));
+        finalize(END_BLOCK);
+        assert_eq!(ERC721::owner_of(token_id), Some(Auction::account_id()));
+        assert_eq!(Auction::auctions(token_id).unwrap().highest_bid, None);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(BOB), bidder_balance);
+        assert!(<system::Module<NftRegistryTest>>::events()
+            .iter()
+            .find(|e| match e.event {
+                MetaEvent::auction(RawEvent::SettlementFailed(_)) => true,
+                _ => false,
+            })
+            .is_some());
+
+        // Settled again by any account once the token can be released
+        assert!(Auction::settle_auction(Origin::signed(BOB), token_id).is_err());
+        ERC721::_unlock(&token_id);
+        assert_ok!(Auction::settle_auction(Origin::signed(BOB), token_id));
+        assert_eq!(ERC721::owner_of(token_id), Some(ALICE));
+        assert_eq!(Auction::auctions(token_id), None);
+    });
+}
+
+#[test]
+fn auctions_ending_at_block_are_capped() {
+    ExtBuilder::default().build().execute_with(|| {
+        let max = MaxAuctionsEnding::get() as u8;
+        for byte in 1..=max {
+            let token_id = mint_test(ALICE, byte);
+            create_auction_test(
+                ALICE,
+                token_id,
+                AuctionKind::English,
+                START_PRICE,
+                RESERVE,
+                Ok(()),
+            );
+        }
+
+        let token_id = mint_test(ALICE, max + 1);
+        create_auction_test(
+            ALICE,
+            token_id,
+            AuctionKind::English,
+            START_PRICE,
+            RESERVE,
+            Err(Error::<NftRegistryTest>::TooManyAuctionsEnding.into()),
+        );
+        assert_eq!(ERC721::owner_of(token_id), Some(ALICE));
+    });
+}
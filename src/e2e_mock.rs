@@ -0,0 +1,370 @@
+//! Runtime and helpers for end-to-end tests running the compiled ink! contracts.
+//!
+//! Types of the runtime are the same with `NodeRuntimeTypes` used by contracts,
+//! so parameters and calls can be decoded on both sides. Contracts are loaded from
+//! the prebuilt Wasm in `fixtures/`, run `scripts/build_fixtures.sh` to update them.
+
+use super::*;
+
+use sp_core::crypto::AccountId32;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, Hash, IdentityLookup},
+    MultiSignature, MultiSigner, Perbill,
+};
+use support::{
+    assert_ok, impl_outer_dispatch, impl_outer_event, impl_outer_origin, parameter_types,
+    traits::Currency, weights::Weight,
+};
+
+pub mod nftregistry {
+    // Re-export contents of the root, required for `impl_outer_event!`.
+    pub use super::super::*;
+    use support::impl_outer_event;
+}
+
+#[derive(Eq, Clone, PartialEq)]
+pub struct E2eTest;
+
+impl_outer_origin! {
+    pub enum Origin for E2eTest {}
+}
+
+// Module indices of the test runtime, the same with the runtime calls encoded by contracts
+pub mod indices {
+    include!("../test-runtime/src/indices.rs");
+}
+
+// Modules keep the order of the test runtime, so calls have the indices above
+impl_outer_dispatch! {
+    pub enum Call for E2eTest where origin: Origin {
+        system::System,
+        pallet_timestamp::Timestamp,
+        nftregistry::NftRegistry,
+        erc721::ERC721,
+        anchor::Anchor,
+        contracts::Contract,
+        balances::Balances,
+    }
+}
+
+impl_outer_event! {
+    pub enum MetaEvent for E2eTest {
+        balances<T>, contracts<T>, nftregistry<T>, erc721<T>, erc1155<T>,
+    }
+}
+
+type BlockNumber = u64;
+type Balance = u128;
+
+parameter_types! {
+    pub const BlockHashCount: BlockNumber = 250;
+    // Room for safe_transfer_from, weighed with the receiver gas limit
+    pub const MaximumBlockWeight: Weight = 100_000_000;
+    pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    pub const MaximumBlockLength: u32 = 5 * 1024 * 1024;
+}
+
+impl system::Trait for E2eTest {
+    type Call = ();
+    type AccountId = AccountId32;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Index = u64;
+    type BlockNumber = BlockNumber;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type Header = Header;
+    type Event = MetaEvent;
+    type Origin = Origin;
+    type BlockHashCount = BlockHashCount;
+    type MaximumBlockWeight = MaximumBlockWeight;
+    type MaximumBlockLength = MaximumBlockLength;
+    type AvailableBlockRatio = AvailableBlockRatio;
+    type Version = ();
+    type ModuleToIndex = ();
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: Balance = 500;
+    pub const TransferFee: Balance = 0;
+    pub const CreationFee: Balance = 0;
+}
+
+impl balances::Trait for E2eTest {
+    type Balance = Balance;
+    type OnFreeBalanceZero = Contract;
+    type OnNewAccount = ();
+    type Event = MetaEvent;
+    type DustRemoval = ();
+    type TransferPayment = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type TransferFee = TransferFee;
+    type CreationFee = CreationFee;
+}
+
+parameter_types! {
+    pub const MinimumPeriod: u64 = 1;
+}
+
+impl pallet_timestamp::Trait for E2eTest {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+}
+
+parameter_types! {
+    pub const MaxNameLength: u32 = 64;
+    pub const MaxSymbolLength: u32 = 16;
+    pub const MaxUriLength: u32 = 256;
+    pub const MaxBatchSize: u32 = 100;
+    pub const MaxNestingDepth: u32 = 8;
+    pub const MaxApprovalsExpiring: u32 = 100;
+}
+
+impl erc721::Trait for E2eTest {
+    type Event = MetaEvent;
+    type Randomness = RandomnessCollectiveFlip;
+    type TokenIndex = u64;
+    type CollectionId = u32;
+    type MetadataOrigin = system::EnsureRoot<AccountId32>;
+    type MaxNameLength = MaxNameLength;
+    type MaxSymbolLength = MaxSymbolLength;
+    type MaxUriLength = MaxUriLength;
+    type MaxBatchSize = MaxBatchSize;
+    type MaxNestingDepth = MaxNestingDepth;
+    type MaxApprovalsExpiring = MaxApprovalsExpiring;
+    type TokenReceiver = ContractReceiver<E2eTest>;
+    type TokenHooks = NftRegistry;
+    type Signature = MultiSignature;
+    type Signer = MultiSigner;
+}
+
+impl anchor::Trait for E2eTest {}
+
+impl erc1155::Trait for E2eTest {
+    type Event = MetaEvent;
+    type TokenBalance = u128;
+    type TokenHooks = NftRegistry;
+}
+
+parameter_types! {
+    pub const SignedClaimHandicap: u32 = 2;
+    pub const TombstoneDeposit: Balance = 16;
+    pub const StorageSizeOffset: u32 = 8;
+    pub const RentByteFee: Balance = 4;
+    pub const RentDepositOffset: Balance = 10_000;
+    pub const SurchargeReward: Balance = 150;
+    pub const TransactionBaseFee: Balance = 2;
+    pub const TransactionByteFee: Balance = 6;
+    pub const ContractFee: Balance = 21;
+    pub const CallBaseFee: u64 = 135;
+    pub const InstantiateBaseFee: u64 = 175;
+    pub const MaxDepth: u32 = 100;
+    pub const MaxValueSize: u32 = 16_384;
+    pub const BlockGasLimit: u64 = 100_000_000;
+}
+
+impl contracts::Trait for E2eTest {
+    type Currency = Balances;
+    type Time = Timestamp;
+    type Randomness = RandomnessCollectiveFlip;
+    type Call = Call;
+    type Event = MetaEvent;
+    type DetermineContractAddress = contracts::SimpleAddressDeterminator<E2eTest>;
+    type ComputeDispatchFee = contracts::DefaultDispatchFeeComputor<E2eTest>;
+    type TrieIdGenerator = contracts::TrieIdFromParentCounter<E2eTest>;
+    type GasPayment = ();
+    type RentPayment = ();
+    type SignedClaimHandicap = SignedClaimHandicap;
+    type TombstoneDeposit = TombstoneDeposit;
+    type StorageSizeOffset = StorageSizeOffset;
+    type RentByteFee = RentByteFee;
+    type RentDepositOffset = RentDepositOffset;
+    type SurchargeReward = SurchargeReward;
+    type TransferFee = TransferFee;
+    type CreationFee = CreationFee;
+    type TransactionBaseFee = TransactionBaseFee;
+    type TransactionByteFee = TransactionByteFee;
+    type ContractFee = ContractFee;
+    type CallBaseFee = CallBaseFee;
+    type InstantiateBaseFee = InstantiateBaseFee;
+    type MaxDepth = MaxDepth;
+    type MaxValueSize = MaxValueSize;
+    type BlockGasLimit = BlockGasLimit;
+}
+
+parameter_types! {
+    pub const NFTDepositBase: Balance = 1_000;
+    pub const NFTDepositPerByte: Balance = 100;
+    pub const NFTValidationRegistryDeposit: Balance = 1_000;
+    pub const ReceiverGasLimit: u64 = 10_000_000;
+}
+
+impl super::Trait for E2eTest {
+    type Event = MetaEvent;
+    type Randomness = RandomnessCollectiveFlip;
+    type NFTDepositBase = NFTDepositBase;
+    type NFTDepositPerByte = NFTDepositPerByte;
+    type NFTValidationRegistryDeposit = NFTValidationRegistryDeposit;
+    type Currency = Balances;
+    type ReceiverGasLimit = ReceiverGasLimit;
+}
+
+pub type System = system::Module<E2eTest>;
+pub type RandomnessCollectiveFlip = randomness_collective_flip::Module<E2eTest>;
+pub type Anchor = anchor::Module<E2eTest>;
+pub type Timestamp = pallet_timestamp::Module<E2eTest>;
+pub type Balances = balances::Module<E2eTest>;
+pub type Contract = contracts::Module<E2eTest>;
+pub type NftReg = super::Module<E2eTest>;
+pub type NftRegistry = super::Module<E2eTest>;
+pub type ERC721 = erc721::Module<E2eTest>;
+
+#[derive(Default)]
+pub struct ExtBuilder {}
+
+impl ExtBuilder {
+    pub fn build(self) -> sp_io::TestExternalities {
+        let mut t = system::GenesisConfig::default()
+            .build_storage::<E2eTest>()
+            .unwrap();
+        balances::GenesisConfig::<E2eTest> {
+            balances: vec![],
+            vesting: vec![],
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+        contracts::GenesisConfig::<E2eTest> {
+            current_schedule: contracts::Schedule {
+                enable_println: true,
+                ..Default::default()
+            },
+            gas_price: 1,
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+
+        sp_io::TestExternalities::new(t)
+    }
+}
+
+pub const GAS_LIMIT: u64 = 10_000_000;
+
+pub fn alice() -> AccountId32 {
+    [1 as u8; 32].into()
+}
+
+pub fn bob() -> AccountId32 {
+    [2 as u8; 32].into()
+}
+
+pub fn create_account_test(account_id: &AccountId32) {
+    let _ = Balances::deposit_creating(account_id, 100_000_000_000_000_000);
+}
+
+// Read a prebuilt contract from the fixtures
+pub fn load_fixture(name: &str) -> Vec<u8> {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join(format!("{}.wasm", name));
+
+    std::fs::read(&path).unwrap_or_else(|_| {
+        panic!(
+            "Failed to read {:?}, run scripts/build_fixtures.sh to build it",
+            path
+        )
+    })
+}
+
+// Store the validation contract code and instantiate it
+pub fn deploy_validation_contract(owner: &AccountId32) -> AccountId32 {
+    deploy_contract(owner, "validation")
+}
+
+// Store the receiver contract code and instantiate it
+pub fn deploy_receiver_contract(owner: &AccountId32) -> AccountId32 {
+    deploy_contract(owner, "receiver")
+}
+
+// Store a contract code from the fixtures and instantiate it
+pub fn deploy_contract(owner: &AccountId32, name: &str) -> AccountId32 {
+    let origin = Origin::signed(owner.clone());
+    let wasm = load_fixture(name);
+    let code_hash = <E2eTest as system::Trait>::Hashing::hash(&wasm);
+
+    // Constructor `new` takes no parameters
+    let data = message_selector("new").encode();
+
+    assert_ok!(
+        Contract::put_code(origin.clone(), GAS_LIMIT, wasm).and_then(|_| {
+            Contract::instantiate(origin, 1_000, GAS_LIMIT, code_hash, data.clone())
+        })
+    );
+
+    let contract_address =
+        <E2eTest as contracts::Trait>::DetermineContractAddress::contract_address_for(
+            &code_hash, &data, owner,
+        );
+    create_account_test(&contract_address);
+
+    contract_address
+}
+
+// Call a message of the contract, parameters must already be encoded
+pub fn call_contract_test(
+    sender: &AccountId32,
+    contract_address: &AccountId32,
+    message: &str,
+    mut parameters: Vec<u8>,
+) {
+    let mut data = message_selector(message).encode();
+    data.append(&mut parameters);
+
+    assert_ok!(Contract::call(
+        Origin::signed(sender.clone()),
+        contract_address.clone(),
+        0,
+        GAS_LIMIT,
+        data
+    ));
+}
+
+pub fn register_validation_test(account_id: &AccountId32, contract_address: &AccountId32) {
+    assert_ok!(NftReg::new_registry(
+        Origin::signed(account_id.clone()),
+        contract_address.clone()
+    ));
+}
+
+pub fn mint_test(
+    registry_id: u64,
+    account_id: &AccountId32,
+    token_id: H256,
+    anchor_id: H256,
+    metadata: Vec<u8>,
+    triple: (Proof, H256, [H256; 3]),
+) {
+    let (proof, _, static_proofs) = triple;
+
+    assert_ok!(NftReg::mint(
+        Origin::signed(account_id.clone()),
+        registry_id,
+        token_id,
+        metadata,
+        anchor_id,
+        vec![proof],
+        static_proofs,
+        0,
+        GAS_LIMIT
+    ));
+}
+
+pub fn mint_nft_event_exists() -> bool {
+    <system::Module<E2eTest>>::events()
+        .iter()
+        .find(|e| match e.event {
+            MetaEvent::nftregistry(RawEvent::MintNft(_, _)) => true,
+            _ => false,
+        })
+        .is_some()
+}
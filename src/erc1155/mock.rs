@@ -0,0 +1,145 @@
+use super::*;
+
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+    Perbill,
+};
+use support::{impl_outer_event, impl_outer_origin, parameter_types, weights::Weight};
+
+pub mod erc1155 {
+    pub use super::*;
+    use support::impl_outer_event;
+}
+
+#[derive(Eq, Clone, PartialEq)]
+pub struct ERC1155Test;
+
+impl_outer_origin! {
+    pub enum Origin for ERC1155Test {}
+}
+
+impl_outer_event! {
+    pub enum MetaEvent for ERC1155Test {
+        erc1155<T>,
+    }
+}
+
+type BlockNumber = u64;
+
+parameter_types! {
+    pub const BlockHashCount: BlockNumber = 250;
+    pub const MaximumBlockWeight: Weight = 1_000_000;
+    pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    pub const MaximumBlockLength: u32 = 5 * 1024 * 1024;
+}
+
+impl system::Trait for ERC1155Test {
+    type Call = ();
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Index = u64;
+    type BlockNumber = BlockNumber;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type Header = Header;
+    type Event = MetaEvent;
+    type Origin = Origin;
+    type BlockHashCount = BlockHashCount;
+    type MaximumBlockWeight = MaximumBlockWeight;
+    type MaximumBlockLength = MaximumBlockLength;
+    type AvailableBlockRatio = AvailableBlockRatio;
+    type Version = ();
+    type ModuleToIndex = ();
+}
+
+#[derive(Default)]
+pub struct ExtBuilder {}
+
+impl ExtBuilder {
+    pub fn build(self) -> sp_io::TestExternalities {
+        let t = system::GenesisConfig::default()
+            .build_storage::<ERC1155Test>()
+            .unwrap();
+
+        sp_io::TestExternalities::new(t)
+    }
+}
+
+pub type ERC1155 = Module<ERC1155Test>;
+
+impl Trait for ERC1155Test {
+    type Event = MetaEvent;
+    type TokenBalance = u64;
+    type TokenHooks = ();
+}
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+
+pub fn token_id(byte: u8) -> H256 {
+    H256::repeat_byte(byte)
+}
+
+pub fn mint_batch_test(sender: u64, ids: Vec<H256>, values: Vec<u64>, result: DispatchResult) {
+    assert_eq!(
+        ERC1155::_mint_batch(&sender, &sender, ids.clone(), values.clone()),
+        result
+    );
+
+    if result.is_ok() {
+        for (id, value) in ids.iter().zip(values.iter()) {
+            assert_eq!(ERC1155::balance_of(sender, id), *value);
+            assert_eq!(ERC1155::total_supply(id), Some(*value));
+        }
+
+        assert!(<system::Module<ERC1155Test>>::events()
+            .iter()
+            .find(|e| match e.event {
+                MetaEvent::erc1155(RawEvent::TransferBatch(_, None, _, _, _)) => true,
+                _ => false,
+            })
+            .is_some());
+    }
+}
+
+pub fn batch_transfer_test(
+    sender: u64,
+    from: u64,
+    to: u64,
+    ids: Vec<H256>,
+    values: Vec<u64>,
+    result: DispatchResult,
+) {
+    let origin = Origin::signed(sender);
+
+    assert_eq!(
+        ERC1155::safe_batch_transfer_from(origin, from, to, ids, values),
+        result
+    );
+
+    if result.is_ok() {
+        assert!(<system::Module<ERC1155Test>>::events()
+            .iter()
+            .find(|e| match e.event {
+                MetaEvent::erc1155(RawEvent::TransferBatch(_, Some(_), Some(_), _, _)) => true,
+                _ => false,
+            })
+            .is_some());
+    }
+}
+
+pub fn approve_for_all_test(sender: u64, operator: u64, approved: bool, result: DispatchResult) {
+    let origin = Origin::signed(sender);
+
+    assert_eq!(
+        ERC1155::set_approval_for_all(origin, operator, approved),
+        result
+    );
+
+    if result.is_ok() {
+        assert_eq!(ERC1155::is_approved_for_all(sender, operator), approved);
+    }
+}
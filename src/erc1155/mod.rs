@@ -0,0 +1,429 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+use support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::{DispatchError, DispatchResult},
+    Parameter,
+};
+use system::ensure_signed;
+
+use sp_runtime::traits::{CheckedAdd, MaybeSerialize, Member, SimpleArithmetic, Zero};
+use sp_std::{
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    prelude::*,
+};
+
+// Encoding library
+use codec::Codec;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+    // Amount of a token id owned by an account, set as parameter in Trait to make it
+    // more flexible to binding during building runtime
+    type TokenBalance: Parameter
+        + Member
+        + SimpleArithmetic
+        + Codec
+        + Default
+        + Copy
+        + MaybeSerialize
+        + PartialEq;
+
+    // Called when tokens are burned, like releasing what was kept for a token id
+    type TokenHooks: OnBurn<Self::AccountId, Self::Hash, Self::TokenBalance>;
+}
+
+pub trait OnBurn<AccountId, Hash, TokenBalance> {
+    // Called after an amount of a token id is burned, with the supply left
+    fn on_burn(_: &AccountId, _: &Hash, _: TokenBalance) {}
+}
+
+impl<AccountId, Hash, TokenBalance> OnBurn<AccountId, Hash, TokenBalance> for () {}
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        // Token id already minted, the supply of a token id is fixed when minted
+        TokenAlreadyExists,
+
+        // Ids and values have different lengths
+        LengthMismatch,
+
+        // Account does not own enough of a token id
+        InsufficientBalance,
+
+        // Balance would overflow
+        BalanceOverflow,
+
+        // Not owner or owner's operator
+        NotOwnerOrOperator,
+
+        // Owner not needed to be operator
+        OwnerAlwaysCanApprove,
+
+        // Minted amount must be more than zero
+        ZeroAmount,
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+        where
+        <T as system::Trait>::AccountId,
+        <T as system::Trait>::Hash,
+        <T as Trait>::TokenBalance {
+        // Operator moved value of one token id, from is None when minted, to is None when burned
+        TransferSingle(AccountId, Option<AccountId>, Option<AccountId>, Hash, TokenBalance),
+
+        // Operator moved values of many token ids, from is None when minted, to is None when burned
+        TransferBatch(AccountId, Option<AccountId>, Option<AccountId>, Vec<Hash>, Vec<TokenBalance>),
+
+        // All tokens owned are approved to other account
+        ApprovalForAll(AccountId, AccountId, bool),
+    }
+);
+
+decl_storage! {
+    trait Store for Module<T: Trait> as ERC1155 {
+        // Amount of each token id owned by an account
+        pub Balances get(balance_of): double_map T::AccountId, twox_128(T::Hash) => T::TokenBalance;
+
+        // Account delegate's account id
+        pub OperatorApprovals get(is_approved_for_all): double_map T::AccountId, twox_128(T::AccountId) => bool;
+
+        // Total minted amount of a token id, None if never minted
+        pub TotalSupply get(total_supply): map T::Hash => Option<T::TokenBalance>;
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin  {
+        fn deposit_event() = default;
+
+        // Transfer value of a token id
+        fn safe_transfer_from(origin, from: T::AccountId, to: T::AccountId, id: T::Hash, value: T::TokenBalance) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_safe_transfer_from(&sender, &from, &to, &id, value)?;
+
+            Ok(())
+        }
+
+        // Transfer values of many token ids, all or nothing
+        fn safe_batch_transfer_from(origin, from: T::AccountId, to: T::AccountId, ids: Vec<T::Hash>, values: Vec<T::TokenBalance>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_safe_batch_transfer_from(&sender, &from, &to, ids, values)?;
+
+            Ok(())
+        }
+
+        // Set if an operator can transfer all owner's tokens
+        fn set_approval_for_all(origin, operator: T::AccountId, approved: bool) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_set_approval_for_all(&sender, &operator, approved)?;
+
+            Ok(())
+        }
+
+        // Burn values of many token ids, all or nothing
+        fn burn_batch(origin, from: T::AccountId, ids: Vec<T::Hash>, values: Vec<T::TokenBalance>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_burn_batch(&sender, &from, ids, values)?;
+
+            Ok(())
+        }
+    }
+}
+
+// Token ids are only minted by other modules, like registries minting documents as shares
+impl<T: Trait> Module<T> {
+    // Mint a new token id with its whole supply
+    pub fn _mint(
+        operator: &T::AccountId,
+        to: &T::AccountId,
+        id: &T::Hash,
+        value: T::TokenBalance,
+    ) -> DispatchResult {
+        Self::ensure_can_mint(&[*id], &[value])?;
+
+        Self::mint_unchecked(to, id, value);
+
+        Self::deposit_event(RawEvent::TransferSingle(
+            operator.clone(),
+            None,
+            Some(to.clone()),
+            *id,
+            value,
+        ));
+
+        Ok(())
+    }
+
+    // Mint many new token ids with their whole supply
+    pub fn _mint_batch(
+        operator: &T::AccountId,
+        to: &T::AccountId,
+        ids: Vec<T::Hash>,
+        values: Vec<T::TokenBalance>,
+    ) -> DispatchResult {
+        Self::ensure_can_mint(&ids, &values)?;
+
+        for (id, value) in ids.iter().zip(values.iter()) {
+            Self::mint_unchecked(to, id, *value);
+        }
+
+        Self::deposit_event(RawEvent::TransferBatch(
+            operator.clone(),
+            None,
+            Some(to.clone()),
+            ids,
+            values,
+        ));
+
+        Ok(())
+    }
+
+    // Transfer value of a token id
+    pub fn _safe_transfer_from(
+        operator: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        id: &T::Hash,
+        value: T::TokenBalance,
+    ) -> DispatchResult {
+        Self::ensure_owner_or_approved_for_all(operator, from)?;
+
+        let totals = Self::sum_by_id(&[*id], &[value])?;
+        Self::move_balances(from, to, totals)?;
+
+        Self::deposit_event(RawEvent::TransferSingle(
+            operator.clone(),
+            Some(from.clone()),
+            Some(to.clone()),
+            *id,
+            value,
+        ));
+
+        Ok(())
+    }
+
+    // Transfer values of many token ids, nothing is transferred if any fails
+    pub fn _safe_batch_transfer_from(
+        operator: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        ids: Vec<T::Hash>,
+        values: Vec<T::TokenBalance>,
+    ) -> DispatchResult {
+        Self::ensure_owner_or_approved_for_all(operator, from)?;
+
+        let totals = Self::sum_by_id(&ids, &values)?;
+        Self::move_balances(from, to, totals)?;
+
+        Self::deposit_event(RawEvent::TransferBatch(
+            operator.clone(),
+            Some(from.clone()),
+            Some(to.clone()),
+            ids,
+            values,
+        ));
+
+        Ok(())
+    }
+
+    // Burn value of a token id
+    pub fn _burn(
+        operator: &T::AccountId,
+        from: &T::AccountId,
+        id: &T::Hash,
+        value: T::TokenBalance,
+    ) -> DispatchResult {
+        Self::ensure_owner_or_approved_for_all(operator, from)?;
+
+        let totals = Self::sum_by_id(&[*id], &[value])?;
+        Self::burn_balances(from, totals)?;
+
+        Self::deposit_event(RawEvent::TransferSingle(
+            operator.clone(),
+            Some(from.clone()),
+            None,
+            *id,
+            value,
+        ));
+
+        Ok(())
+    }
+
+    // Burn values of many token ids, nothing is burned if any fails
+    pub fn _burn_batch(
+        operator: &T::AccountId,
+        from: &T::AccountId,
+        ids: Vec<T::Hash>,
+        values: Vec<T::TokenBalance>,
+    ) -> DispatchResult {
+        Self::ensure_owner_or_approved_for_all(operator, from)?;
+
+        let totals = Self::sum_by_id(&ids, &values)?;
+        Self::burn_balances(from, totals)?;
+
+        Self::deposit_event(RawEvent::TransferBatch(
+            operator.clone(),
+            Some(from.clone()),
+            None,
+            ids,
+            values,
+        ));
+
+        Ok(())
+    }
+
+    // Set if an operator can transfer all owner's tokens
+    pub fn _set_approval_for_all(
+        owner: &T::AccountId,
+        operator: &T::AccountId,
+        approved: bool,
+    ) -> DispatchResult {
+        // Unnecessary to approve for self
+        if owner == operator {
+            return Err(Error::<T>::OwnerAlwaysCanApprove.into());
+        }
+
+        <OperatorApprovals<T>>::insert(owner, operator, approved);
+
+        Self::deposit_event(RawEvent::ApprovalForAll(
+            owner.clone(),
+            operator.clone(),
+            approved,
+        ));
+
+        Ok(())
+    }
+
+    // Ensure ids are new and not repeated in the batch, with amounts more than zero
+    fn ensure_can_mint(ids: &[T::Hash], values: &[T::TokenBalance]) -> DispatchResult {
+        if ids.len() != values.len() {
+            return Err(Error::<T>::LengthMismatch.into());
+        }
+
+        if values.iter().any(|value| value.is_zero()) {
+            return Err(Error::<T>::ZeroAmount.into());
+        }
+
+        let mut seen = BTreeSet::<T::Hash>::new();
+        for id in ids {
+            if <TotalSupply<T>>::exists(id) || !seen.insert(*id) {
+                return Err(Error::<T>::TokenAlreadyExists.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    // Mint without checks, ids must be validated by ensure_can_mint
+    fn mint_unchecked(to: &T::AccountId, id: &T::Hash, value: T::TokenBalance) {
+        Self::set_balance(to, id, value);
+        <TotalSupply<T>>::insert(id, value);
+    }
+
+    // Move the summed amounts after checking all of them, so a batch never applies partially
+    fn move_balances(
+        from: &T::AccountId,
+        to: &T::AccountId,
+        totals: BTreeMap<T::Hash, T::TokenBalance>,
+    ) -> DispatchResult {
+        for (id, amount) in totals.iter() {
+            if Self::balance_of(from, id) < *amount {
+                return Err(Error::<T>::InsufficientBalance.into());
+            }
+
+            if from != to {
+                Self::balance_of(to, id)
+                    .checked_add(amount)
+                    .ok_or(Error::<T>::BalanceOverflow)?;
+            }
+        }
+
+        // Nothing moves when sending to self
+        if from == to {
+            return Ok(());
+        }
+
+        for (id, amount) in totals.iter() {
+            Self::set_balance(from, id, Self::balance_of(from, id) - *amount);
+            Self::set_balance(to, id, Self::balance_of(to, id) + *amount);
+        }
+
+        Ok(())
+    }
+
+    // Burn the summed amounts after checking all of them, so a batch never applies partially
+    fn burn_balances(
+        from: &T::AccountId,
+        totals: BTreeMap<T::Hash, T::TokenBalance>,
+    ) -> DispatchResult {
+        for (id, amount) in totals.iter() {
+            if Self::balance_of(from, id) < *amount {
+                return Err(Error::<T>::InsufficientBalance.into());
+            }
+        }
+
+        for (id, amount) in totals.iter() {
+            Self::set_balance(from, id, Self::balance_of(from, id) - *amount);
+            <TotalSupply<T>>::mutate(id, |supply| {
+                *supply = supply.map(|supply| supply - *amount);
+            });
+
+            T::TokenHooks::on_burn(from, id, Self::total_supply(id).unwrap_or_default());
+        }
+
+        Ok(())
+    }
+
+    // Sum values by id, the same id may appear many times in a batch
+    fn sum_by_id(
+        ids: &[T::Hash],
+        values: &[T::TokenBalance],
+    ) -> Result<BTreeMap<T::Hash, T::TokenBalance>, DispatchError> {
+        if ids.len() != values.len() {
+            return Err(Error::<T>::LengthMismatch.into());
+        }
+
+        let mut totals = BTreeMap::<T::Hash, T::TokenBalance>::new();
+        for (id, value) in ids.iter().zip(values.iter()) {
+            let total = totals.entry(*id).or_insert_with(Zero::zero);
+            *total = total
+                .checked_add(value)
+                .ok_or(Error::<T>::BalanceOverflow)?;
+        }
+
+        Ok(totals)
+    }
+
+    // Store balance, zero balances are removed from storage
+    fn set_balance(account_id: &T::AccountId, id: &T::Hash, value: T::TokenBalance) {
+        if value.is_zero() {
+            <Balances<T>>::remove(account_id, id);
+        } else {
+            <Balances<T>>::insert(account_id, id, value);
+        }
+    }
+
+    // Ensure sender is owner or an operator approved by owner
+    fn ensure_owner_or_approved_for_all(
+        sender: &T::AccountId,
+        owner: &T::AccountId,
+    ) -> DispatchResult {
+        if sender == owner || Self::is_approved_for_all(owner, sender) {
+            Ok(())
+        } else {
+            Err(Error::<T>::NotOwnerOrOperator.into())
+        }
+    }
+}
@@ -0,0 +1,207 @@
+#![cfg(test)]
+
+use super::*;
+use mock::*;
+use support::assert_ok;
+
+#[test]
+fn mint_batch() {
+    ExtBuilder::default().build().execute_with(|| {
+        mint_batch_test(ALICE, vec![token_id(1), token_id(2)], vec![100, 1], Ok(()));
+    });
+}
+
+#[test]
+fn mint_existing_id() {
+    ExtBuilder::default().build().execute_with(|| {
+        mint_batch_test(ALICE, vec![token_id(1)], vec![100], Ok(()));
+
+        // Supply is fixed when minted, also for another account
+        mint_batch_test(
+            BOB,
+            vec![token_id(2), token_id(1)],
+            vec![1, 1],
+            Err(Error::<ERC1155Test>::TokenAlreadyExists.into()),
+        );
+        mint_batch_test(
+            BOB,
+            vec![token_id(3), token_id(3)],
+            vec![1, 1],
+            Err(Error::<ERC1155Test>::TokenAlreadyExists.into()),
+        );
+
+        // Nothing minted from failed batches
+        assert_eq!(ERC1155::total_supply(token_id(2)), None);
+        assert_eq!(ERC1155::total_supply(token_id(3)), None);
+        assert_eq!(ERC1155::balance_of(ALICE, token_id(1)), 100);
+    });
+}
+
+#[test]
+fn mint_length_mismatch() {
+    ExtBuilder::default().build().execute_with(|| {
+        mint_batch_test(
+            ALICE,
+            vec![token_id(1), token_id(2)],
+            vec![100],
+            Err(Error::<ERC1155Test>::LengthMismatch.into()),
+        );
+    });
+}
+
+#[test]
+fn mint_zero_amount() {
+    ExtBuilder::default().build().execute_with(|| {
+        mint_batch_test(
+            ALICE,
+            vec![token_id(1), token_id(2)],
+            vec![100, 0],
+            Err(Error::<ERC1155Test>::ZeroAmount.into()),
+        );
+        assert_eq!(ERC1155::total_supply(token_id(1)), None);
+    });
+}
+
+#[test]
+fn safe_transfer_from() {
+    ExtBuilder::default().build().execute_with(|| {
+        mint_batch_test(ALICE, vec![token_id(1)], vec![100], Ok(()));
+
+        assert_ok!(ERC1155::safe_transfer_from(
+            Origin::signed(ALICE),
+            ALICE,
+            BOB,
+            token_id(1),
+            40
+        ));
+        assert_eq!(ERC1155::balance_of(ALICE, token_id(1)), 60);
+        assert_eq!(ERC1155::balance_of(BOB, token_id(1)), 40);
+        assert_eq!(ERC1155::total_supply(token_id(1)), Some(100));
+
+        assert_eq!(
+            ERC1155::safe_transfer_from(Origin::signed(BOB), BOB, ALICE, token_id(1), 41),
+            Err(Error::<ERC1155Test>::InsufficientBalance.into())
+        );
+    });
+}
+
+#[test]
+fn batch_transfer_is_all_or_nothing() {
+    ExtBuilder::default().build().execute_with(|| {
+        mint_batch_test(ALICE, vec![token_id(1), token_id(2)], vec![100, 10], Ok(()));
+
+        // Second id fails, so the first one is not moved
+        batch_transfer_test(
+            ALICE,
+            ALICE,
+            BOB,
+            vec![token_id(1), token_id(2)],
+            vec![50, 11],
+            Err(Error::<ERC1155Test>::InsufficientBalance.into()),
+        );
+        assert_eq!(ERC1155::balance_of(ALICE, token_id(1)), 100);
+        assert_eq!(ERC1155::balance_of(BOB, token_id(1)), 0);
+
+        // Repeated ids are summed before checking balances
+        batch_transfer_test(
+            ALICE,
+            ALICE,
+            BOB,
+            vec![token_id(2), token_id(2)],
+            vec![6, 6],
+            Err(Error::<ERC1155Test>::InsufficientBalance.into()),
+        );
+
+        batch_transfer_test(
+            ALICE,
+            ALICE,
+            BOB,
+            vec![token_id(1), token_id(2), token_id(2)],
+            vec![50, 5, 5],
+            Ok(()),
+        );
+        assert_eq!(ERC1155::balance_of(ALICE, token_id(1)), 50);
+        assert_eq!(ERC1155::balance_of(BOB, token_id(1)), 50);
+        assert_eq!(ERC1155::balance_of(ALICE, token_id(2)), 0);
+        assert_eq!(ERC1155::balance_of(BOB, token_id(2)), 10);
+    });
+}
+
+#[test]
+fn transfer_by_operator() {
+    ExtBuilder::default().build().execute_with(|| {
+        mint_batch_test(ALICE, vec![token_id(1)], vec![100], Ok(()));
+
+        batch_transfer_test(
+            BOB,
+            ALICE,
+            CHARLIE,
+            vec![token_id(1)],
+            vec![10],
+            Err(Error::<ERC1155Test>::NotOwnerOrOperator.into()),
+        );
+
+        approve_for_all_test(ALICE, BOB, true, Ok(()));
+        batch_transfer_test(BOB, ALICE, CHARLIE, vec![token_id(1)], vec![10], Ok(()));
+        assert_eq!(ERC1155::balance_of(CHARLIE, token_id(1)), 10);
+
+        approve_for_all_test(ALICE, BOB, false, Ok(()));
+        batch_transfer_test(
+            BOB,
+            ALICE,
+            CHARLIE,
+            vec![token_id(1)],
+            vec![10],
+            Err(Error::<ERC1155Test>::NotOwnerOrOperator.into()),
+        );
+    });
+}
+
+#[test]
+fn approve_self() {
+    ExtBuilder::default().build().execute_with(|| {
+        approve_for_all_test(
+            ALICE,
+            ALICE,
+            true,
+            Err(Error::<ERC1155Test>::OwnerAlwaysCanApprove.into()),
+        );
+    });
+}
+
+#[test]
+fn burn_batch() {
+    ExtBuilder::default().build().execute_with(|| {
+        mint_batch_test(ALICE, vec![token_id(1), token_id(2)], vec![100, 10], Ok(()));
+
+        assert_eq!(
+            ERC1155::burn_batch(
+                Origin::signed(ALICE),
+                ALICE,
+                vec![token_id(1), token_id(2)],
+                vec![100, 11]
+            ),
+            Err(Error::<ERC1155Test>::InsufficientBalance.into())
+        );
+        assert_eq!(ERC1155::total_supply(token_id(1)), Some(100));
+
+        assert_ok!(ERC1155::burn_batch(
+            Origin::signed(ALICE),
+            ALICE,
+            vec![token_id(1), token_id(2)],
+            vec![100, 4]
+        ));
+        assert_eq!(ERC1155::balance_of(ALICE, token_id(1)), 0);
+        assert_eq!(ERC1155::total_supply(token_id(1)), Some(0));
+        assert_eq!(ERC1155::balance_of(ALICE, token_id(2)), 6);
+        assert_eq!(ERC1155::total_supply(token_id(2)), Some(6));
+
+        // Burned id can not be minted again
+        mint_batch_test(
+            ALICE,
+            vec![token_id(1)],
+            vec![1],
+            Err(Error::<ERC1155Test>::TokenAlreadyExists.into()),
+        );
+    });
+}
@@ -4,10 +4,11 @@ use node_runtime::constants::currency::*;
 use sp_core::H256;
 use sp_core::{sr25519, Blake2Hasher};
 use sp_runtime::{
-    testing::{Digest, DigestItem, Header},
+    testing::{Digest, DigestItem, Header, TestSignature, UintAuthorityId},
     traits::{BlakeTwo256, Hash, IdentityLookup},
     BuildStorage, Perbill,
 };
+use std::cell::RefCell;
 use support::{
     assert_ok, impl_outer_dispatch, impl_outer_event, impl_outer_origin, parameter_types,
     traits::Currency, weights::Weight,
@@ -64,9 +65,16 @@ pub struct ExtBuilder {}
 
 impl ExtBuilder {
     pub fn build(self) -> sp_io::TestExternalities {
-        let t = system::GenesisConfig::default()
+        let mut t = system::GenesisConfig::default()
             .build_storage::<ERC721Test>()
             .unwrap();
+        GenesisConfig {
+            name: COLLECTION_NAME.to_vec(),
+            symbol: COLLECTION_SYMBOL.to_vec(),
+            base_uri: BASE_URI.to_vec(),
+        }
+        .assimilate_storage::<ERC721Test>(&mut t)
+        .unwrap();
 
         sp_io::TestExternalities::new(t)
     }
@@ -77,10 +85,31 @@ pub type Balances = balances::Module<ERC721Test>;
 pub type ERC721 = Module<ERC721Test>;
 pub type System = system::Module<ERC721Test>;
 
+parameter_types! {
+    pub const MaxNameLength: u32 = 64;
+    pub const MaxSymbolLength: u32 = 16;
+    pub const MaxUriLength: u32 = 256;
+    pub const MaxBatchSize: u32 = 4;
+    pub const MaxNestingDepth: u32 = 3;
+    pub const MaxApprovalsExpiring: u32 = 4;
+}
+
 impl Trait for ERC721Test {
     type Event = MetaEvent;
     type Randomness = RandomnessCollectiveFlip;
     type TokenIndex = u64;
+    type CollectionId = u32;
+    type MetadataOrigin = system::EnsureRoot<u64>;
+    type MaxNameLength = MaxNameLength;
+    type MaxSymbolLength = MaxSymbolLength;
+    type MaxUriLength = MaxUriLength;
+    type MaxBatchSize = MaxBatchSize;
+    type MaxNestingDepth = MaxNestingDepth;
+    type MaxApprovalsExpiring = MaxApprovalsExpiring;
+    type TokenReceiver = TestReceiver;
+    type TokenHooks = TestHooks;
+    type Signature = TestSignature;
+    type Signer = UintAuthorityId;
 }
 
 pub const ALICE: u64 = 1;
@@ -88,15 +117,105 @@ pub const BOB: u64 = 2;
 pub const CHARLIE: u64 = 3;
 pub const DJANGO: u64 = 4;
 
+pub const COLLECTION_NAME: &[u8] = b"Centrifuge NFT";
+pub const COLLECTION_SYMBOL: &[u8] = b"CNFT";
+pub const BASE_URI: &[u8] = b"https://nft.example/";
+
+// Account rejecting all tokens sent by safe_transfer_from
+pub const REJECTING_RECEIVER: u64 = 9;
+
+pub struct TestReceiver;
+
+impl OnErc721Received<u32, u64, H256> for TestReceiver {
+    fn on_erc721_received(_: &u32, _: &u64, _: &u64, to: &u64, _: &H256, _: Vec<u8>) -> bool {
+        *to != REJECTING_RECEIVER
+    }
+}
+
+// Account tokens can not be minted or transferred to
+pub const BLOCKED: u64 = 10;
+
+// Token burned, transferred or minted, observed by RecordingHooks
+#[derive(Debug, PartialEq)]
+pub enum HookCall {
+    Transfer(u64, u64, H256),
+    Mint(u64, H256),
+    Burn(u64, H256),
+}
+
+thread_local! {
+    static HOOK_CALLS: RefCell<Vec<HookCall>> = RefCell::new(vec![]);
+}
+
+// Take the hook calls recorded so far
+pub fn take_hook_calls() -> Vec<HookCall> {
+    HOOK_CALLS.with(|calls| calls.replace(vec![]))
+}
+
+// Vetoes tokens going to the blocked account
+pub struct BlockingHooks;
+
+impl OnTokenTransfer<u32, u64, H256> for BlockingHooks {
+    fn can_transfer(_: &u32, _: &u64, to: &u64, _: &H256) -> DispatchResult {
+        if *to == BLOCKED {
+            Err("blocked account".into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl OnMint<u32, u64, H256> for BlockingHooks {
+    fn can_mint(_: &u32, to: &u64, _: &H256) -> DispatchResult {
+        if *to == BLOCKED {
+            Err("blocked account".into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl OnBurn<u32, u64, H256> for BlockingHooks {}
+
+// Records all operations
+pub struct RecordingHooks;
+
+impl OnTokenTransfer<u32, u64, H256> for RecordingHooks {
+    fn on_transfer(_: &u32, from: &u64, to: &u64, token_id: &H256) {
+        HOOK_CALLS.with(|calls| {
+            calls
+                .borrow_mut()
+                .push(HookCall::Transfer(*from, *to, *token_id))
+        });
+    }
+}
+
+impl OnMint<u32, u64, H256> for RecordingHooks {
+    fn on_mint(_: &u32, to: &u64, token_id: &H256) {
+        HOOK_CALLS.with(|calls| calls.borrow_mut().push(HookCall::Mint(*to, *token_id)));
+    }
+}
+
+impl OnBurn<u32, u64, H256> for RecordingHooks {
+    fn on_burn(_: &u32, owner: &u64, token_id: &H256) {
+        HOOK_CALLS.with(|calls| calls.borrow_mut().push(HookCall::Burn(*owner, *token_id)));
+    }
+}
+
+pub type TestHooks = (BlockingHooks, RecordingHooks);
+
+// Collection used by the single collection API
+pub const DEFAULT_COLLECTION: u32 = 0;
+
 pub fn create_token_test(sender: u64) {
     let origin = Origin::signed(sender);
 
-    assert_eq!(ERC721::create_token(origin), Ok(()));
+    assert_eq!(ERC721::create_token(origin, DEFAULT_COLLECTION), Ok(()));
 
     assert!(<system::Module<ERC721Test>>::events()
         .iter()
         .find(|e| match e.event {
-            MetaEvent::erc721(RawEvent::Transfer(None, _, _)) => true,
+            MetaEvent::erc721(RawEvent::Transfer(_, None, _, _)) => true,
             _ => false,
         })
         .is_some());
@@ -105,13 +224,13 @@ pub fn create_token_test(sender: u64) {
 pub fn mint_token_test(sender: u64, token_id: H256, result: DispatchResult) {
     let origin = Origin::signed(sender);
 
-    assert_eq!(ERC721::mint(origin, token_id), result);
+    assert_eq!(ERC721::mint(origin, DEFAULT_COLLECTION, token_id), result);
 
     if result.is_ok() {
         assert!(<system::Module<ERC721Test>>::events()
             .iter()
             .find(|e| match e.event {
-                MetaEvent::erc721(RawEvent::Transfer(None, _, _)) => true,
+                MetaEvent::erc721(RawEvent::Transfer(_, None, _, _)) => true,
                 _ => false,
             })
             .is_some());
@@ -127,14 +246,17 @@ pub fn transfer_token_test(
 ) {
     let origin = Origin::signed(sender);
 
-    assert_eq!(ERC721::transfer_from(origin, from, to, token_id), result);
+    assert_eq!(
+        ERC721::transfer_from(origin, DEFAULT_COLLECTION, from, to, token_id),
+        result
+    );
 
     if result.is_ok() {
         // Check event
         assert!(<system::Module<ERC721Test>>::events()
             .iter()
             .find(|e| match e.event {
-                MetaEvent::erc721(RawEvent::Transfer(_, _, _)) => true,
+                MetaEvent::erc721(RawEvent::Transfer(_, _, _, _)) => true,
                 _ => false,
             })
             .is_some());
@@ -147,13 +269,13 @@ pub fn transfer_token_test(
 pub fn burn_token_test(sender: u64, token_id: H256, result: DispatchResult) {
     let origin = Origin::signed(sender);
 
-    assert_eq!(ERC721::burn(origin, token_id), result);
+    assert_eq!(ERC721::burn(origin, DEFAULT_COLLECTION, token_id), result);
 
     if result.is_ok() {
         assert!(<system::Module<ERC721Test>>::events()
             .iter()
             .find(|e| match e.event {
-                MetaEvent::erc721(RawEvent::Transfer(_, None, _)) => true,
+                MetaEvent::erc721(RawEvent::Transfer(_, _, None, _)) => true,
                 _ => false,
             })
             .is_some());
@@ -165,13 +287,16 @@ pub fn burn_token_test(sender: u64, token_id: H256, result: DispatchResult) {
 pub fn approve_token_test(sender: u64, to_account: u64, token_id: H256, result: DispatchResult) {
     let origin = Origin::signed(sender);
 
-    assert_eq!(ERC721::approve(origin, to_account, token_id), result);
+    assert_eq!(
+        ERC721::approve(origin, DEFAULT_COLLECTION, to_account, token_id),
+        result
+    );
 
     if result.is_ok() {
         assert!(<system::Module<ERC721Test>>::events()
             .iter()
             .find(|e| match e.event {
-                MetaEvent::erc721(RawEvent::Approval(_, _, token_id)) => true,
+                MetaEvent::erc721(RawEvent::Approval(_, _, _, token_id)) => true,
                 _ => false,
             })
             .is_some());
@@ -184,7 +309,7 @@ pub fn approve_for_all_test(sender: u64, to_account: u64, approved: bool, result
     let origin = Origin::signed(sender);
 
     assert_eq!(
-        ERC721::set_approval_for_all(origin, to_account, approved),
+        ERC721::set_approval_for_all(origin, DEFAULT_COLLECTION, to_account, approved),
         result
     );
 
@@ -192,7 +317,7 @@ pub fn approve_for_all_test(sender: u64, to_account: u64, approved: bool, result
         assert!(<system::Module<ERC721Test>>::events()
             .iter()
             .find(|e| match e.event {
-                MetaEvent::erc721(RawEvent::ApprovalForAll(_, _, approved)) => true,
+                MetaEvent::erc721(RawEvent::ApprovalForAll(_, _, _, approved)) => true,
                 _ => false,
             })
             .is_some());
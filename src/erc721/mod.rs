@@ -1,15 +1,26 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 use sp_runtime::traits::Hash as HashT;
 use support::{
-    decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchError,
-    dispatch::DispatchResult, traits::Randomness, Parameter,
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::DispatchError,
+    dispatch::DispatchResult,
+    traits::{EnsureOrigin, Get, Randomness},
+    weights::{ClassifyDispatch, DispatchClass, PaysFee, SimpleDispatchInfo, WeighData, Weight},
+    Parameter,
 };
-use system::ensure_signed;
+use system::{ensure_signed, EnsureRoot};
 
-use sp_runtime::traits::{MaybeSerialize, Member, One, SimpleArithmetic};
+use sp_runtime::traits::{
+    IdentifyAccount, MaybeSerialize, Member, One, SimpleArithmetic, Verify, Zero,
+};
+use sp_std::{
+    borrow::Borrow,
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    prelude::*,
+};
 
 // Encoding library
-use codec::{Codec, Encode};
+use codec::{Codec, Decode, Encode};
 
 #[cfg(test)]
 mod mock;
@@ -32,6 +43,256 @@ pub trait Trait: system::Trait {
         + Copy
         + MaybeSerialize
         + PartialEq;
+
+    // Collection id, the default value is the collection used by the single collection API
+    type CollectionId: Parameter
+        + Member
+        + SimpleArithmetic
+        + Codec
+        + Default
+        + Copy
+        + MaybeSerialize
+        + PartialEq;
+
+    // Origin allowed to set any token's URI besides the token owner
+    type MetadataOrigin: EnsureOrigin<Self::Origin>;
+
+    // Max length of collection name
+    type MaxNameLength: Get<u32>;
+
+    // Max length of collection symbol
+    type MaxSymbolLength: Get<u32>;
+
+    // Max length of base URI and token URI
+    type MaxUriLength: Get<u32>;
+
+    // Max number of tokens in a batch call
+    type MaxBatchSize: Get<u32>;
+
+    // Max levels of tokens nested below a root token
+    type MaxNestingDepth: Get<u32>;
+
+    // Max number of limited approvals expiring at one block
+    type MaxApprovalsExpiring: Get<u32>;
+
+    // Receiver checked by safe_transfer_from
+    type TokenReceiver: OnErc721Received<Self::CollectionId, Self::AccountId, Self::Hash>;
+
+    // Hooks of other modules checking and observing token movements
+    type TokenHooks: OnTokenTransfer<Self::CollectionId, Self::AccountId, Self::Hash>
+        + OnMint<Self::CollectionId, Self::AccountId, Self::Hash>
+        + OnBurn<Self::CollectionId, Self::AccountId, Self::Hash>;
+
+    // Signature of an off-chain permit, verified against the token owner
+    type Signature: Parameter + Verify<Signer = Self::Signer>;
+
+    // Signer of a permit signature, identifies the owner account
+    type Signer: IdentifyAccount<AccountId = Self::AccountId>;
+}
+
+// Hook of token transfers, default methods allow and ignore all transfers
+pub trait OnTokenTransfer<CollectionId, AccountId, Hash> {
+    // Checked before a token is transferred, an error vetoes the transfer
+    fn can_transfer(_: &CollectionId, _: &AccountId, _: &AccountId, _: &Hash) -> DispatchResult {
+        Ok(())
+    }
+
+    // Called after a token is transferred
+    fn on_transfer(_: &CollectionId, _: &AccountId, _: &AccountId, _: &Hash) {}
+}
+
+// Hook of token mints, default methods allow and ignore all mints
+pub trait OnMint<CollectionId, AccountId, Hash> {
+    // Checked before a token is minted to an account, an error vetoes the mint
+    fn can_mint(_: &CollectionId, _: &AccountId, _: &Hash) -> DispatchResult {
+        Ok(())
+    }
+
+    // Called after a token is minted
+    fn on_mint(_: &CollectionId, _: &AccountId, _: &Hash) {}
+}
+
+// Hook of token burns, default methods allow and ignore all burns
+pub trait OnBurn<CollectionId, AccountId, Hash> {
+    // Checked before a token owned by an account is burned, an error vetoes the burn
+    fn can_burn(_: &CollectionId, _: &AccountId, _: &Hash) -> DispatchResult {
+        Ok(())
+    }
+
+    // Called after a token is burned
+    fn on_burn(_: &CollectionId, _: &AccountId, _: &Hash) {}
+}
+
+impl<CollectionId, AccountId, Hash> OnTokenTransfer<CollectionId, AccountId, Hash> for () {}
+impl<CollectionId, AccountId, Hash> OnMint<CollectionId, AccountId, Hash> for () {}
+impl<CollectionId, AccountId, Hash> OnBurn<CollectionId, AccountId, Hash> for () {}
+
+// Hooks of a tuple are checked and called in order, the first veto stops the operation
+macro_rules! impl_token_hooks_for_tuples {
+    ($($hook:ident),+) => {
+        impl<CollectionId, AccountId, Hash, $($hook),+> OnTokenTransfer<CollectionId, AccountId, Hash>
+            for ($($hook,)+)
+        where
+            $($hook: OnTokenTransfer<CollectionId, AccountId, Hash>),+
+        {
+            fn can_transfer(
+                collection_id: &CollectionId,
+                from: &AccountId,
+                to: &AccountId,
+                token_id: &Hash,
+            ) -> DispatchResult {
+                $($hook::can_transfer(collection_id, from, to, token_id)?;)+
+                Ok(())
+            }
+
+            fn on_transfer(
+                collection_id: &CollectionId,
+                from: &AccountId,
+                to: &AccountId,
+                token_id: &Hash,
+            ) {
+                $($hook::on_transfer(collection_id, from, to, token_id);)+
+            }
+        }
+
+        impl<CollectionId, AccountId, Hash, $($hook),+> OnMint<CollectionId, AccountId, Hash>
+            for ($($hook,)+)
+        where
+            $($hook: OnMint<CollectionId, AccountId, Hash>),+
+        {
+            fn can_mint(
+                collection_id: &CollectionId,
+                to: &AccountId,
+                token_id: &Hash,
+            ) -> DispatchResult {
+                $($hook::can_mint(collection_id, to, token_id)?;)+
+                Ok(())
+            }
+
+            fn on_mint(collection_id: &CollectionId, to: &AccountId, token_id: &Hash) {
+                $($hook::on_mint(collection_id, to, token_id);)+
+            }
+        }
+
+        impl<CollectionId, AccountId, Hash, $($hook),+> OnBurn<CollectionId, AccountId, Hash>
+            for ($($hook,)+)
+        where
+            $($hook: OnBurn<CollectionId, AccountId, Hash>),+
+        {
+            fn can_burn(
+                collection_id: &CollectionId,
+                owner: &AccountId,
+                token_id: &Hash,
+            ) -> DispatchResult {
+                $($hook::can_burn(collection_id, owner, token_id)?;)+
+                Ok(())
+            }
+
+            fn on_burn(collection_id: &CollectionId, owner: &AccountId, token_id: &Hash) {
+                $($hook::on_burn(collection_id, owner, token_id);)+
+            }
+        }
+    };
+}
+
+impl_token_hooks_for_tuples!(A);
+impl_token_hooks_for_tuples!(A, B);
+impl_token_hooks_for_tuples!(A, B, C);
+impl_token_hooks_for_tuples!(A, B, C, D);
+
+// Value returned by a receiver contract accepting a token, the same with
+// the selector of IERC721Receiver-onERC721Received in Ethereum
+pub const ERC721_RECEIVED: [u8; 4] = [0x15, 0x0b, 0x7a, 0x02];
+
+// Check if the account receiving a token by safe_transfer_from accepts it
+pub trait OnErc721Received<CollectionId, AccountId, Hash> {
+    // Returns false if the token is rejected, called before the token moves.
+    // Accounts which are not receiver contracts accept all tokens
+    fn on_erc721_received(
+        collection_id: &CollectionId,
+        operator: &AccountId,
+        from: &AccountId,
+        to: &AccountId,
+        token_id: &Hash,
+        data: Vec<u8>,
+    ) -> bool;
+
+    // Max weight of a receiver check, charged by safe_transfer_from
+    fn max_weight() -> Weight {
+        0
+    }
+}
+
+// All accounts accept tokens
+impl<CollectionId, AccountId, Hash> OnErc721Received<CollectionId, AccountId, Hash> for () {
+    fn on_erc721_received(
+        _: &CollectionId,
+        _: &AccountId,
+        _: &AccountId,
+        _: &AccountId,
+        _: &Hash,
+        _: Vec<u8>,
+    ) -> bool {
+        true
+    }
+}
+
+// Weight of a batch call, a base weight and the weight per token times the
+// number of tokens in the last parameter of the call
+pub struct BatchWeight(pub Weight, pub Weight);
+
+macro_rules! impl_batch_weight {
+    ($($param:ident),*) => {
+        #[allow(non_snake_case)]
+        impl<'a, $($param,)* H> WeighData<($($param,)* &'a Vec<H>,)> for BatchWeight {
+            fn weigh_data(&self, params: ($($param,)* &'a Vec<H>,)) -> Weight {
+                let ($($param,)* token_ids,) = params;
+                $(let _ = $param;)*
+                self.1
+                    .saturating_mul(token_ids.len() as Weight)
+                    .saturating_add(self.0)
+            }
+        }
+
+        impl<'a, $($param,)* H> ClassifyDispatch<($($param,)* &'a Vec<H>,)> for BatchWeight {
+            fn classify_dispatch(&self, _: ($($param,)* &'a Vec<H>,)) -> DispatchClass {
+                DispatchClass::Normal
+            }
+        }
+    };
+}
+
+impl_batch_weight!();
+impl_batch_weight!(A);
+impl_batch_weight!(A, B);
+impl_batch_weight!(A, B, C);
+
+impl PaysFee for BatchWeight {
+    fn pays_fee(&self) -> bool {
+        true
+    }
+}
+
+// Domain of permit payloads, so a permit signature is never valid for other messages
+pub const PERMIT_DOMAIN: [u8; 13] = *b"erc721/permit";
+
+// Limits of an approval, it is invalid from the expiry block or once its uses are spent
+#[derive(Encode, Decode, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ApprovalLimit<BlockNumber> {
+    pub expiry: Option<BlockNumber>,
+    pub uses: Option<u32>,
+}
+
+// Approval removed when its expiry block starts
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum ApprovalKey<CollectionId, AccountId, Hash> {
+    // Approval of a token
+    Token(CollectionId, Hash),
+
+    // Approval of an operator by an owner
+    Operator(CollectionId, AccountId, AccountId),
 }
 
 decl_error! {
@@ -53,6 +314,78 @@ decl_error! {
 
         // Not token's owner or approver
         NotOwnerOrApprover,
+
+        // Not token's owner or owner's operator
+        NotOwnerOrOperator,
+
+        // Collection name longer than MaxNameLength
+        NameTooLong,
+
+        // Collection symbol longer than MaxSymbolLength
+        SymbolTooLong,
+
+        // Base URI or token URI longer than MaxUriLength
+        UriTooLong,
+
+        // Collection not existed in storage
+        CollectionNotExisted,
+
+        // Account not collection's owner
+        NotCollectionOwner,
+
+        // Collection still has tokens
+        CollectionNotEmpty,
+
+        // Token locked, can not be transferred or burned
+        TokenLocked,
+
+        // More tokens than MaxBatchSize in a batch
+        BatchTooLarge,
+
+        // Batch without tokens
+        EmptyBatch,
+
+        // Same token repeated in a batch
+        DuplicateToken,
+
+        // Receiver contract did not accept the token
+        TransferRejected,
+
+        // Approval expiry block already passed or zero uses
+        InvalidApprovalLimit,
+
+        // Operator approval has fewer uses left than tokens in a batch
+        NotEnoughApprovalUses,
+
+        // More than MaxApprovalsExpiring approvals would expire at the expiry block
+        TooManyApprovalsExpiring,
+
+        // Permit deadline block already passed
+        PermitExpired,
+
+        // Permit nonce is not the next nonce of the owner
+        InvalidPermitNonce,
+
+        // Permit not signed by the owner
+        InvalidPermitSignature,
+
+        // Token nested in a parent, it moves with the parent until unnested
+        TokenNested,
+
+        // Token not nested in a parent
+        TokenNotNested,
+
+        // Token still has nested children
+        TokenHasChildren,
+
+        // Parent is the child itself or nested in it
+        NestingCycle,
+
+        // Nesting would be deeper than MaxNestingDepth
+        NestingTooDeep,
+
+        // No collection id left to assign
+        CollectionIdOverflow,
     }
 }
 
@@ -60,49 +393,149 @@ decl_event!(
     pub enum Event<T>
         where
         <T as system::Trait>::AccountId,
-        <T as system::Trait>::Hash {
+        <T as system::Trait>::Hash,
+        <T as system::Trait>::BlockNumber,
+        <T as Trait>::CollectionId {
         // Token transfer event
-        Transfer(Option<AccountId>, Option<AccountId>, Hash),
+        Transfer(CollectionId, Option<AccountId>, Option<AccountId>, Hash),
 
         // One token approved to an account
-        Approval(AccountId, AccountId, Hash),
+        Approval(CollectionId, AccountId, AccountId, Hash),
 
         // All tokens owned are approved to other account
-        ApprovalForAll(AccountId, AccountId, bool),
+        ApprovalForAll(CollectionId, AccountId, AccountId, bool),
+
+        // Collection name, symbol and base URI updated
+        CollectionMetadataSet(CollectionId, Vec<u8>, Vec<u8>, Vec<u8>),
+
+        // Token URI updated
+        TokenUriSet(CollectionId, Hash, Vec<u8>),
+
+        // New collection created with its owner
+        CollectionCreated(CollectionId, AccountId),
+
+        // Empty collection destroyed
+        CollectionDestroyed(CollectionId),
+
+        // Many tokens transferred at once, to is None when burned
+        BatchTransfer(CollectionId, Option<AccountId>, Option<AccountId>, Vec<Hash>),
+
+        // Many tokens approved to an account at once
+        BatchApproval(CollectionId, AccountId, AccountId, Vec<Hash>),
+
+        // Approval by owner to account expired or spent its uses, token is None for an operator
+        ApprovalExpired(CollectionId, AccountId, AccountId, Option<Hash>),
+
+        // Token user set until the expiry block, an expiry already passed removes the user
+        TokenUserSet(CollectionId, Hash, AccountId, BlockNumber),
+
+        // Child token nested in parent token
+        Nested(CollectionId, Hash, Hash),
+
+        // Child token removed from parent token
+        Unnested(CollectionId, Hash, Hash),
     }
 );
 
 decl_storage! {
     trait Store for Module<T: Trait> as ERC721 {
+        // Owner of each created collection, the default collection has no owner
+        pub CollectionOwner get(collection_owner): map T::CollectionId => Option<T::AccountId>;
+
+        // Last assigned collection id, ids are never reused
+        pub LastCollectionId get(last_collection_id): T::CollectionId;
+
         // Token count owned by an account
-        pub OwnedTokensCount get(balance_of): map T::AccountId => T::TokenIndex;
+        pub OwnedTokensCount get(balance_of_in): double_map T::CollectionId, twox_128(T::AccountId) => T::TokenIndex;
 
         // Token owner's account id
-        pub TokenOwner get(owner_of): map T::Hash => Option<T::AccountId>;
+        pub TokenOwner get(owner_of_in): map (T::CollectionId, T::Hash) => Option<T::AccountId>;
 
         // Token delegate's account id
-        pub TokenApprovals get(get_approved): map T::Hash => Option<T::AccountId>;
+        pub TokenApprovals get(get_approved_in): map (T::CollectionId, T::Hash) => Option<T::AccountId>;
 
         // Account delegate's account id
-        pub OperatorApprovals get(is_approved_for_all): double_map T::AccountId, twox_128(T::AccountId) => bool;
+        pub OperatorApprovals get(is_approved_for_all_in): double_map (T::CollectionId, T::AccountId), twox_128(T::AccountId) => bool;
 
         // Total count of minted token
-        pub TotalSupply get(total_supply): T::TokenIndex;
+        pub TotalSupply get(total_supply_in): map T::CollectionId => T::TokenIndex;
 
         // Map of token index to token id
-        pub AllTokens get(token_by_index): map T::TokenIndex => T::Hash;
+        pub AllTokens get(token_by_index_in): double_map T::CollectionId, twox_128(T::TokenIndex) => T::Hash;
 
         // Last token index
-        pub AllTokensIndex: map T::Hash => T::TokenIndex;
+        pub AllTokensIndex: map (T::CollectionId, T::Hash) => T::TokenIndex;
 
         // Map of account and index to token id
-        pub OwnedTokens get(token_of_owner_by_index): double_map T::AccountId, twox_128(T::TokenIndex)  => T::Hash;
+        pub OwnedTokens get(token_of_owner_by_index_in): double_map (T::CollectionId, T::AccountId), twox_128(T::TokenIndex)  => T::Hash;
 
         // Token id to index
-        pub OwnedTokensIndex: map T::Hash => T::TokenIndex;
+        pub OwnedTokensIndex: map (T::CollectionId, T::Hash) => T::TokenIndex;
 
         // Value use to generate random value
         pub Nonce: u64;
+
+        // Collection name
+        pub Name get(name_of): map T::CollectionId => Vec<u8>;
+
+        // Collection symbol
+        pub Symbol get(symbol_of): map T::CollectionId => Vec<u8>;
+
+        // Prefix of all token URIs in a collection
+        pub BaseUri get(base_uri_of): map T::CollectionId => Vec<u8>;
+
+        // URI of a token, appended to the base URI
+        pub TokenUris get(token_uri_suffix_in): map (T::CollectionId, T::Hash) => Vec<u8>;
+
+        // Token locked by other module, like fractional vault
+        pub LockedTokens get(is_locked_in): map (T::CollectionId, T::Hash) => bool;
+
+        // Limits of a token approval, an approval without limits is valid until revoked
+        pub TokenApprovalLimits get(token_approval_limit_in): map (T::CollectionId, T::Hash) => Option<ApprovalLimit<T::BlockNumber>>;
+
+        // Limits of an operator approval
+        pub OperatorApprovalLimits get(operator_approval_limit_in): double_map (T::CollectionId, T::AccountId), twox_128(T::AccountId) => Option<ApprovalLimit<T::BlockNumber>>;
+
+        // Approvals removed when each block starts
+        pub ApprovalsExpiringAt get(approvals_expiring_at): map T::BlockNumber => Vec<ApprovalKey<T::CollectionId, T::AccountId, T::Hash>>;
+
+        // Next permit nonce of each owner, a permit can be used only once
+        pub PermitNonces get(permit_nonce): map T::AccountId => u64;
+
+        // User of a token and the block its role expires, the user is cleared on transfer
+        pub TokenUsers get(token_user_in): map (T::CollectionId, T::Hash) => Option<(T::AccountId, T::BlockNumber)>;
+
+        // Parent of a nested token, the nested token is owned by the owner of its root
+        pub TokenParent get(parent_of_in): map (T::CollectionId, T::Hash) => Option<T::Hash>;
+
+        // Tokens nested directly in a token
+        pub TokenChildren get(children_of_in): map (T::CollectionId, T::Hash) => Vec<T::Hash>;
+    }
+    add_extra_genesis {
+        // Metadata of the default collection
+        config(name): Vec<u8>;
+        config(symbol): Vec<u8>;
+        config(base_uri): Vec<u8>;
+
+        build(|config: &GenesisConfig| {
+            assert!(
+                config.name.len() <= T::MaxNameLength::get() as usize,
+                "Collection name longer than MaxNameLength"
+            );
+            assert!(
+                config.symbol.len() <= T::MaxSymbolLength::get() as usize,
+                "Collection symbol longer than MaxSymbolLength"
+            );
+            assert!(
+                config.base_uri.len() <= T::MaxUriLength::get() as usize,
+                "Base URI longer than MaxUriLength"
+            );
+
+            let collection_id = T::CollectionId::default();
+            <Name<T>>::insert(collection_id, &config.name);
+            <Symbol<T>>::insert(collection_id, &config.symbol);
+            <BaseUri<T>>::insert(collection_id, &config.base_uri);
+        })
     }
 }
 
@@ -111,64 +544,298 @@ decl_module! {
         fn deposit_event() = default;
 
         // Mint a new token
-        fn mint(origin, token_id: T::Hash) -> DispatchResult {
+        fn mint(origin, collection_id: T::CollectionId, token_id: T::Hash) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            Self::_mint(&sender, &token_id)?;
+            Self::ensure_can_mint(&collection_id, &sender)?;
+            Self::_mint_in(&collection_id, &sender, &token_id)?;
 
             Ok(())
         }
 
         // Burn a token
-        fn burn(origin, token_id: T::Hash) -> DispatchResult {
+        fn burn(origin, collection_id: T::CollectionId, token_id: T::Hash) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            Self::_burn(&sender, &token_id)?;
+            Self::_burn_in(&collection_id, &sender, &token_id)?;
 
             Ok(())
         }
 
         // Approve a token to an account
-        fn approve(origin, to: T::AccountId, token_id: T::Hash) -> DispatchResult {
+        fn approve(origin, collection_id: T::CollectionId, to: T::AccountId, token_id: T::Hash) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            Self::_approve(&sender, &to, &token_id)?;
+            Self::_approve_in(&collection_id, &sender, &to, &token_id)?;
 
             Ok(())
         }
 
         // Set if an operator can transfer an owner's token
-        fn set_approval_for_all(origin, to: T::AccountId, approved: bool) -> DispatchResult {
+        fn set_approval_for_all(origin, collection_id: T::CollectionId, to: T::AccountId, approved: bool) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            Self::_set_approval_for_all(&sender, &to, approved)?;
+            Self::_set_approval_for_all_in(&collection_id, &sender, &to, approved)?;
 
             Ok(())
         }
 
         // Transfer token
-        fn transfer_from(origin, from: T::AccountId, to: T::AccountId, token_id: T::Hash) -> DispatchResult {
+        fn transfer_from(origin, collection_id: T::CollectionId, from: T::AccountId, to: T::AccountId, token_id: T::Hash) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            Self::_transfer_from(&sender, &from, &to, &token_id)?;
+            Self::_transfer_from_in(&collection_id, &sender, &from, &to, &token_id)?;
 
             Ok(())
         }
 
         // Create a new token, not part of ECR721 specification but useful to get a token
-        fn create_token(origin) -> DispatchResult {
+        fn create_token(origin, collection_id: T::CollectionId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::ensure_can_mint(&collection_id, &sender)?;
+            Self::_create_token_in(&collection_id, &sender)?;
+
+            Ok(())
+        }
+
+        // Set collection name, symbol and base URI, by root or collection owner
+        fn set_collection_metadata(
+            origin,
+            collection_id: T::CollectionId,
+            name: Vec<u8>,
+            symbol: Vec<u8>,
+            base_uri: Vec<u8>
+        ) -> DispatchResult {
+            // Root can set any collection's metadata, otherwise sender must be collection owner
+            let sender = match EnsureRoot::<T::AccountId>::try_origin(origin) {
+                Ok(_) => None,
+                Err(origin) => Some(ensure_signed(origin)?),
+            };
+
+            if let Some(sender) = sender {
+                Self::ensure_collection_owner(&collection_id, &sender)?;
+            }
+
+            Self::_set_collection_metadata_in(&collection_id, name, symbol, base_uri)?;
+
+            Ok(())
+        }
+
+        // Set the URI of a token, by token owner or metadata origin
+        fn set_token_uri(origin, collection_id: T::CollectionId, token_id: T::Hash, uri: Vec<u8>) -> DispatchResult {
+            // Metadata origin can set any token's URI, otherwise sender must be token owner
+            let sender = match T::MetadataOrigin::try_origin(origin) {
+                Ok(_) => None,
+                Err(origin) => Some(ensure_signed(origin)?),
+            };
+
+            Self::_set_token_uri_in(&collection_id, sender.as_ref(), &token_id, uri)?;
+
+            Ok(())
+        }
+
+        // Create a new collection owned by sender
+        fn create_collection(origin) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_create_collection(&sender)?;
+
+            Ok(())
+        }
+
+        // Destroy an empty collection owned by sender
+        fn destroy_collection(origin, collection_id: T::CollectionId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_destroy_collection(&sender, &collection_id)?;
+
+            Ok(())
+        }
+
+        // Transfer many tokens from one account to other, all or nothing
+        #[weight = BatchWeight(10_000, 10_000)]
+        fn batch_transfer_from(origin, collection_id: T::CollectionId, from: T::AccountId, to: T::AccountId, token_ids: Vec<T::Hash>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_batch_transfer_from_in(&collection_id, &sender, &from, &to, token_ids)?;
+
+            Ok(())
+        }
+
+        // Approve many tokens to an account, all or nothing
+        #[weight = BatchWeight(10_000, 10_000)]
+        fn batch_approve(origin, collection_id: T::CollectionId, to: T::AccountId, token_ids: Vec<T::Hash>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_batch_approve_in(&collection_id, &sender, &to, token_ids)?;
+
+            Ok(())
+        }
+
+        // Burn many tokens, all or nothing
+        #[weight = BatchWeight(10_000, 10_000)]
+        fn batch_burn(origin, collection_id: T::CollectionId, token_ids: Vec<T::Hash>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_batch_burn_in(&collection_id, &sender, token_ids)?;
+
+            Ok(())
+        }
+
+        // Transfer token, receiver contract must accept it
+        #[weight = SimpleDispatchInfo::FixedNormal(
+            T::TokenReceiver::max_weight().saturating_add(10_000)
+        )]
+        fn safe_transfer_from(origin, collection_id: T::CollectionId, from: T::AccountId, to: T::AccountId, token_id: T::Hash, data: Vec<u8>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_safe_transfer_from_in(&collection_id, &sender, &from, &to, &token_id, data)?;
+
+            Ok(())
+        }
+
+        // Approve a token to an account until expiry block or for a number of uses
+        fn approve_with_limit(
+            origin,
+            collection_id: T::CollectionId,
+            to: T::AccountId,
+            token_id: T::Hash,
+            expiry: Option<T::BlockNumber>,
+            uses: Option<u32>
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_approve_with_limit_in(&collection_id, &sender, &to, &token_id, ApprovalLimit { expiry, uses })?;
+
+            Ok(())
+        }
+
+        // Approve an operator until expiry block or for a number of transfers
+        fn set_approval_for_all_with_limit(
+            origin,
+            collection_id: T::CollectionId,
+            to: T::AccountId,
+            expiry: Option<T::BlockNumber>,
+            uses: Option<u32>
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_set_approval_for_all_with_limit_in(&collection_id, &sender, &to, ApprovalLimit { expiry, uses })?;
+
+            Ok(())
+        }
+
+        // Approve a token by a permit signed off-chain by the owner, any account can submit it
+        fn permit(
+            origin,
+            collection_id: T::CollectionId,
+            owner: T::AccountId,
+            spender: T::AccountId,
+            token_id: T::Hash,
+            deadline: T::BlockNumber,
+            nonce: u64,
+            signature: T::Signature
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            Self::_permit_in(&collection_id, &owner, &spender, &token_id, deadline, nonce, &signature)?;
+
+            Ok(())
+        }
+
+        // Give the usage rights of a token to an account until expiry block, ownership is kept
+        fn set_user(
+            origin,
+            collection_id: T::CollectionId,
+            token_id: T::Hash,
+            user: T::AccountId,
+            expires: T::BlockNumber
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_set_user_in(&collection_id, &sender, &token_id, &user, expires)?;
+
+            Ok(())
+        }
+
+        // Nest an owned token in another owned token, it moves with the parent until unnested
+        fn nest(origin, collection_id: T::CollectionId, child: T::Hash, parent: T::Hash) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_nest_in(&collection_id, &sender, &child, &parent)?;
+
+            Ok(())
+        }
+
+        // Remove a nested token from its parent, it is kept by the root owner
+        fn unnest(origin, collection_id: T::CollectionId, child: T::Hash) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            Self::_create_token(&sender)?;
+            Self::_unnest_in(&collection_id, &sender, &child)?;
 
             Ok(())
         }
+
+        // Remove approvals expiring at this block
+        fn on_initialize(n: T::BlockNumber) {
+            for key in <ApprovalsExpiringAt<T>>::take(n) {
+                Self::expire_approval(key, n);
+            }
+        }
     }
 }
 
 impl<T: Trait> Module<T> {
+    // Collection used by the single collection API
+    pub fn default_collection() -> T::CollectionId {
+        T::CollectionId::default()
+    }
+
+    // Create a new collection and return its id
+    pub fn _create_collection(
+        owner: &T::AccountId,
+    ) -> sp_std::result::Result<T::CollectionId, DispatchError> {
+        // The default id is taken by the default collection, so new ids start after it
+        let collection_id = Self::last_collection_id()
+            .checked_add(&One::one())
+            .ok_or(Error::<T>::CollectionIdOverflow)?;
+
+        <LastCollectionId<T>>::put(collection_id);
+        <CollectionOwner<T>>::insert(collection_id, owner);
+
+        Self::deposit_event(RawEvent::CollectionCreated(collection_id, owner.clone()));
+
+        Ok(collection_id)
+    }
+
+    // Destroy a collection without tokens
+    pub fn _destroy_collection(
+        sender: &T::AccountId,
+        collection_id: &T::CollectionId,
+    ) -> DispatchResult {
+        // Default collection has no owner, so it can never be destroyed
+        Self::ensure_collection_owner(collection_id, sender)?;
+
+        if Self::total_supply_in(collection_id) != T::TokenIndex::default() {
+            return Err(Error::<T>::CollectionNotEmpty.into());
+        }
+
+        // Operator approvals are left in storage, they are unreachable as ids are not reused
+        <CollectionOwner<T>>::remove(collection_id);
+        <TotalSupply<T>>::remove(collection_id);
+        <Name<T>>::remove(collection_id);
+        <Symbol<T>>::remove(collection_id);
+        <BaseUri<T>>::remove(collection_id);
+
+        Self::deposit_event(RawEvent::CollectionDestroyed(*collection_id));
+
+        Ok(())
+    }
+
     // Create a new token and token id from random generator
-    pub fn _create_token(
+    pub fn _create_token_in(
+        collection_id: &T::CollectionId,
         account_id: &T::AccountId,
     ) -> sp_std::result::Result<T::Hash, DispatchError> {
         // Nonce used to generate random hash value
@@ -183,7 +850,7 @@ impl<T: Trait> Module<T> {
             .using_encoded(<T as system::Trait>::Hashing::hash);
 
         // Mint the token
-        Self::_mint(account_id, &random_hash.into())?;
+        Self::_mint_in(collection_id, account_id, &random_hash.into())?;
 
         // Update nonce
         Nonce::mutate(|n| *n = n.saturating_add(1));
@@ -192,134 +859,316 @@ impl<T: Trait> Module<T> {
     }
 
     // Mint a new token
-    pub fn _mint(to: &T::AccountId, token_id: &T::Hash) -> DispatchResult {
+    pub fn _mint_in(
+        collection_id: &T::CollectionId,
+        to: &T::AccountId,
+        token_id: &T::Hash,
+    ) -> DispatchResult {
+        // Ensure collection existed
+        Self::ensure_collection_exists(collection_id)?;
+
         // Check if token id already minted
-        Self::ensure_token_not_existed(token_id)?;
+        Self::ensure_token_not_existed_in(collection_id, token_id)?;
+
+        // Ensure no hook vetoes the mint
+        T::TokenHooks::can_mint(collection_id, to, token_id)?;
 
         // Add token id to storage
-        Self::add_token_to_all_tokens_enumeration(token_id);
-        Self::add_token_to_owner_enumeration(to, token_id);
+        Self::add_token_to_all_tokens_enumeration(collection_id, token_id);
+        Self::add_token_to_owner_enumeration(collection_id, to, token_id);
+
+        <TokenOwner<T>>::insert((*collection_id, *token_id), to);
 
-        <TokenOwner<T>>::insert(token_id, to);
-        <OwnedTokensCount<T>>::mutate(to, |value| *value += One::one());
+        T::TokenHooks::on_mint(collection_id, to, token_id);
 
         // Store event
-        Self::deposit_event(RawEvent::Transfer(None, Some(to.clone()), *token_id));
+        Self::deposit_event(RawEvent::Transfer(
+            *collection_id,
+            None,
+            Some(to.clone()),
+            *token_id,
+        ));
 
         Ok(())
     }
 
     // Burn a token
-    pub fn _burn(sender: &T::AccountId, token_id: &T::Hash) -> DispatchResult {
-        // Ensure token existed
-        Self::ensure_token_exists(token_id)?;
-
-        // Ensure get token owner
-        let owner = Self::ensure_get_token_owner(token_id)?;
-
-        // Ensure sender is token's owner
-        if *sender != owner {
-            return Err(Error::<T>::NotTokenOwner.into());
-        }
+    pub fn _burn_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        token_id: &T::Hash,
+    ) -> DispatchResult {
+        let owner = Self::ensure_can_burn_in(collection_id, sender, token_id)?;
 
         // Remove token id from storage
-        Self::remove_token_from_all_tokens_enumeration(token_id);
-        Self::remove_token_from_owner_enumeration(&owner, token_id);
+        Self::remove_token_from_all_tokens_enumeration(collection_id, token_id);
+        Self::remove_token_from_owner_enumeration(collection_id, &owner, token_id);
 
-        <OwnedTokensIndex<T>>::remove(token_id);
-        <TokenApprovals<T>>::remove(token_id);
-        <TokenOwner<T>>::remove(token_id);
+        let key = (*collection_id, *token_id);
+        <TokenApprovals<T>>::remove(key);
+        Self::remove_token_approval_limit(key);
+        <TokenUsers<T>>::remove(key);
+        <TokenOwner<T>>::remove(key);
+        <TokenUris<T>>::remove(key);
+
+        T::TokenHooks::on_burn(collection_id, &owner, token_id);
 
         // Store event
-        Self::deposit_event(RawEvent::Transfer(Some(owner), None, *token_id));
+        Self::deposit_event(RawEvent::Transfer(
+            *collection_id,
+            Some(owner),
+            None,
+            *token_id,
+        ));
 
         Ok(())
     }
 
     // Transfer token from one account to other
-    pub fn _transfer_from(
+    pub fn _transfer_from_in(
+        collection_id: &T::CollectionId,
         sender: &T::AccountId,
         from: &T::AccountId,
         to: &T::AccountId,
         token_id: &T::Hash,
     ) -> DispatchResult {
-        // Ensure token existed
-        Self::ensure_token_exists(token_id)?;
-
-        // Ensure get token owner
-        let owner = Self::ensure_get_token_owner(token_id)?;
+        Self::ensure_can_transfer_in(collection_id, sender, from, to, token_id)?;
 
-        // Ensure from account is owner
-        if owner != *from {
-            return Err(Error::<T>::NotTokenOwner.into());
-        }
+        Self::spend_approval_use_in(collection_id, sender, from, token_id);
+        Self::move_token_in(collection_id, from, to, token_id);
 
-        // Ensure sender can transfer token
-        Self::ensure_approver_or_owner(&sender, token_id)?;
-
-        Self::remove_token_from_owner_enumeration(from, token_id);
-        Self::add_token_to_owner_enumeration(to, token_id);
+        Ok(())
+    }
 
-        <TokenApprovals<T>>::remove(token_id);
-        <TokenOwner<T>>::insert(token_id, to);
+    // Transfer token if the receiver accepts it. No transactional storage, so the
+    // receiver is asked before anything is changed.
+    pub fn _safe_transfer_from_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        token_id: &T::Hash,
+        data: Vec<u8>,
+    ) -> DispatchResult {
+        Self::ensure_can_transfer_in(collection_id, sender, from, to, token_id)?;
 
-        <OwnedTokensCount<T>>::mutate(from, |value| *value -= One::one());
-        <OwnedTokensCount<T>>::mutate(to, |value| *value += One::one());
+        if !T::TokenReceiver::on_erc721_received(collection_id, sender, from, to, token_id, data) {
+            return Err(Error::<T>::TransferRejected.into());
+        }
 
-        Self::deposit_event(RawEvent::Transfer(
-            Some(from.clone()),
-            Some(to.clone()),
+        // Checked again, the receiver may have dispatched calls moving the token
+        Self::_transfer_from_in(collection_id, sender, from, to, token_id)
+    }
+
+    // Set the user of a token until expiry block, by its owner or an approved account
+    pub fn _set_user_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        token_id: &T::Hash,
+        user: &T::AccountId,
+        expires: T::BlockNumber,
+    ) -> DispatchResult {
+        Self::ensure_token_exists_in(collection_id, token_id)?;
+        Self::ensure_approver_or_owner(collection_id, sender, token_id)?;
+
+        let owner = Self::ensure_get_token_owner(collection_id, token_id)?;
+        Self::spend_approval_use_in(collection_id, sender, &owner, token_id);
+
+        let key = (*collection_id, *token_id);
+        if expires > <system::Module<T>>::block_number() {
+            <TokenUsers<T>>::insert(key, (user.clone(), expires));
+        } else {
+            <TokenUsers<T>>::remove(key);
+        }
+
+        Self::deposit_event(RawEvent::TokenUserSet(
+            *collection_id,
             *token_id,
+            user.clone(),
+            expires,
         ));
 
         Ok(())
     }
 
-    // Approve a token to an account
-    pub fn _approve(
+    // User of a token, None once its role expired
+    pub fn user_of_in(collection_id: &T::CollectionId, token_id: &T::Hash) -> Option<T::AccountId> {
+        match Self::token_user_in((*collection_id, *token_id)) {
+            Some((user, expires)) if <system::Module<T>>::block_number() < expires => Some(user),
+            _ => None,
+        }
+    }
+
+    // Nest child in parent, both owned by sender. A nested token is owned by the owner
+    // of its root token and moves with it.
+    pub fn _nest_in(
+        collection_id: &T::CollectionId,
         sender: &T::AccountId,
-        to: &T::AccountId,
-        token_id: &T::Hash,
+        child: &T::Hash,
+        parent: &T::Hash,
     ) -> DispatchResult {
-        // Ensure token existed
-        Self::ensure_token_exists(token_id)?;
+        Self::ensure_token_exists_in(collection_id, child)?;
+        Self::ensure_token_exists_in(collection_id, parent)?;
+        Self::ensure_token_not_locked_in(collection_id, child)?;
+        Self::ensure_token_not_nested_in(collection_id, child)?;
+
+        if Self::owner_of_in((*collection_id, *child)).as_ref() != Some(sender)
+            || Self::owner_of_in((*collection_id, *parent)).as_ref() != Some(sender)
+        {
+            return Err(Error::<T>::NotTokenOwner.into());
+        }
 
-        // Ensure token owner exist
-        let owner = Self::ensure_get_token_owner(token_id)?;
+        let ancestors = Self::ancestors_in(collection_id, parent);
+        if child == parent || ancestors.contains(child) {
+            return Err(Error::<T>::NestingCycle.into());
+        }
 
-        // Unnecessary to approve for self
-        if to.clone() == owner {
-            return Err(Error::<T>::OwnerAlwaysCanApprove.into());
+        // Deepest token below child ends below the new depth of child
+        let depth = (ancestors.len() as u32)
+            .saturating_add(1)
+            .saturating_add(Self::nesting_height_in(collection_id, child));
+        if depth > T::MaxNestingDepth::get() {
+            return Err(Error::<T>::NestingTooDeep.into());
         }
 
-        // Ensure sender can approve
-        Self::ensure_owner_or_approved_for_all(&sender, &owner)?;
+        <TokenParent<T>>::insert((*collection_id, *child), parent);
+        <TokenChildren<T>>::mutate((*collection_id, *parent), |children| children.push(*child));
+
+        Self::deposit_event(RawEvent::Nested(*collection_id, *child, *parent));
+
+        Ok(())
+    }
+
+    // Remove child from its parent, by the root owner
+    pub fn _unnest_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        child: &T::Hash,
+    ) -> DispatchResult {
+        let key = (*collection_id, *child);
+        let parent = Self::parent_of_in(key).ok_or(Error::<T>::TokenNotNested)?;
+
+        if Self::owner_of_in(key).as_ref() != Some(sender) {
+            return Err(Error::<T>::NotTokenOwner.into());
+        }
+
+        <TokenParent<T>>::remove(key);
+        <TokenChildren<T>>::mutate((*collection_id, parent), |children| {
+            children.retain(|token_id| token_id != child)
+        });
+
+        Self::deposit_event(RawEvent::Unnested(*collection_id, *child, parent));
+
+        Ok(())
+    }
+
+    // Parents of a token up to its root, nearest first
+    pub fn ancestors_in(collection_id: &T::CollectionId, token_id: &T::Hash) -> Vec<T::Hash> {
+        let mut ancestors = Vec::new();
+        let mut current = *token_id;
+        while let Some(parent) = Self::parent_of_in((*collection_id, current)) {
+            ancestors.push(parent);
+            current = parent;
+        }
+
+        ancestors
+    }
+
+    // All tokens nested below a token, at any level
+    pub fn descendants_in(collection_id: &T::CollectionId, token_id: &T::Hash) -> Vec<T::Hash> {
+        let mut descendants = Self::children_of_in((*collection_id, *token_id));
+        let mut index = 0;
+        while index < descendants.len() {
+            let children = Self::children_of_in((*collection_id, descendants[index]));
+            descendants.extend(children);
+            index += 1;
+        }
+
+        descendants
+    }
+
+    // Owner of the root token a token is nested in, or of the token itself if not nested
+    pub fn root_owner_of_in(
+        collection_id: &T::CollectionId,
+        token_id: &T::Hash,
+    ) -> Option<T::AccountId> {
+        let root = Self::ancestors_in(collection_id, token_id)
+            .pop()
+            .unwrap_or(*token_id);
+
+        Self::owner_of_in((*collection_id, root))
+    }
+
+    // Levels of tokens nested below a token, zero without children
+    fn nesting_height_in(collection_id: &T::CollectionId, token_id: &T::Hash) -> u32 {
+        Self::children_of_in((*collection_id, *token_id))
+            .iter()
+            .map(|child| Self::nesting_height_in(collection_id, child).saturating_add(1))
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Lock a token, it can not be transferred or burned until unlocked
+    pub fn _lock_in(collection_id: &T::CollectionId, token_id: &T::Hash) -> DispatchResult {
+        Self::ensure_token_exists_in(collection_id, token_id)?;
+        Self::ensure_token_not_locked_in(collection_id, token_id)?;
 
-        // Update data in storage
-        <TokenApprovals<T>>::insert(token_id, to);
+        <LockedTokens<T>>::insert((*collection_id, *token_id), true);
+
+        Ok(())
+    }
+
+    // Unlock a locked token
+    pub fn _unlock_in(collection_id: &T::CollectionId, token_id: &T::Hash) {
+        <LockedTokens<T>>::remove((*collection_id, *token_id));
+    }
+
+    // Approve a token to an account
+    pub fn _approve_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        to: &T::AccountId,
+        token_id: &T::Hash,
+    ) -> DispatchResult {
+        Self::ensure_can_approve_in(collection_id, sender, to, token_id)?;
+
+        // Update data in storage, a new approval has no limits
+        <TokenApprovals<T>>::insert((*collection_id, *token_id), to);
+        Self::remove_token_approval_limit((*collection_id, *token_id));
 
         // Stroe the event
-        Self::deposit_event(RawEvent::Approval(sender.clone(), to.clone(), *token_id));
+        Self::deposit_event(RawEvent::Approval(
+            *collection_id,
+            sender.clone(),
+            to.clone(),
+            *token_id,
+        ));
 
         Ok(())
     }
 
     // Set if an operator can transfer an owner's token
-    pub fn _set_approval_for_all(
+    pub fn _set_approval_for_all_in(
+        collection_id: &T::CollectionId,
         sender: &T::AccountId,
         to: &T::AccountId,
         approved: bool,
     ) -> DispatchResult {
+        // Ensure collection existed
+        Self::ensure_collection_exists(collection_id)?;
+
         // Unnecessary to approve for self
         if to == sender {
             return Err(Error::<T>::OwnerAlwaysCanApprove.into());
         }
 
-        // Insert operator approval into storage
-        <OperatorApprovals<T>>::insert(sender, to, approved);
+        // Insert operator approval into storage, a new approval has no limits
+        <OperatorApprovals<T>>::insert((*collection_id, sender.clone()), to, approved);
+        Self::remove_operator_approval_limit(collection_id, sender, to);
 
         // Store the event
         Self::deposit_event(RawEvent::ApprovalForAll(
+            *collection_id,
             sender.clone(),
             to.clone(),
             approved,
@@ -328,17 +1177,388 @@ impl<T: Trait> Module<T> {
         Ok(())
     }
 
+    // Approve a token to an account with an expiry block or a number of uses
+    pub fn _approve_with_limit_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        to: &T::AccountId,
+        token_id: &T::Hash,
+        limit: ApprovalLimit<T::BlockNumber>,
+    ) -> DispatchResult {
+        Self::ensure_valid_limit(&limit)?;
+        Self::ensure_can_expire_at(limit.expiry)?;
+
+        Self::_approve_in(collection_id, sender, to, token_id)?;
+
+        let key = (*collection_id, *token_id);
+        <TokenApprovalLimits<T>>::insert(key, limit);
+        if let Some(expiry) = limit.expiry {
+            <ApprovalsExpiringAt<T>>::mutate(expiry, |keys| {
+                keys.push(ApprovalKey::Token(*collection_id, *token_id))
+            });
+        }
+
+        Ok(())
+    }
+
+    // Approve an operator with an expiry block or a number of transfers
+    pub fn _set_approval_for_all_with_limit_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        to: &T::AccountId,
+        limit: ApprovalLimit<T::BlockNumber>,
+    ) -> DispatchResult {
+        Self::ensure_valid_limit(&limit)?;
+        Self::ensure_can_expire_at(limit.expiry)?;
+
+        Self::_set_approval_for_all_in(collection_id, sender, to, true)?;
+
+        <OperatorApprovalLimits<T>>::insert((*collection_id, sender.clone()), to, limit);
+        if let Some(expiry) = limit.expiry {
+            <ApprovalsExpiringAt<T>>::mutate(expiry, |keys| {
+                keys.push(ApprovalKey::Operator(
+                    *collection_id,
+                    sender.clone(),
+                    to.clone(),
+                ))
+            });
+        }
+
+        Ok(())
+    }
+
+    // Approve a token to spender if owner signed the permit, the owner's nonce is spent
+    pub fn _permit_in(
+        collection_id: &T::CollectionId,
+        owner: &T::AccountId,
+        spender: &T::AccountId,
+        token_id: &T::Hash,
+        deadline: T::BlockNumber,
+        nonce: u64,
+        signature: &T::Signature,
+    ) -> DispatchResult {
+        if <system::Module<T>>::block_number() > deadline {
+            return Err(Error::<T>::PermitExpired.into());
+        }
+
+        if nonce != Self::permit_nonce(owner) {
+            return Err(Error::<T>::InvalidPermitNonce.into());
+        }
+
+        let payload =
+            Self::permit_payload(collection_id, owner, spender, token_id, deadline, nonce);
+        if !signature.verify(&payload[..], owner) {
+            return Err(Error::<T>::InvalidPermitSignature.into());
+        }
+
+        // Owner approves as if signed, the nonce is spent only if the approval succeeds
+        Self::_approve_in(collection_id, owner, spender, token_id)?;
+        <PermitNonces<T>>::insert(owner, nonce.saturating_add(1));
+
+        Ok(())
+    }
+
+    // SCALE encoded message signed by the owner for a permit, separated by the permit
+    // domain and the genesis hash from other messages and chains
+    pub fn permit_payload(
+        collection_id: &T::CollectionId,
+        owner: &T::AccountId,
+        spender: &T::AccountId,
+        token_id: &T::Hash,
+        deadline: T::BlockNumber,
+        nonce: u64,
+    ) -> Vec<u8> {
+        (
+            PERMIT_DOMAIN,
+            <system::Module<T>>::block_hash(T::BlockNumber::zero()),
+            collection_id,
+            owner,
+            spender,
+            token_id,
+            deadline,
+            nonce,
+        )
+            .encode()
+    }
+
+    // Operator approval is set and within its limits
+    pub fn is_operator_in(
+        collection_id: &T::CollectionId,
+        owner: &T::AccountId,
+        operator: &T::AccountId,
+    ) -> bool {
+        let owner_key = (*collection_id, owner.clone());
+        Self::is_approved_for_all_in(&owner_key, operator)
+            && Self::is_within_limit(Self::operator_approval_limit_in(&owner_key, operator))
+    }
+
+    // Token approval to account is set and within its limits
+    pub fn is_approved_user_in(
+        collection_id: &T::CollectionId,
+        token_id: &T::Hash,
+        account_id: &T::AccountId,
+    ) -> bool {
+        let key = (*collection_id, *token_id);
+        Self::get_approved_in(key).as_ref() == Some(account_id)
+            && Self::is_within_limit(Self::token_approval_limit_in(key))
+    }
+
+    // Transfer many tokens from one account to other, nothing is transferred if any fails
+    pub fn _batch_transfer_from_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        token_ids: Vec<T::Hash>,
+    ) -> DispatchResult {
+        Self::ensure_batch_not_empty(&token_ids)?;
+        Self::ensure_batch_size(&token_ids)?;
+        Self::ensure_no_duplicate_tokens(&token_ids)?;
+
+        // Check all tokens before changing any of them
+        for token_id in token_ids.iter() {
+            Self::ensure_can_transfer_in(collection_id, sender, from, to, token_id)?;
+        }
+
+        // Each token spends a use of a limited operator approval
+        if sender != from && Self::is_operator_in(collection_id, from, sender) {
+            let limit = Self::operator_approval_limit_in((*collection_id, from.clone()), sender);
+            if let Some(uses) = limit.and_then(|limit| limit.uses) {
+                if (uses as usize) < token_ids.len() {
+                    return Err(Error::<T>::NotEnoughApprovalUses.into());
+                }
+            }
+        }
+
+        for token_id in token_ids.iter() {
+            Self::_transfer_from_in(collection_id, sender, from, to, token_id)?;
+        }
+
+        Self::deposit_event(RawEvent::BatchTransfer(
+            *collection_id,
+            Some(from.clone()),
+            Some(to.clone()),
+            token_ids,
+        ));
+
+        Ok(())
+    }
+
+    // Approve many tokens to an account, nothing is approved if any fails
+    pub fn _batch_approve_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        to: &T::AccountId,
+        token_ids: Vec<T::Hash>,
+    ) -> DispatchResult {
+        Self::ensure_batch_not_empty(&token_ids)?;
+        Self::ensure_batch_size(&token_ids)?;
+
+        // Check all tokens before changing any of them
+        for token_id in token_ids.iter() {
+            Self::ensure_can_approve_in(collection_id, sender, to, token_id)?;
+        }
+
+        for token_id in token_ids.iter() {
+            Self::_approve_in(collection_id, sender, to, token_id)?;
+        }
+
+        Self::deposit_event(RawEvent::BatchApproval(
+            *collection_id,
+            sender.clone(),
+            to.clone(),
+            token_ids,
+        ));
+
+        Ok(())
+    }
+
+    // Burn many tokens, nothing is burned if any fails
+    pub fn _batch_burn_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        token_ids: Vec<T::Hash>,
+    ) -> DispatchResult {
+        Self::ensure_batch_not_empty(&token_ids)?;
+        Self::ensure_batch_size(&token_ids)?;
+        Self::ensure_no_duplicate_tokens(&token_ids)?;
+
+        // Check all tokens before changing any of them
+        for token_id in token_ids.iter() {
+            Self::ensure_can_burn_in(collection_id, sender, token_id)?;
+        }
+
+        for token_id in token_ids.iter() {
+            Self::_burn_in(collection_id, sender, token_id)?;
+        }
+
+        Self::deposit_event(RawEvent::BatchTransfer(
+            *collection_id,
+            Some(sender.clone()),
+            None,
+            token_ids,
+        ));
+
+        Ok(())
+    }
+
+    // Set collection name, symbol and base URI
+    pub fn _set_collection_metadata_in(
+        collection_id: &T::CollectionId,
+        name: Vec<u8>,
+        symbol: Vec<u8>,
+        base_uri: Vec<u8>,
+    ) -> DispatchResult {
+        // Ensure collection existed
+        Self::ensure_collection_exists(collection_id)?;
+
+        // Ensure lengths are bounded
+        if name.len() > T::MaxNameLength::get() as usize {
+            return Err(Error::<T>::NameTooLong.into());
+        }
+        if symbol.len() > T::MaxSymbolLength::get() as usize {
+            return Err(Error::<T>::SymbolTooLong.into());
+        }
+        if base_uri.len() > T::MaxUriLength::get() as usize {
+            return Err(Error::<T>::UriTooLong.into());
+        }
+
+        <Name<T>>::insert(collection_id, &name);
+        <Symbol<T>>::insert(collection_id, &symbol);
+        <BaseUri<T>>::insert(collection_id, &base_uri);
+
+        Self::deposit_event(RawEvent::CollectionMetadataSet(
+            *collection_id,
+            name,
+            symbol,
+            base_uri,
+        ));
+
+        Ok(())
+    }
+
+    // Set the URI of a token, sender is None if called by metadata origin
+    pub fn _set_token_uri_in(
+        collection_id: &T::CollectionId,
+        sender: Option<&T::AccountId>,
+        token_id: &T::Hash,
+        uri: Vec<u8>,
+    ) -> DispatchResult {
+        // Ensure token existed
+        Self::ensure_token_exists_in(collection_id, token_id)?;
+
+        // Ensure signed sender is token's owner
+        if let Some(sender) = sender {
+            if *sender != Self::ensure_get_token_owner(collection_id, token_id)? {
+                return Err(Error::<T>::NotTokenOwner.into());
+            }
+        }
+
+        // Ensure length is bounded
+        if uri.len() > T::MaxUriLength::get() as usize {
+            return Err(Error::<T>::UriTooLong.into());
+        }
+
+        <TokenUris<T>>::insert((*collection_id, *token_id), &uri);
+
+        Self::deposit_event(RawEvent::TokenUriSet(*collection_id, *token_id, uri));
+
+        Ok(())
+    }
+
+    // Full URI of a token, base URI followed by token URI, None if token not existed
+    pub fn token_uri_in(collection_id: &T::CollectionId, token_id: &T::Hash) -> Option<Vec<u8>> {
+        let key = (*collection_id, *token_id);
+        if !<TokenOwner<T>>::exists(key) {
+            return None;
+        }
+
+        let mut uri = Self::base_uri_of(collection_id);
+        uri.extend(Self::token_uri_suffix_in(key));
+
+        Some(uri)
+    }
+
     // Get all tokens owned by account
-    pub fn get_tokens_owned_account(account_id: T::AccountId) -> Vec<T::Hash> {
-        <OwnedTokens<T>>::iter_prefix(account_id).collect::<Vec<_>>()
+    pub fn get_tokens_owned_account_in(
+        collection_id: &T::CollectionId,
+        account_id: T::AccountId,
+    ) -> Vec<T::Hash> {
+        <OwnedTokens<T>>::iter_prefix((*collection_id, account_id)).collect::<Vec<_>>()
+    }
+
+    // Cross check all token enumeration storage of a collection, returns the first
+    // inconsistency found. It is used in tests after every operation, like a try-state check.
+    pub fn check_integrity_in(collection_id: &T::CollectionId) -> Result<(), &'static str> {
+        let total_supply = Self::total_supply_in(collection_id);
+        let mut owned_counts = BTreeMap::<T::AccountId, T::TokenIndex>::new();
+        let mut index = T::TokenIndex::default();
+
+        // Every index below total supply maps to a token with consistent indices
+        while index < total_supply {
+            if !<AllTokens<T>>::exists(collection_id, index) {
+                return Err("AllTokens has no token at an index below TotalSupply");
+            }
+
+            let token_id = <AllTokens<T>>::get(collection_id, index);
+            let key = (*collection_id, token_id);
+            if !<AllTokensIndex<T>>::exists(key) || <AllTokensIndex<T>>::get(key) != index {
+                return Err("AllTokensIndex not match AllTokens");
+            }
+
+            let owner = Self::owner_of_in(key).ok_or("Token in AllTokens has no TokenOwner")?;
+            if !<OwnedTokensIndex<T>>::exists(key) {
+                return Err("Token in AllTokens has no OwnedTokensIndex");
+            }
+
+            let owned_index = <OwnedTokensIndex<T>>::get(key);
+            if owned_index >= Self::balance_of_in(collection_id, &owner) {
+                return Err("OwnedTokensIndex not below owner's OwnedTokensCount");
+            }
+
+            let owner_key = (*collection_id, owner.clone());
+            if !<OwnedTokens<T>>::exists(&owner_key, owned_index)
+                || <OwnedTokens<T>>::get(&owner_key, owned_index) != token_id
+            {
+                return Err("OwnedTokens not match OwnedTokensIndex");
+            }
+
+            *owned_counts.entry(owner).or_insert_with(Default::default) += One::one();
+            index += One::one();
+        }
+
+        if <AllTokens<T>>::exists(collection_id, total_supply) {
+            return Err("AllTokens has a token at the index of TotalSupply");
+        }
+
+        // Each owner's count matches the tokens enumerated for it
+        for (owner, count) in owned_counts {
+            if Self::balance_of_in(collection_id, &owner) != count {
+                return Err("OwnedTokensCount not match tokens owned");
+            }
+
+            let listed = <OwnedTokens<T>>::iter_prefix((*collection_id, owner))
+                .fold(T::TokenIndex::default(), |acc, _| acc + One::one());
+            if listed != count {
+                return Err("OwnedTokens has more entries than tokens owned");
+            }
+        }
+
+        Ok(())
     }
 
     // Token owner or token approval or owner's delegate
-    fn ensure_approver_or_owner(sender: &T::AccountId, token_id: &T::Hash) -> DispatchResult {
-        // Get token owner and approver
-        let token_owner = Self::owner_of(token_id);
+    fn ensure_approver_or_owner(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        token_id: &T::Hash,
+    ) -> DispatchResult {
+        let key = (*collection_id, *token_id);
+
+        // Get token owner and approver, limited approvals must be within their limits
+        let token_owner = Self::owner_of_in(key);
         let approved_or_owner = match token_owner {
-            Some(owner) => owner == *sender || Self::is_approved_for_all(owner, sender),
+            Some(owner) => owner == *sender || Self::is_operator_in(collection_id, &owner, sender),
             None => false,
         };
 
@@ -347,111 +1567,759 @@ impl<T: Trait> Module<T> {
         }
 
         // Check if sender is approved user
-        let approved_user = Self::get_approved(token_id);
-        let approved_as_user = match approved_user {
-            Some(user) => user == *sender,
-            None => false,
-        };
-
-        if approved_as_user {
+        if Self::is_approved_user_in(collection_id, token_id, sender) {
             Ok(())
         } else {
             Err(Error::<T>::NotOwnerOrApprover.into())
         }
     }
 
+    // Move token and its nested tokens to new owner without checks, clears their approvals and users
+    fn move_token_in(
+        collection_id: &T::CollectionId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        token_id: &T::Hash,
+    ) {
+        Self::remove_token_from_owner_enumeration(collection_id, from, token_id);
+        Self::add_token_to_owner_enumeration(collection_id, to, token_id);
+
+        let key = (*collection_id, *token_id);
+        <TokenApprovals<T>>::remove(key);
+        Self::remove_token_approval_limit(key);
+        <TokenUsers<T>>::remove(key);
+        <TokenOwner<T>>::insert(key, to);
+
+        T::TokenHooks::on_transfer(collection_id, from, to, token_id);
+
+        Self::deposit_event(RawEvent::Transfer(
+            *collection_id,
+            Some(from.clone()),
+            Some(to.clone()),
+            *token_id,
+        ));
+
+        // Nested tokens move with their parent
+        for child in Self::children_of_in(key) {
+            Self::move_token_in(collection_id, from, to, &child);
+        }
+    }
+
     // Add token index to its owner's enumeration
-    fn add_token_to_owner_enumeration(to: &T::AccountId, token_id: &T::Hash) {
-        let new_token_index = Self::balance_of(to);
-        <OwnedTokensIndex<T>>::insert(token_id, new_token_index);
-        <OwnedTokens<T>>::insert(to, new_token_index, token_id);
-        <OwnedTokensCount<T>>::mutate(to, |value| *value += One::one());
+    fn add_token_to_owner_enumeration(
+        collection_id: &T::CollectionId,
+        to: &T::AccountId,
+        token_id: &T::Hash,
+    ) {
+        let new_token_index = Self::balance_of_in(collection_id, to);
+        <OwnedTokensIndex<T>>::insert((*collection_id, *token_id), new_token_index);
+        <OwnedTokens<T>>::insert((*collection_id, to.clone()), new_token_index, token_id);
+        <OwnedTokensCount<T>>::mutate(collection_id, to, |value| *value += One::one());
     }
 
     // Add token index to all token's enumeration
-    fn add_token_to_all_tokens_enumeration(token_id: &T::Hash) {
-        let new_token_index = <TotalSupply<T>>::get();
-        <AllTokensIndex<T>>::insert(token_id, new_token_index);
-        <AllTokens<T>>::insert(new_token_index, token_id);
-        <TotalSupply<T>>::mutate(|value| *value += One::one());
+    fn add_token_to_all_tokens_enumeration(collection_id: &T::CollectionId, token_id: &T::Hash) {
+        let new_token_index = <TotalSupply<T>>::get(collection_id);
+        <AllTokensIndex<T>>::insert((*collection_id, *token_id), new_token_index);
+        <AllTokens<T>>::insert(collection_id, new_token_index, token_id);
+        <TotalSupply<T>>::mutate(collection_id, |value| *value += One::one());
     }
 
     // Remove token info from owner's enumeration
-    fn remove_token_from_owner_enumeration(from: &T::AccountId, token_id: &T::Hash) {
+    fn remove_token_from_owner_enumeration(
+        collection_id: &T::CollectionId,
+        from: &T::AccountId,
+        token_id: &T::Hash,
+    ) {
         // Sub owner's token count
-        <OwnedTokensCount<T>>::mutate(from, |value| *value -= One::one());
+        <OwnedTokensCount<T>>::mutate(collection_id, from, |value| *value -= One::one());
 
         // Get last owned token index
-        let last_token_index = <OwnedTokensCount<T>>::get(from);
+        let last_token_index = <OwnedTokensCount<T>>::get(collection_id, from);
 
         // exchange token index and last token index
-        let token_index = <OwnedTokensIndex<T>>::get(token_id);
+        let owner_key = (*collection_id, from.clone());
+        let token_index = <OwnedTokensIndex<T>>::get((*collection_id, *token_id));
 
         if token_index != last_token_index {
-            let last_token_id = <OwnedTokens<T>>::get(from.clone(), last_token_index);
-            <OwnedTokens<T>>::insert(from.clone(), token_index, last_token_id);
-            <OwnedTokensIndex<T>>::insert(last_token_id, token_index);
+            let last_token_id = <OwnedTokens<T>>::get(&owner_key, last_token_index);
+            <OwnedTokens<T>>::insert(&owner_key, token_index, last_token_id);
+            <OwnedTokensIndex<T>>::insert((*collection_id, last_token_id), token_index);
         }
 
-        <OwnedTokens<T>>::remove(from, last_token_index);
-        <OwnedTokensIndex<T>>::remove(token_id);
+        <OwnedTokens<T>>::remove(&owner_key, last_token_index);
+        <OwnedTokensIndex<T>>::remove((*collection_id, *token_id));
     }
 
     // Remove token info from all token's enumeration
-    fn remove_token_from_all_tokens_enumeration(token_id: &T::Hash) {
+    fn remove_token_from_all_tokens_enumeration(
+        collection_id: &T::CollectionId,
+        token_id: &T::Hash,
+    ) {
         // Update total supply
-        <TotalSupply<T>>::mutate(|value| *value -= One::one());
+        <TotalSupply<T>>::mutate(collection_id, |value| *value -= One::one());
 
-        let last_token_index = <TotalSupply<T>>::get();
+        let last_token_index = <TotalSupply<T>>::get(collection_id);
 
         // exchange token index and last token index
-        let token_index = <AllTokensIndex<T>>::get(token_id);
+        let token_index = <AllTokensIndex<T>>::get((*collection_id, *token_id));
 
         if token_index != last_token_index {
-            let last_token_id = <AllTokens<T>>::get(last_token_index);
-            <AllTokens<T>>::insert(token_index, last_token_id);
-            <AllTokensIndex<T>>::insert(last_token_id, token_index);
+            let last_token_id = <AllTokens<T>>::get(collection_id, last_token_index);
+            <AllTokens<T>>::insert(collection_id, token_index, last_token_id);
+            <AllTokensIndex<T>>::insert((*collection_id, last_token_id), token_index);
         }
 
-        <AllTokens<T>>::remove(last_token_index);
-        <AllTokensIndex<T>>::remove(token_id);
+        <AllTokens<T>>::remove(collection_id, last_token_index);
+        <AllTokensIndex<T>>::remove((*collection_id, *token_id));
     }
 
     // Ensure get token owner
-    fn ensure_get_token_owner(token_id: &T::Hash) -> Result<T::AccountId, DispatchError> {
-        match Self::owner_of(token_id) {
+    fn ensure_get_token_owner(
+        collection_id: &T::CollectionId,
+        token_id: &T::Hash,
+    ) -> Result<T::AccountId, DispatchError> {
+        match Self::owner_of_in((*collection_id, *token_id)) {
             Some(owner) => Ok(owner),
             None => Err(Error::<T>::TokenOwnerNotSet.into()),
         }
     }
 
-    // Ensure sender can transfer owner's token
+    // Ensure sender is owner or an operator approved by owner
     fn ensure_owner_or_approved_for_all(
+        collection_id: &T::CollectionId,
         sender: &T::AccountId,
         owner: &T::AccountId,
     ) -> DispatchResult {
-        if sender == owner || Self::is_approved_for_all(sender, owner) {
+        if sender == owner || Self::is_operator_in(collection_id, owner, sender) {
             Ok(())
         } else {
-            Err(Error::<T>::OwnerAlwaysCanApprove.into())
+            Err(Error::<T>::NotOwnerOrOperator.into())
         }
     }
 
-    // Ensure token exists
-    pub fn ensure_token_exists(token_id: &T::Hash) -> DispatchResult {
-        if <TokenOwner<T>>::exists(token_id) {
-            Ok(())
-        } else {
-            Err(Error::<T>::TokenNotExisted.into())
-        }
-    }
+    // Ensure sender can transfer token owned by from account
+    pub fn ensure_can_transfer_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        token_id: &T::Hash,
+    ) -> DispatchResult {
+        // Ensure token existed
+        Self::ensure_token_exists_in(collection_id, token_id)?;
 
-    // Ensure token not existed
-    pub fn ensure_token_not_existed(token_id: &T::Hash) -> DispatchResult {
-        if <TokenOwner<T>>::exists(token_id) {
-            Err(Error::<T>::TokenAlreadyExists.into())
+        // Ensure token not locked
+        Self::ensure_token_not_locked_in(collection_id, token_id)?;
+
+        // Nested token only moves with its parent
+        Self::ensure_token_not_nested_in(collection_id, token_id)?;
+
+        // Ensure get token owner
+        let owner = Self::ensure_get_token_owner(collection_id, token_id)?;
+
+        // Ensure from account is owner
+        if owner != *from {
+            return Err(Error::<T>::NotTokenOwner.into());
+        }
+
+        // Ensure sender can transfer token
+        Self::ensure_approver_or_owner(collection_id, sender, token_id)?;
+
+        // Ensure no hook vetoes the transfer of the token or its nested tokens
+        T::TokenHooks::can_transfer(collection_id, from, to, token_id)?;
+        for descendant in Self::descendants_in(collection_id, token_id) {
+            T::TokenHooks::can_transfer(collection_id, from, to, &descendant)?;
+        }
+
+        Ok(())
+    }
+
+    // Ensure sender can burn token, returns token owner
+    fn ensure_can_burn_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        token_id: &T::Hash,
+    ) -> Result<T::AccountId, DispatchError> {
+        // Ensure token existed
+        Self::ensure_token_exists_in(collection_id, token_id)?;
+
+        // Ensure token not locked
+        Self::ensure_token_not_locked_in(collection_id, token_id)?;
+
+        // Nested token and token with nested tokens can not be burned
+        Self::ensure_token_not_nested_in(collection_id, token_id)?;
+        if !Self::children_of_in((*collection_id, *token_id)).is_empty() {
+            return Err(Error::<T>::TokenHasChildren.into());
+        }
+
+        // Ensure get token owner
+        let owner = Self::ensure_get_token_owner(collection_id, token_id)?;
+
+        // Ensure sender is token's owner
+        if *sender != owner {
+            return Err(Error::<T>::NotTokenOwner.into());
+        }
+
+        // Ensure no hook vetoes the burn
+        T::TokenHooks::can_burn(collection_id, &owner, token_id)?;
+
+        Ok(owner)
+    }
+
+    // Ensure sender can approve token to an account
+    fn ensure_can_approve_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        to: &T::AccountId,
+        token_id: &T::Hash,
+    ) -> DispatchResult {
+        // Ensure token existed
+        Self::ensure_token_exists_in(collection_id, token_id)?;
+
+        // Ensure token owner exist
+        let owner = Self::ensure_get_token_owner(collection_id, token_id)?;
+
+        // Unnecessary to approve for self
+        if *to == owner {
+            return Err(Error::<T>::OwnerAlwaysCanApprove.into());
+        }
+
+        // Ensure sender can approve
+        Self::ensure_owner_or_approved_for_all(collection_id, sender, &owner)
+    }
+
+    // Ensure limit expires after the current block and has uses
+    fn ensure_valid_limit(limit: &ApprovalLimit<T::BlockNumber>) -> DispatchResult {
+        if Self::is_within_limit(Some(*limit)) {
+            Ok(())
+        } else {
+            Err(Error::<T>::InvalidApprovalLimit.into())
+        }
+    }
+
+    // Approval without limit, or before its expiry block with uses left
+    fn is_within_limit(limit: Option<ApprovalLimit<T::BlockNumber>>) -> bool {
+        match limit {
+            Some(limit) => {
+                let expired = match limit.expiry {
+                    Some(expiry) => expiry <= <system::Module<T>>::block_number(),
+                    None => false,
+                };
+                !expired && limit.uses != Some(0)
+            }
+            None => true,
+        }
+    }
+
+    // Spend a use of the approval sender acts with, an operator approval before
+    // a token approval. The approval is removed with its last use.
+    fn spend_approval_use_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        owner: &T::AccountId,
+        token_id: &T::Hash,
+    ) {
+        if sender == owner {
+            return;
+        }
+
+        if Self::is_operator_in(collection_id, owner, sender) {
+            Self::spend_operator_use_in(collection_id, sender, owner);
+        } else if Self::is_approved_user_in(collection_id, token_id, sender) {
+            Self::spend_token_use_in(collection_id, sender, owner, token_id);
+        }
+    }
+
+    fn spend_operator_use_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        owner: &T::AccountId,
+    ) {
+        let owner_key = (*collection_id, owner.clone());
+        let mut limit = match Self::operator_approval_limit_in(&owner_key, sender) {
+            Some(limit) => limit,
+            None => return,
+        };
+
+        match limit.uses {
+            Some(uses) if uses <= 1 => {
+                <OperatorApprovals<T>>::remove(&owner_key, sender);
+                Self::remove_operator_approval_limit(collection_id, owner, sender);
+
+                Self::deposit_event(RawEvent::ApprovalExpired(
+                    *collection_id,
+                    owner.clone(),
+                    sender.clone(),
+                    None,
+                ));
+            }
+            Some(uses) => {
+                limit.uses = Some(uses - 1);
+                <OperatorApprovalLimits<T>>::insert(&owner_key, sender, limit);
+            }
+            None => (),
+        }
+    }
+
+    fn spend_token_use_in(
+        collection_id: &T::CollectionId,
+        sender: &T::AccountId,
+        owner: &T::AccountId,
+        token_id: &T::Hash,
+    ) {
+        let key = (*collection_id, *token_id);
+        let mut limit = match Self::token_approval_limit_in(key) {
+            Some(limit) => limit,
+            None => return,
+        };
+
+        match limit.uses {
+            Some(uses) if uses <= 1 => {
+                <TokenApprovals<T>>::remove(key);
+                Self::remove_token_approval_limit(key);
+
+                Self::deposit_event(RawEvent::ApprovalExpired(
+                    *collection_id,
+                    owner.clone(),
+                    sender.clone(),
+                    Some(*token_id),
+                ));
+            }
+            Some(uses) => {
+                limit.uses = Some(uses - 1);
+                <TokenApprovalLimits<T>>::insert(key, limit);
+            }
+            None => (),
+        }
+    }
+
+    // Ensure another approval can expire at the expiry block
+    fn ensure_can_expire_at(expiry: Option<T::BlockNumber>) -> DispatchResult {
+        match expiry {
+            Some(expiry)
+                if Self::approvals_expiring_at(expiry).len()
+                    >= T::MaxApprovalsExpiring::get() as usize =>
+            {
+                Err(Error::<T>::TooManyApprovalsExpiring.into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Remove the limit of a token approval and its expiry entry
+    fn remove_token_approval_limit(key: (T::CollectionId, T::Hash)) {
+        if let Some(ApprovalLimit {
+            expiry: Some(expiry),
+            ..
+        }) = <TokenApprovalLimits<T>>::take(key)
+        {
+            Self::remove_expiring_approval(expiry, &ApprovalKey::Token(key.0, key.1));
+        }
+    }
+
+    // Remove the limit of an operator approval and its expiry entry
+    fn remove_operator_approval_limit(
+        collection_id: &T::CollectionId,
+        owner: &T::AccountId,
+        operator: &T::AccountId,
+    ) {
+        if let Some(ApprovalLimit {
+            expiry: Some(expiry),
+            ..
+        }) = <OperatorApprovalLimits<T>>::take((*collection_id, owner.clone()), operator)
+        {
+            let key = ApprovalKey::Operator(*collection_id, owner.clone(), operator.clone());
+            Self::remove_expiring_approval(expiry, &key);
+        }
+    }
+
+    fn remove_expiring_approval(
+        expiry: T::BlockNumber,
+        key: &ApprovalKey<T::CollectionId, T::AccountId, T::Hash>,
+    ) {
+        <ApprovalsExpiringAt<T>>::mutate(expiry, |keys| keys.retain(|k| k != key));
+    }
+
+    // Remove an approval if it still expires at block n, it may be revoked or replaced since
+    fn expire_approval(
+        key: ApprovalKey<T::CollectionId, T::AccountId, T::Hash>,
+        n: T::BlockNumber,
+    ) {
+        match key {
+            ApprovalKey::Token(collection_id, token_id) => {
+                let key = (collection_id, token_id);
+                match Self::token_approval_limit_in(key) {
+                    Some(limit) if limit.expiry == Some(n) => (),
+                    _ => return,
+                }
+
+                <TokenApprovalLimits<T>>::remove(key);
+                let approved = <TokenApprovals<T>>::take(key);
+                if let (Some(owner), Some(approved)) = (Self::owner_of_in(key), approved) {
+                    Self::deposit_event(RawEvent::ApprovalExpired(
+                        collection_id,
+                        owner,
+                        approved,
+                        Some(token_id),
+                    ));
+                }
+            }
+            ApprovalKey::Operator(collection_id, owner, operator) => {
+                let owner_key = (collection_id, owner.clone());
+                match Self::operator_approval_limit_in(&owner_key, &operator) {
+                    Some(limit) if limit.expiry == Some(n) => (),
+                    _ => return,
+                }
+
+                <OperatorApprovals<T>>::remove(&owner_key, &operator);
+                <OperatorApprovalLimits<T>>::remove(&owner_key, &operator);
+                Self::deposit_event(RawEvent::ApprovalExpired(
+                    collection_id,
+                    owner,
+                    operator,
+                    None,
+                ));
+            }
+        }
+    }
+
+    // Ensure batch has tokens, an empty batch call would cost only the base weight
+    fn ensure_batch_not_empty(token_ids: &[T::Hash]) -> DispatchResult {
+        if token_ids.is_empty() {
+            Err(Error::<T>::EmptyBatch.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    // Ensure batch is not larger than MaxBatchSize
+    pub fn ensure_batch_size(token_ids: &[T::Hash]) -> DispatchResult {
+        if token_ids.len() > T::MaxBatchSize::get() as usize {
+            Err(Error::<T>::BatchTooLarge.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    // Ensure every token appears only once in a batch
+    pub fn ensure_no_duplicate_tokens(token_ids: &[T::Hash]) -> DispatchResult {
+        let mut seen = BTreeSet::<T::Hash>::new();
+        for token_id in token_ids {
+            if !seen.insert(*token_id) {
+                return Err(Error::<T>::DuplicateToken.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    // Ensure collection exists, the default collection always exists
+    pub fn ensure_collection_exists(collection_id: &T::CollectionId) -> DispatchResult {
+        if *collection_id == Self::default_collection()
+            || <CollectionOwner<T>>::exists(collection_id)
+        {
+            Ok(())
+        } else {
+            Err(Error::<T>::CollectionNotExisted.into())
+        }
+    }
+
+    // Ensure account is collection's owner
+    fn ensure_collection_owner(
+        collection_id: &T::CollectionId,
+        account_id: &T::AccountId,
+    ) -> DispatchResult {
+        Self::ensure_collection_exists(collection_id)?;
+
+        match Self::collection_owner(collection_id) {
+            Some(ref owner) if owner == account_id => Ok(()),
+            _ => Err(Error::<T>::NotCollectionOwner.into()),
+        }
+    }
+
+    // Anyone can mint in the default collection, only the owner in created collections
+    fn ensure_can_mint(collection_id: &T::CollectionId, sender: &T::AccountId) -> DispatchResult {
+        if *collection_id == Self::default_collection() {
+            Ok(())
+        } else {
+            Self::ensure_collection_owner(collection_id, sender)
+        }
+    }
+
+    // Ensure token exists
+    pub fn ensure_token_exists_in(
+        collection_id: &T::CollectionId,
+        token_id: &T::Hash,
+    ) -> DispatchResult {
+        if <TokenOwner<T>>::exists((*collection_id, *token_id)) {
+            Ok(())
+        } else {
+            Err(Error::<T>::TokenNotExisted.into())
+        }
+    }
+
+    // Ensure token not locked
+    pub fn ensure_token_not_locked_in(
+        collection_id: &T::CollectionId,
+        token_id: &T::Hash,
+    ) -> DispatchResult {
+        if Self::is_locked_in((*collection_id, *token_id)) {
+            Err(Error::<T>::TokenLocked.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    // Ensure token not nested in a parent
+    pub fn ensure_token_not_nested_in(
+        collection_id: &T::CollectionId,
+        token_id: &T::Hash,
+    ) -> DispatchResult {
+        if <TokenParent<T>>::exists((*collection_id, *token_id)) {
+            Err(Error::<T>::TokenNested.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    // Ensure token not existed
+    pub fn ensure_token_not_existed_in(
+        collection_id: &T::CollectionId,
+        token_id: &T::Hash,
+    ) -> DispatchResult {
+        if <TokenOwner<T>>::exists((*collection_id, *token_id)) {
+            Err(Error::<T>::TokenAlreadyExists.into())
         } else {
             Ok(())
         }
     }
 }
+
+// Single collection API on the default collection, used by nftregistry
+impl<T: Trait> Module<T> {
+    pub fn _create_token(
+        account_id: &T::AccountId,
+    ) -> sp_std::result::Result<T::Hash, DispatchError> {
+        Self::_create_token_in(&Self::default_collection(), account_id)
+    }
+
+    pub fn _mint(to: &T::AccountId, token_id: &T::Hash) -> DispatchResult {
+        Self::_mint_in(&Self::default_collection(), to, token_id)
+    }
+
+    pub fn _burn(sender: &T::AccountId, token_id: &T::Hash) -> DispatchResult {
+        Self::_burn_in(&Self::default_collection(), sender, token_id)
+    }
+
+    pub fn _transfer_from(
+        sender: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        token_id: &T::Hash,
+    ) -> DispatchResult {
+        Self::_transfer_from_in(&Self::default_collection(), sender, from, to, token_id)
+    }
+
+    pub fn _lock(token_id: &T::Hash) -> DispatchResult {
+        Self::_lock_in(&Self::default_collection(), token_id)
+    }
+
+    pub fn _unlock(token_id: &T::Hash) {
+        Self::_unlock_in(&Self::default_collection(), token_id)
+    }
+
+    pub fn _safe_transfer_from(
+        sender: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        token_id: &T::Hash,
+        data: Vec<u8>,
+    ) -> DispatchResult {
+        Self::_safe_transfer_from_in(
+            &Self::default_collection(),
+            sender,
+            from,
+            to,
+            token_id,
+            data,
+        )
+    }
+
+    pub fn _batch_transfer_from(
+        sender: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        token_ids: Vec<T::Hash>,
+    ) -> DispatchResult {
+        Self::_batch_transfer_from_in(&Self::default_collection(), sender, from, to, token_ids)
+    }
+
+    pub fn _batch_approve(
+        sender: &T::AccountId,
+        to: &T::AccountId,
+        token_ids: Vec<T::Hash>,
+    ) -> DispatchResult {
+        Self::_batch_approve_in(&Self::default_collection(), sender, to, token_ids)
+    }
+
+    pub fn _batch_burn(sender: &T::AccountId, token_ids: Vec<T::Hash>) -> DispatchResult {
+        Self::_batch_burn_in(&Self::default_collection(), sender, token_ids)
+    }
+
+    pub fn _approve(
+        sender: &T::AccountId,
+        to: &T::AccountId,
+        token_id: &T::Hash,
+    ) -> DispatchResult {
+        Self::_approve_in(&Self::default_collection(), sender, to, token_id)
+    }
+
+    pub fn _set_approval_for_all(
+        sender: &T::AccountId,
+        to: &T::AccountId,
+        approved: bool,
+    ) -> DispatchResult {
+        Self::_set_approval_for_all_in(&Self::default_collection(), sender, to, approved)
+    }
+
+    pub fn _set_collection_metadata(
+        name: Vec<u8>,
+        symbol: Vec<u8>,
+        base_uri: Vec<u8>,
+    ) -> DispatchResult {
+        Self::_set_collection_metadata_in(&Self::default_collection(), name, symbol, base_uri)
+    }
+
+    pub fn _set_token_uri(
+        sender: Option<&T::AccountId>,
+        token_id: &T::Hash,
+        uri: Vec<u8>,
+    ) -> DispatchResult {
+        Self::_set_token_uri_in(&Self::default_collection(), sender, token_id, uri)
+    }
+
+    pub fn _set_user(
+        sender: &T::AccountId,
+        token_id: &T::Hash,
+        user: &T::AccountId,
+        expires: T::BlockNumber,
+    ) -> DispatchResult {
+        Self::_set_user_in(&Self::default_collection(), sender, token_id, user, expires)
+    }
+
+    pub fn _nest(sender: &T::AccountId, child: &T::Hash, parent: &T::Hash) -> DispatchResult {
+        Self::_nest_in(&Self::default_collection(), sender, child, parent)
+    }
+
+    pub fn _unnest(sender: &T::AccountId, child: &T::Hash) -> DispatchResult {
+        Self::_unnest_in(&Self::default_collection(), sender, child)
+    }
+
+    pub fn balance_of<K: Borrow<T::AccountId>>(account_id: K) -> T::TokenIndex {
+        Self::balance_of_in(Self::default_collection(), account_id.borrow())
+    }
+
+    pub fn owner_of<K: Borrow<T::Hash>>(token_id: K) -> Option<T::AccountId> {
+        Self::owner_of_in((Self::default_collection(), *token_id.borrow()))
+    }
+
+    pub fn user_of<K: Borrow<T::Hash>>(token_id: K) -> Option<T::AccountId> {
+        Self::user_of_in(&Self::default_collection(), token_id.borrow())
+    }
+
+    pub fn parent_of<K: Borrow<T::Hash>>(token_id: K) -> Option<T::Hash> {
+        Self::parent_of_in((Self::default_collection(), *token_id.borrow()))
+    }
+
+    pub fn children_of<K: Borrow<T::Hash>>(token_id: K) -> Vec<T::Hash> {
+        Self::children_of_in((Self::default_collection(), *token_id.borrow()))
+    }
+
+    pub fn root_owner_of<K: Borrow<T::Hash>>(token_id: K) -> Option<T::AccountId> {
+        Self::root_owner_of_in(&Self::default_collection(), token_id.borrow())
+    }
+
+    pub fn get_approved<K: Borrow<T::Hash>>(token_id: K) -> Option<T::AccountId> {
+        Self::get_approved_in((Self::default_collection(), *token_id.borrow()))
+    }
+
+    pub fn is_approved_for_all<K1, K2>(owner: K1, operator: K2) -> bool
+    where
+        K1: Borrow<T::AccountId>,
+        K2: Borrow<T::AccountId>,
+    {
+        Self::is_approved_for_all_in(
+            (Self::default_collection(), owner.borrow().clone()),
+            operator.borrow(),
+        )
+    }
+
+    pub fn total_supply() -> T::TokenIndex {
+        Self::total_supply_in(Self::default_collection())
+    }
+
+    pub fn token_by_index<K: Borrow<T::TokenIndex>>(index: K) -> T::Hash {
+        Self::token_by_index_in(Self::default_collection(), index.borrow())
+    }
+
+    pub fn token_of_owner_by_index<K1, K2>(owner: K1, index: K2) -> T::Hash
+    where
+        K1: Borrow<T::AccountId>,
+        K2: Borrow<T::TokenIndex>,
+    {
+        Self::token_of_owner_by_index_in(
+            (Self::default_collection(), owner.borrow().clone()),
+            index.borrow(),
+        )
+    }
+
+    pub fn name() -> Vec<u8> {
+        Self::name_of(Self::default_collection())
+    }
+
+    pub fn symbol() -> Vec<u8> {
+        Self::symbol_of(Self::default_collection())
+    }
+
+    pub fn base_uri() -> Vec<u8> {
+        Self::base_uri_of(Self::default_collection())
+    }
+
+    pub fn token_uri_suffix<K: Borrow<T::Hash>>(token_id: K) -> Vec<u8> {
+        Self::token_uri_suffix_in((Self::default_collection(), *token_id.borrow()))
+    }
+
+    pub fn is_locked<K: Borrow<T::Hash>>(token_id: K) -> bool {
+        Self::is_locked_in((Self::default_collection(), *token_id.borrow()))
+    }
+
+    pub fn token_uri(token_id: &T::Hash) -> Option<Vec<u8>> {
+        Self::token_uri_in(&Self::default_collection(), token_id)
+    }
+
+    pub fn get_tokens_owned_account(account_id: T::AccountId) -> Vec<T::Hash> {
+        Self::get_tokens_owned_account_in(&Self::default_collection(), account_id)
+    }
+
+    pub fn check_integrity() -> Result<(), &'static str> {
+        Self::check_integrity_in(&Self::default_collection())
+    }
+
+    pub fn ensure_can_transfer(
+        sender: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        token_id: &T::Hash,
+    ) -> DispatchResult {
+        Self::ensure_can_transfer_in(&Self::default_collection(), sender, from, to, token_id)
+    }
+
+    pub fn ensure_token_exists(token_id: &T::Hash) -> DispatchResult {
+        Self::ensure_token_exists_in(&Self::default_collection(), token_id)
+    }
+
+    pub fn ensure_token_not_existed(token_id: &T::Hash) -> DispatchResult {
+        Self::ensure_token_not_existed_in(&Self::default_collection(), token_id)
+    }
+}
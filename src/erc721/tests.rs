@@ -3,6 +3,9 @@
 use super::*;
 use mock::*;
 use sp_core::H256;
+use sp_runtime::{testing::TestSignature, traits::OnInitialize};
+use std::collections::BTreeMap;
+use support::{assert_ok, traits::Get};
 
 #[test]
 fn create_token() {
@@ -159,3 +162,1658 @@ fn approve_token_self() {
         );
     });
 }
+
+#[test]
+fn balance_after_mint_and_transfer() {
+    ExtBuilder::default().build().execute_with(|| {
+        let first_token = ERC721::_create_token(&ALICE).unwrap();
+        let second_token = ERC721::_create_token(&ALICE).unwrap();
+        assert_eq!(ERC721::balance_of(ALICE), 2);
+
+        transfer_token_test(first_token, ALICE, ALICE, BOB, Ok(()));
+        assert_eq!(ERC721::balance_of(ALICE), 1);
+        assert_eq!(ERC721::balance_of(BOB), 1);
+        assert_eq!(ERC721::token_of_owner_by_index(ALICE, 0), second_token);
+        assert_eq!(ERC721::token_of_owner_by_index(BOB, 0), first_token);
+
+        burn_token_test(ALICE, second_token, Ok(()));
+        assert_eq!(ERC721::balance_of(ALICE), 0);
+        assert_eq!(ERC721::total_supply(), 1);
+        assert_eq!(ERC721::check_integrity(), Ok(()));
+    });
+}
+
+// Small xorshift generator, keeps random operation sequences reproducible by seed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+const ACCOUNTS: [u64; 4] = [ALICE, BOB, CHARLIE, DJANGO];
+
+// Check storage against the expected owner of each token
+fn assert_state_matches(model: &BTreeMap<H256, u64>) {
+    assert_eq!(ERC721::check_integrity(), Ok(()));
+    assert_eq!(ERC721::total_supply(), model.len() as u64);
+
+    for account in ACCOUNTS.iter() {
+        let owned = model.values().filter(|owner| *owner == account).count() as u64;
+        assert_eq!(ERC721::balance_of(account), owned);
+        assert_eq!(
+            ERC721::get_tokens_owned_account(*account).len() as u64,
+            owned
+        );
+    }
+
+    for (token_id, owner) in model.iter() {
+        assert_eq!(ERC721::owner_of(token_id), Some(*owner));
+    }
+}
+
+// Apply random operations by random accounts, most of them fail on purpose
+fn run_random_operations(seed: u64, steps: usize) {
+    ExtBuilder::default().build().execute_with(|| {
+        let mut rng = Rng(seed);
+        let mut model = BTreeMap::<H256, u64>::new();
+
+        for _ in 0..steps {
+            let sender = ACCOUNTS[rng.below(4) as usize];
+            let other = ACCOUNTS[rng.below(4) as usize];
+            let tokens: Vec<H256> = model.keys().cloned().collect();
+            let token_id = if tokens.is_empty() || rng.below(4) == 0 {
+                H256::from_low_u64_be(rng.below(16))
+            } else {
+                tokens[rng.below(tokens.len() as u64) as usize]
+            };
+
+            match rng.below(5) {
+                0 => {
+                    if ERC721::_mint(&sender, &token_id).is_ok() {
+                        model.insert(token_id, sender);
+                    }
+                }
+                1 => {
+                    if ERC721::_burn(&sender, &token_id).is_ok() {
+                        model.remove(&token_id);
+                    }
+                }
+                2 => {
+                    let from = model.get(&token_id).cloned().unwrap_or(sender);
+                    if ERC721::_transfer_from(&sender, &from, &other, &token_id).is_ok() {
+                        model.insert(token_id, other);
+                    }
+                }
+                3 => {
+                    let _ = ERC721::_approve(&sender, &other, &token_id);
+                }
+                _ => {
+                    let _ = ERC721::_set_approval_for_all(&sender, &other, rng.below(2) == 0);
+                }
+            }
+
+            assert_state_matches(&model);
+        }
+    });
+}
+
+#[test]
+fn random_operations_keep_storage_consistent() {
+    for seed in 1..=32 {
+        run_random_operations(seed, 200);
+    }
+}
+
+const EVE: u64 = 5;
+
+#[derive(Clone, Copy, Debug)]
+enum Action {
+    Approve,
+    Transfer,
+    Burn,
+}
+
+// ALICE owns the token, BOB is ALICE's operator, CHARLIE is approved for the token
+// and DJANGO has no approval
+fn setup_approval_matrix() -> H256 {
+    let token_id = ERC721::_create_token(&ALICE).unwrap();
+    assert_ok!(ERC721::_set_approval_for_all(&ALICE, &BOB, true));
+    assert_ok!(ERC721::_approve(&ALICE, &CHARLIE, &token_id));
+    token_id
+}
+
+fn apply_action(action: Action, sender: u64, token_id: H256) -> DispatchResult {
+    match action {
+        Action::Approve => {
+            ERC721::approve(Origin::signed(sender), DEFAULT_COLLECTION, EVE, token_id)
+        }
+        Action::Transfer => ERC721::transfer_from(
+            Origin::signed(sender),
+            DEFAULT_COLLECTION,
+            ALICE,
+            EVE,
+            token_id,
+        ),
+        Action::Burn => ERC721::burn(Origin::signed(sender), DEFAULT_COLLECTION, token_id),
+    }
+}
+
+#[test]
+fn approval_matrix() {
+    let not_owner_or_operator: DispatchResult = Err(Error::<ERC721Test>::NotOwnerOrOperator.into());
+    let not_owner_or_approver: DispatchResult = Err(Error::<ERC721Test>::NotOwnerOrApprover.into());
+    let not_owner: DispatchResult = Err(Error::<ERC721Test>::NotTokenOwner.into());
+
+    let matrix = [
+        (ALICE, Action::Approve, Ok(())),
+        (ALICE, Action::Transfer, Ok(())),
+        (ALICE, Action::Burn, Ok(())),
+        (BOB, Action::Approve, Ok(())),
+        (BOB, Action::Transfer, Ok(())),
+        (BOB, Action::Burn, not_owner),
+        (CHARLIE, Action::Approve, not_owner_or_operator),
+        (CHARLIE, Action::Transfer, Ok(())),
+        (CHARLIE, Action::Burn, not_owner),
+        (DJANGO, Action::Approve, not_owner_or_operator),
+        (DJANGO, Action::Transfer, not_owner_or_approver),
+        (DJANGO, Action::Burn, not_owner),
+    ];
+
+    for (sender, action, result) in matrix.iter() {
+        ExtBuilder::default().build().execute_with(|| {
+            let token_id = setup_approval_matrix();
+
+            assert_eq!(
+                apply_action(*action, *sender, token_id),
+                *result,
+                "sender {} {:?}",
+                sender,
+                action
+            );
+
+            if result.is_err() {
+                assert_eq!(ERC721::owner_of(token_id), Some(ALICE));
+                assert_eq!(ERC721::get_approved(token_id), Some(CHARLIE));
+            }
+            assert_eq!(ERC721::check_integrity(), Ok(()));
+        });
+    }
+}
+
+#[test]
+fn operator_of_other_account_cannot_approve() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+
+        // DJANGO makes ALICE its operator, which gives DJANGO nothing on ALICE's token
+        approve_for_all_test(DJANGO, ALICE, true, Ok(()));
+        approve_token_test(
+            DJANGO,
+            EVE,
+            token_id,
+            Err(Error::<ERC721Test>::NotOwnerOrOperator.into()),
+        );
+    });
+}
+
+#[test]
+fn collection_metadata_from_genesis() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(ERC721::name(), COLLECTION_NAME.to_vec());
+        assert_eq!(ERC721::symbol(), COLLECTION_SYMBOL.to_vec());
+        assert_eq!(ERC721::base_uri(), BASE_URI.to_vec());
+    });
+}
+
+#[test]
+fn set_collection_metadata() {
+    ExtBuilder::default().build().execute_with(|| {
+        let name = b"Other".to_vec();
+        let symbol = b"OTH".to_vec();
+        let base_uri = b"ipfs://".to_vec();
+
+        // Default collection has no owner, only root can set its metadata
+        assert!(ERC721::set_collection_metadata(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            name.clone(),
+            symbol.clone(),
+            base_uri.clone()
+        )
+        .is_err());
+        assert_ok!(ERC721::set_collection_metadata(
+            Origin::ROOT,
+            DEFAULT_COLLECTION,
+            name.clone(),
+            symbol.clone(),
+            base_uri.clone()
+        ));
+
+        assert_eq!(ERC721::name(), name);
+        assert_eq!(ERC721::symbol(), symbol);
+        assert_eq!(ERC721::base_uri(), base_uri);
+        assert!(<system::Module<ERC721Test>>::events()
+            .iter()
+            .find(|e| match e.event {
+                MetaEvent::erc721(RawEvent::CollectionMetadataSet(_, _, _, _)) => true,
+                _ => false,
+            })
+            .is_some());
+    });
+}
+
+#[test]
+fn collection_metadata_too_long() {
+    ExtBuilder::default().build().execute_with(|| {
+        let name = vec![b'n'; MaxNameLength::get() as usize + 1];
+        let symbol = vec![b's'; MaxSymbolLength::get() as usize + 1];
+        let base_uri = vec![b'u'; MaxUriLength::get() as usize + 1];
+
+        assert_eq!(
+            ERC721::set_collection_metadata(Origin::ROOT, DEFAULT_COLLECTION, name, vec![], vec![]),
+            Err(Error::<ERC721Test>::NameTooLong.into())
+        );
+        assert_eq!(
+            ERC721::set_collection_metadata(
+                Origin::ROOT,
+                DEFAULT_COLLECTION,
+                vec![],
+                symbol,
+                vec![]
+            ),
+            Err(Error::<ERC721Test>::SymbolTooLong.into())
+        );
+        assert_eq!(
+            ERC721::set_collection_metadata(
+                Origin::ROOT,
+                DEFAULT_COLLECTION,
+                vec![],
+                vec![],
+                base_uri
+            ),
+            Err(Error::<ERC721Test>::UriTooLong.into())
+        );
+        assert_eq!(ERC721::name(), COLLECTION_NAME.to_vec());
+    });
+}
+
+#[test]
+fn set_token_uri_by_owner_or_metadata_origin() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+
+        // Token without URI only has the base URI
+        assert_eq!(ERC721::token_uri(&token_id), Some(BASE_URI.to_vec()));
+
+        assert_ok!(ERC721::set_token_uri(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            token_id,
+            b"1.json".to_vec()
+        ));
+        assert_eq!(
+            ERC721::token_uri(&token_id),
+            Some(b"https://nft.example/1.json".to_vec())
+        );
+
+        // Metadata origin can overwrite any token's URI
+        assert_ok!(ERC721::set_token_uri(
+            Origin::ROOT,
+            DEFAULT_COLLECTION,
+            token_id,
+            b"2.json".to_vec()
+        ));
+        assert_eq!(
+            ERC721::token_uri(&token_id),
+            Some(b"https://nft.example/2.json".to_vec())
+        );
+        assert!(<system::Module<ERC721Test>>::events()
+            .iter()
+            .find(|e| match e.event {
+                MetaEvent::erc721(RawEvent::TokenUriSet(_, _, _)) => true,
+                _ => false,
+            })
+            .is_some());
+    });
+}
+
+#[test]
+fn set_token_uri_not_owner() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+
+        // Approvals do not allow setting the URI
+        approve_for_all_test(ALICE, BOB, true, Ok(()));
+        assert_eq!(
+            ERC721::set_token_uri(
+                Origin::signed(BOB),
+                DEFAULT_COLLECTION,
+                token_id,
+                b"1.json".to_vec()
+            ),
+            Err(Error::<ERC721Test>::NotTokenOwner.into())
+        );
+        assert_eq!(
+            ERC721::set_token_uri(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                H256::repeat_byte(1),
+                b"1.json".to_vec()
+            ),
+            Err(Error::<ERC721Test>::TokenNotExisted.into())
+        );
+        assert_eq!(
+            ERC721::set_token_uri(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                token_id,
+                vec![b'u'; MaxUriLength::get() as usize + 1]
+            ),
+            Err(Error::<ERC721Test>::UriTooLong.into())
+        );
+        assert_eq!(ERC721::token_uri_suffix(token_id), Vec::<u8>::new());
+    });
+}
+
+#[test]
+fn burn_clears_token_uri() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+        assert_ok!(ERC721::set_token_uri(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            token_id,
+            b"1.json".to_vec()
+        ));
+
+        burn_token_test(ALICE, token_id, Ok(()));
+        assert_eq!(ERC721::token_uri(&token_id), None);
+        assert_eq!(ERC721::token_uri_suffix(token_id), Vec::<u8>::new());
+    });
+}
+
+fn create_collection_test(owner: u64) -> u32 {
+    assert_ok!(ERC721::create_collection(Origin::signed(owner)));
+
+    let collection_id = ERC721::last_collection_id();
+    assert_eq!(ERC721::collection_owner(collection_id), Some(owner));
+    assert!(<system::Module<ERC721Test>>::events()
+        .iter()
+        .find(|e| match e.event {
+            MetaEvent::erc721(RawEvent::CollectionCreated(id, _)) => id == collection_id,
+            _ => false,
+        })
+        .is_some());
+
+    collection_id
+}
+
+#[test]
+fn create_collections() {
+    ExtBuilder::default().build().execute_with(|| {
+        // The default collection id is never assigned to a created collection
+        let first = create_collection_test(ALICE);
+        let second = create_collection_test(BOB);
+        assert_ne!(first, DEFAULT_COLLECTION);
+        assert_ne!(first, second);
+        assert_eq!(ERC721::collection_owner(DEFAULT_COLLECTION), None);
+
+        // No collection is created once the ids run out
+        <LastCollectionId<ERC721Test>>::put(u32::max_value());
+        assert_eq!(
+            ERC721::create_collection(Origin::signed(ALICE)),
+            Err(Error::<ERC721Test>::CollectionIdOverflow.into())
+        );
+        assert_eq!(ERC721::last_collection_id(), u32::max_value());
+    });
+}
+
+#[test]
+fn collections_have_independent_enumeration() {
+    ExtBuilder::default().build().execute_with(|| {
+        let collection_id = create_collection_test(ALICE);
+        let token_id = H256::repeat_byte(1);
+
+        // Same token id can exist in both collections
+        assert_ok!(ERC721::mint(Origin::signed(ALICE), collection_id, token_id));
+        assert_ok!(ERC721::mint(
+            Origin::signed(BOB),
+            DEFAULT_COLLECTION,
+            token_id
+        ));
+        assert_ok!(ERC721::create_token(Origin::signed(ALICE), collection_id));
+
+        assert_eq!(ERC721::total_supply_in(collection_id), 2);
+        assert_eq!(ERC721::balance_of_in(collection_id, ALICE), 2);
+        assert_eq!(ERC721::owner_of_in((collection_id, token_id)), Some(ALICE));
+        assert_eq!(ERC721::total_supply(), 1);
+        assert_eq!(ERC721::balance_of(ALICE), 0);
+        assert_eq!(ERC721::owner_of(token_id), Some(BOB));
+
+        // Transfer in one collection leaves the other untouched
+        assert_ok!(ERC721::transfer_from(
+            Origin::signed(ALICE),
+            collection_id,
+            ALICE,
+            CHARLIE,
+            token_id
+        ));
+        assert_eq!(
+            ERC721::owner_of_in((collection_id, token_id)),
+            Some(CHARLIE)
+        );
+        assert_eq!(ERC721::owner_of(token_id), Some(BOB));
+        assert_eq!(
+            ERC721::get_tokens_owned_account_in(&collection_id, CHARLIE),
+            vec![token_id]
+        );
+
+        assert_eq!(ERC721::check_integrity_in(&collection_id), Ok(()));
+        assert_eq!(ERC721::check_integrity(), Ok(()));
+    });
+}
+
+#[test]
+fn collection_approvals_are_scoped() {
+    ExtBuilder::default().build().execute_with(|| {
+        let collection_id = create_collection_test(ALICE);
+        let token_id = ERC721::_create_token_in(&collection_id, &ALICE).unwrap();
+        let default_token_id = ERC721::_create_token(&ALICE).unwrap();
+
+        // Operator of the default collection is not an operator of other collections
+        approve_for_all_test(ALICE, BOB, true, Ok(()));
+        assert_eq!(
+            ERC721::transfer_from(Origin::signed(BOB), collection_id, ALICE, BOB, token_id),
+            Err(Error::<ERC721Test>::NotOwnerOrApprover.into())
+        );
+        transfer_token_test(default_token_id, BOB, ALICE, BOB, Ok(()));
+    });
+}
+
+#[test]
+fn mint_in_collection_only_by_owner() {
+    ExtBuilder::default().build().execute_with(|| {
+        let collection_id = create_collection_test(ALICE);
+
+        assert_eq!(
+            ERC721::mint(Origin::signed(BOB), collection_id, H256::repeat_byte(1)),
+            Err(Error::<ERC721Test>::NotCollectionOwner.into())
+        );
+        assert_eq!(
+            ERC721::create_token(Origin::signed(BOB), collection_id),
+            Err(Error::<ERC721Test>::NotCollectionOwner.into())
+        );
+        assert_eq!(
+            ERC721::create_token(Origin::signed(ALICE), collection_id + 1),
+            Err(Error::<ERC721Test>::CollectionNotExisted.into())
+        );
+        assert_eq!(
+            ERC721::set_approval_for_all(Origin::signed(ALICE), collection_id + 1, BOB, true),
+            Err(Error::<ERC721Test>::CollectionNotExisted.into())
+        );
+        assert_eq!(ERC721::total_supply_in(collection_id), 0);
+    });
+}
+
+#[test]
+fn collection_metadata_by_collection_owner() {
+    ExtBuilder::default().build().execute_with(|| {
+        let collection_id = create_collection_test(ALICE);
+        let name = b"Invoices".to_vec();
+
+        assert_eq!(
+            ERC721::set_collection_metadata(
+                Origin::signed(BOB),
+                collection_id,
+                name.clone(),
+                vec![],
+                vec![]
+            ),
+            Err(Error::<ERC721Test>::NotCollectionOwner.into())
+        );
+        assert_ok!(ERC721::set_collection_metadata(
+            Origin::signed(ALICE),
+            collection_id,
+            name.clone(),
+            b"INV".to_vec(),
+            b"ipfs://".to_vec()
+        ));
+
+        // Default collection metadata is unchanged
+        assert_eq!(ERC721::name_of(collection_id), name);
+        assert_eq!(ERC721::name(), COLLECTION_NAME.to_vec());
+
+        let token_id = ERC721::_create_token_in(&collection_id, &ALICE).unwrap();
+        assert_eq!(
+            ERC721::token_uri_in(&collection_id, &token_id),
+            Some(b"ipfs://".to_vec())
+        );
+        assert_eq!(ERC721::token_uri(&token_id), None);
+    });
+}
+
+#[test]
+fn destroy_collection() {
+    ExtBuilder::default().build().execute_with(|| {
+        let collection_id = create_collection_test(ALICE);
+        let token_id = ERC721::_create_token_in(&collection_id, &ALICE).unwrap();
+
+        assert_eq!(
+            ERC721::destroy_collection(Origin::signed(BOB), collection_id),
+            Err(Error::<ERC721Test>::NotCollectionOwner.into())
+        );
+        assert_eq!(
+            ERC721::destroy_collection(Origin::signed(ALICE), collection_id),
+            Err(Error::<ERC721Test>::CollectionNotEmpty.into())
+        );
+
+        assert_ok!(ERC721::burn(Origin::signed(ALICE), collection_id, token_id));
+        assert_ok!(ERC721::destroy_collection(
+            Origin::signed(ALICE),
+            collection_id
+        ));
+        assert_eq!(ERC721::collection_owner(collection_id), None);
+        assert_eq!(
+            ERC721::create_token(Origin::signed(ALICE), collection_id),
+            Err(Error::<ERC721Test>::CollectionNotExisted.into())
+        );
+
+        // Ids of destroyed collections are not reused
+        assert_ne!(create_collection_test(ALICE), collection_id);
+    });
+}
+
+#[test]
+fn default_collection_can_not_be_destroyed() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(
+            ERC721::destroy_collection(Origin::signed(ALICE), DEFAULT_COLLECTION),
+            Err(Error::<ERC721Test>::NotCollectionOwner.into())
+        );
+    });
+}
+
+#[test]
+fn locked_token_can_not_move() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = H256::from_low_u64_be(1);
+        mint_token_test(ALICE, token_id, Ok(()));
+
+        assert_ok!(ERC721::_lock(&token_id));
+        assert!(ERC721::is_locked(token_id));
+        assert_eq!(
+            ERC721::_lock(&token_id),
+            Err(Error::<ERC721Test>::TokenLocked.into())
+        );
+
+        transfer_token_test(
+            token_id,
+            ALICE,
+            ALICE,
+            BOB,
+            Err(Error::<ERC721Test>::TokenLocked.into()),
+        );
+        burn_token_test(
+            ALICE,
+            token_id,
+            Err(Error::<ERC721Test>::TokenLocked.into()),
+        );
+
+        ERC721::_unlock(&token_id);
+        assert!(!ERC721::is_locked(token_id));
+        transfer_token_test(token_id, ALICE, ALICE, BOB, Ok(()));
+    });
+}
+
+#[test]
+fn batch_transfer_from() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_ids: Vec<H256> = (1..4).map(H256::from_low_u64_be).collect();
+        for token_id in token_ids.iter() {
+            mint_token_test(ALICE, *token_id, Ok(()));
+        }
+        mint_token_test(BOB, H256::from_low_u64_be(4), Ok(()));
+
+        // Token of other owner fails the whole batch
+        let mut mixed = token_ids.clone();
+        mixed.push(H256::from_low_u64_be(4));
+        assert_eq!(
+            ERC721::batch_transfer_from(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                ALICE,
+                CHARLIE,
+                mixed
+            ),
+            Err(Error::<ERC721Test>::NotTokenOwner.into())
+        );
+        assert_eq!(ERC721::balance_of(ALICE), 3);
+        assert_eq!(ERC721::balance_of(CHARLIE), 0);
+
+        assert_eq!(
+            ERC721::batch_transfer_from(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                ALICE,
+                CHARLIE,
+                vec![token_ids[0], token_ids[0]]
+            ),
+            Err(Error::<ERC721Test>::DuplicateToken.into())
+        );
+
+        // Operator moves all tokens at once
+        approve_for_all_test(ALICE, BOB, true, Ok(()));
+        assert_ok!(ERC721::batch_transfer_from(
+            Origin::signed(BOB),
+            DEFAULT_COLLECTION,
+            ALICE,
+            CHARLIE,
+            token_ids.clone()
+        ));
+        for token_id in token_ids.iter() {
+            assert_eq!(ERC721::owner_of(token_id), Some(CHARLIE));
+        }
+
+        let events = <system::Module<ERC721Test>>::events();
+        let transfers = events
+            .iter()
+            .filter(|e| match e.event {
+                MetaEvent::erc721(RawEvent::Transfer(_, Some(ALICE), Some(CHARLIE), _)) => true,
+                _ => false,
+            })
+            .count();
+        assert_eq!(transfers, 3);
+        assert!(events
+            .iter()
+            .find(|e| e.event
+                == MetaEvent::erc721(RawEvent::BatchTransfer(
+                    DEFAULT_COLLECTION,
+                    Some(ALICE),
+                    Some(CHARLIE),
+                    token_ids.clone()
+                )))
+            .is_some());
+
+        assert_ok!(ERC721::check_integrity());
+    });
+}
+
+#[test]
+fn batch_too_large() {
+    ExtBuilder::default().build().execute_with(|| {
+        let max = <ERC721Test as Trait>::MaxBatchSize::get() as u64;
+        let token_ids: Vec<H256> = (0..=max).map(H256::from_low_u64_be).collect();
+        for token_id in token_ids.iter() {
+            mint_token_test(ALICE, *token_id, Ok(()));
+        }
+
+        assert_eq!(
+            ERC721::batch_transfer_from(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                ALICE,
+                BOB,
+                token_ids.clone()
+            ),
+            Err(Error::<ERC721Test>::BatchTooLarge.into())
+        );
+        assert_eq!(
+            ERC721::batch_approve(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                BOB,
+                token_ids.clone()
+            ),
+            Err(Error::<ERC721Test>::BatchTooLarge.into())
+        );
+        assert_eq!(
+            ERC721::batch_burn(Origin::signed(ALICE), DEFAULT_COLLECTION, token_ids.clone()),
+            Err(Error::<ERC721Test>::BatchTooLarge.into())
+        );
+
+        assert_ok!(ERC721::batch_burn(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            token_ids[1..].to_vec()
+        ));
+        assert_eq!(ERC721::balance_of(ALICE), 1);
+    });
+}
+
+#[test]
+fn batch_approve() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_ids: Vec<H256> = (1..3).map(H256::from_low_u64_be).collect();
+        for token_id in token_ids.iter() {
+            mint_token_test(ALICE, *token_id, Ok(()));
+        }
+
+        assert_eq!(
+            ERC721::batch_approve(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                ALICE,
+                token_ids.clone()
+            ),
+            Err(Error::<ERC721Test>::OwnerAlwaysCanApprove.into())
+        );
+        assert_eq!(
+            ERC721::batch_approve(
+                Origin::signed(BOB),
+                DEFAULT_COLLECTION,
+                CHARLIE,
+                token_ids.clone()
+            ),
+            Err(Error::<ERC721Test>::NotOwnerOrOperator.into())
+        );
+        assert_eq!(ERC721::get_approved(token_ids[0]), None);
+
+        assert_ok!(ERC721::batch_approve(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            BOB,
+            token_ids.clone()
+        ));
+        for token_id in token_ids.iter() {
+            assert_eq!(ERC721::get_approved(token_id), Some(BOB));
+        }
+    });
+}
+
+#[test]
+fn batch_burn() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_ids: Vec<H256> = (1..4).map(H256::from_low_u64_be).collect();
+        for token_id in token_ids.iter() {
+            mint_token_test(ALICE, *token_id, Ok(()));
+        }
+        assert_ok!(ERC721::_lock(&token_ids[2]));
+
+        // Locked token fails the whole batch
+        assert_eq!(
+            ERC721::batch_burn(Origin::signed(ALICE), DEFAULT_COLLECTION, token_ids.clone()),
+            Err(Error::<ERC721Test>::TokenLocked.into())
+        );
+        assert_eq!(ERC721::total_supply(), 3);
+
+        assert_ok!(ERC721::batch_burn(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            token_ids[..2].to_vec()
+        ));
+        assert_eq!(ERC721::total_supply(), 1);
+        assert_eq!(ERC721::owner_of(token_ids[0]), None);
+        assert_ok!(ERC721::check_integrity());
+    });
+}
+
+#[test]
+fn batch_weight_is_linear() {
+    let weight = BatchWeight(5_000, 10_000);
+    let token_ids: Vec<H256> = (0..3).map(H256::from_low_u64_be).collect();
+
+    assert_eq!(
+        weight.weigh_data((&DEFAULT_COLLECTION, &Vec::<H256>::new())),
+        5_000
+    );
+    assert_eq!(weight.weigh_data((&DEFAULT_COLLECTION, &token_ids)), 35_000);
+    assert_eq!(
+        weight.weigh_data((&DEFAULT_COLLECTION, &ALICE, &BOB, &token_ids)),
+        35_000
+    );
+}
+
+#[test]
+fn empty_batch_rejected() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(
+            ERC721::batch_transfer_from(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                ALICE,
+                BOB,
+                vec![]
+            ),
+            Err(Error::<ERC721Test>::EmptyBatch.into())
+        );
+        assert_eq!(
+            ERC721::batch_approve(Origin::signed(ALICE), DEFAULT_COLLECTION, BOB, vec![]),
+            Err(Error::<ERC721Test>::EmptyBatch.into())
+        );
+        assert_eq!(
+            ERC721::batch_burn(Origin::signed(ALICE), DEFAULT_COLLECTION, vec![]),
+            Err(Error::<ERC721Test>::EmptyBatch.into())
+        );
+    });
+}
+
+#[test]
+fn safe_transfer_from() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = H256::from_low_u64_be(1);
+        mint_token_test(ALICE, token_id, Ok(()));
+        approve_token_test(ALICE, CHARLIE, token_id, Ok(()));
+        take_hook_calls();
+
+        // Rejected token never leaves the owner, it keeps its approval
+        assert_eq!(
+            ERC721::safe_transfer_from(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                ALICE,
+                REJECTING_RECEIVER,
+                token_id,
+                vec![]
+            ),
+            Err(Error::<ERC721Test>::TransferRejected.into())
+        );
+        assert_eq!(ERC721::owner_of(token_id), Some(ALICE));
+        assert_eq!(ERC721::get_approved(token_id), Some(CHARLIE));
+        assert_eq!(ERC721::balance_of(REJECTING_RECEIVER), 0);
+        assert_eq!(take_hook_calls(), vec![]);
+        assert_ok!(ERC721::check_integrity());
+
+        assert_ok!(ERC721::safe_transfer_from(
+            Origin::signed(CHARLIE),
+            DEFAULT_COLLECTION,
+            ALICE,
+            BOB,
+            token_id,
+            b"data".to_vec()
+        ));
+        assert_eq!(ERC721::owner_of(token_id), Some(BOB));
+        assert_eq!(ERC721::get_approved(token_id), None);
+    });
+}
+
+#[test]
+fn hooks_observe_operations() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = H256::from_low_u64_be(1);
+        take_hook_calls();
+
+        mint_token_test(ALICE, token_id, Ok(()));
+        transfer_token_test(token_id, ALICE, ALICE, BOB, Ok(()));
+        burn_token_test(BOB, token_id, Ok(()));
+
+        assert_eq!(
+            take_hook_calls(),
+            vec![
+                HookCall::Mint(ALICE, token_id),
+                HookCall::Transfer(ALICE, BOB, token_id),
+                HookCall::Burn(BOB, token_id),
+            ]
+        );
+    });
+}
+
+#[test]
+fn hooks_veto_operations() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_ids: Vec<H256> = (1..3).map(H256::from_low_u64_be).collect();
+        mint_token_test(BLOCKED, token_ids[0], Err("blocked account".into()));
+        for token_id in token_ids.iter() {
+            mint_token_test(ALICE, *token_id, Ok(()));
+        }
+        take_hook_calls();
+
+        transfer_token_test(
+            token_ids[0],
+            ALICE,
+            ALICE,
+            BLOCKED,
+            Err("blocked account".into()),
+        );
+        assert_eq!(
+            ERC721::batch_transfer_from(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                ALICE,
+                BLOCKED,
+                token_ids.clone()
+            ),
+            Err("blocked account".into())
+        );
+
+        // Vetoed operations change nothing and are not observed
+        assert_eq!(ERC721::balance_of(ALICE), 2);
+        assert_eq!(take_hook_calls(), vec![]);
+    });
+}
+
+fn approval_expired(owner: u64, approved: u64, token_id: Option<H256>) -> bool {
+    <system::Module<ERC721Test>>::events()
+        .iter()
+        .find(|e| match e.event {
+            MetaEvent::erc721(RawEvent::ApprovalExpired(_, o, a, t)) => {
+                o == owner && a == approved && t == token_id
+            }
+            _ => false,
+        })
+        .is_some()
+}
+
+#[test]
+fn approval_limit_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+        <system::Module<ERC721Test>>::set_block_number(5);
+
+        assert_eq!(
+            ERC721::approve_with_limit(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                BOB,
+                token_id,
+                Some(5),
+                None
+            ),
+            Err(Error::<ERC721Test>::InvalidApprovalLimit.into())
+        );
+        assert_eq!(
+            ERC721::set_approval_for_all_with_limit(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                BOB,
+                None,
+                Some(0)
+            ),
+            Err(Error::<ERC721Test>::InvalidApprovalLimit.into())
+        );
+        assert_eq!(ERC721::get_approved(token_id), None);
+        assert!(!ERC721::is_approved_for_all(ALICE, BOB));
+    });
+}
+
+#[test]
+fn token_approval_expires() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+        assert_ok!(ERC721::approve_with_limit(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            BOB,
+            token_id,
+            Some(10),
+            None
+        ));
+        assert!(ERC721::is_approved_user_in(
+            &DEFAULT_COLLECTION,
+            &token_id,
+            &BOB
+        ));
+
+        // Approval is not valid from its expiry block, even before cleanup
+        <system::Module<ERC721Test>>::set_block_number(10);
+        assert!(!ERC721::is_approved_user_in(
+            &DEFAULT_COLLECTION,
+            &token_id,
+            &BOB
+        ));
+        transfer_token_test(
+            token_id,
+            BOB,
+            ALICE,
+            BOB,
+            Err(Error::<ERC721Test>::NotOwnerOrApprover.into()),
+        );
+
+        ERC721::on_initialize(10);
+        assert_eq!(ERC721::get_approved(token_id), None);
+        assert_eq!(
+            ERC721::token_approval_limit_in((DEFAULT_COLLECTION, token_id)),
+            None
+        );
+        assert!(ERC721::approvals_expiring_at(10).is_empty());
+        assert!(approval_expired(ALICE, BOB, Some(token_id)));
+    });
+}
+
+#[test]
+fn replaced_approval_does_not_expire() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+        assert_ok!(ERC721::approve_with_limit(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            BOB,
+            token_id,
+            Some(10),
+            None
+        ));
+
+        // A plain approval replaces the limited one and stays after its expiry block
+        approve_token_test(ALICE, CHARLIE, token_id, Ok(()));
+        ERC721::on_initialize(10);
+        assert_eq!(ERC721::get_approved(token_id), Some(CHARLIE));
+        assert!(!approval_expired(ALICE, CHARLIE, Some(token_id)));
+    });
+}
+
+#[test]
+fn token_approval_uses() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+        assert_ok!(ERC721::approve_with_limit(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            BOB,
+            token_id,
+            None,
+            Some(2)
+        ));
+
+        assert_ok!(ERC721::set_user(
+            Origin::signed(BOB),
+            DEFAULT_COLLECTION,
+            token_id,
+            CHARLIE,
+            10
+        ));
+        assert_eq!(
+            ERC721::token_approval_limit_in((DEFAULT_COLLECTION, token_id))
+                .unwrap()
+                .uses,
+            Some(1)
+        );
+
+        // Last use removes the approval
+        assert_ok!(ERC721::set_user(
+            Origin::signed(BOB),
+            DEFAULT_COLLECTION,
+            token_id,
+            DJANGO,
+            10
+        ));
+        assert_eq!(ERC721::get_approved(token_id), None);
+        assert!(approval_expired(ALICE, BOB, Some(token_id)));
+        transfer_token_test(
+            token_id,
+            BOB,
+            ALICE,
+            BOB,
+            Err(Error::<ERC721Test>::NotOwnerOrApprover.into()),
+        );
+    });
+}
+
+#[test]
+fn reapproval_replaces_expiry() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+        for _ in 0..2 {
+            assert_ok!(ERC721::approve_with_limit(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                BOB,
+                token_id,
+                Some(10),
+                None
+            ));
+        }
+        assert_eq!(ERC721::approvals_expiring_at(10).len(), 1);
+
+        assert_ok!(ERC721::approve_with_limit(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            BOB,
+            token_id,
+            Some(12),
+            None
+        ));
+        assert!(ERC721::approvals_expiring_at(10).is_empty());
+        assert_eq!(ERC721::approvals_expiring_at(12).len(), 1);
+
+        // Transfer clears the approval with its expiry
+        transfer_token_test(token_id, BOB, ALICE, CHARLIE, Ok(()));
+        assert!(ERC721::approvals_expiring_at(12).is_empty());
+    });
+}
+
+#[test]
+fn approvals_expiring_per_block_are_capped() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_ids: Vec<H256> = (1..6).map(H256::from_low_u64_be).collect();
+        for token_id in token_ids.iter() {
+            mint_token_test(ALICE, *token_id, Ok(()));
+        }
+        for token_id in token_ids[..4].iter() {
+            assert_ok!(ERC721::approve_with_limit(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                BOB,
+                *token_id,
+                Some(10),
+                None
+            ));
+        }
+
+        assert_eq!(
+            ERC721::approve_with_limit(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                BOB,
+                token_ids[4],
+                Some(10),
+                None
+            ),
+            Err(Error::<ERC721Test>::TooManyApprovalsExpiring.into())
+        );
+        assert_eq!(
+            ERC721::set_approval_for_all_with_limit(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                BOB,
+                Some(10),
+                None
+            ),
+            Err(Error::<ERC721Test>::TooManyApprovalsExpiring.into())
+        );
+        assert_eq!(ERC721::get_approved(token_ids[4]), None);
+        assert!(!ERC721::is_approved_for_all(ALICE, BOB));
+
+        // Another block still has room
+        assert_ok!(ERC721::approve_with_limit(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            BOB,
+            token_ids[4],
+            Some(11),
+            None
+        ));
+    });
+}
+
+#[test]
+fn operator_approval_uses() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_ids: Vec<H256> = (1..5).map(H256::from_low_u64_be).collect();
+        for token_id in token_ids.iter() {
+            mint_token_test(ALICE, *token_id, Ok(()));
+        }
+        assert_ok!(ERC721::set_approval_for_all_with_limit(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            BOB,
+            None,
+            Some(2)
+        ));
+
+        // A batch needs a use per token
+        assert_eq!(
+            ERC721::batch_transfer_from(
+                Origin::signed(BOB),
+                DEFAULT_COLLECTION,
+                ALICE,
+                CHARLIE,
+                token_ids[..3].to_vec()
+            ),
+            Err(Error::<ERC721Test>::NotEnoughApprovalUses.into())
+        );
+        assert_eq!(ERC721::balance_of(ALICE), 4);
+
+        transfer_token_test(token_ids[0], BOB, ALICE, CHARLIE, Ok(()));
+        assert_eq!(
+            ERC721::operator_approval_limit_in((DEFAULT_COLLECTION, ALICE), BOB)
+                .unwrap()
+                .uses,
+            Some(1)
+        );
+
+        // Last use removes the approval
+        transfer_token_test(token_ids[1], BOB, ALICE, CHARLIE, Ok(()));
+        assert!(!ERC721::is_approved_for_all(ALICE, BOB));
+        assert!(approval_expired(ALICE, BOB, None));
+        transfer_token_test(
+            token_ids[2],
+            BOB,
+            ALICE,
+            CHARLIE,
+            Err(Error::<ERC721Test>::NotOwnerOrApprover.into()),
+        );
+
+        // Owner transfers spend no uses
+        assert_ok!(ERC721::set_approval_for_all_with_limit(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            BOB,
+            None,
+            Some(1)
+        ));
+        transfer_token_test(token_ids[2], ALICE, ALICE, CHARLIE, Ok(()));
+        assert!(ERC721::is_operator_in(&DEFAULT_COLLECTION, &ALICE, &BOB));
+    });
+}
+
+#[test]
+fn operator_approval_expires() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+        assert_ok!(ERC721::set_approval_for_all_with_limit(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            BOB,
+            Some(10),
+            None
+        ));
+
+        // An expired operator can not approve either
+        <system::Module<ERC721Test>>::set_block_number(10);
+        assert_eq!(
+            ERC721::approve(Origin::signed(BOB), DEFAULT_COLLECTION, CHARLIE, token_id),
+            Err(Error::<ERC721Test>::NotOwnerOrOperator.into())
+        );
+
+        ERC721::on_initialize(10);
+        assert!(!ERC721::is_approved_for_all(ALICE, BOB));
+        assert_eq!(
+            ERC721::operator_approval_limit_in((DEFAULT_COLLECTION, ALICE), BOB),
+            None
+        );
+        assert!(approval_expired(ALICE, BOB, None));
+
+        // Plain approval has no limits
+        approve_for_all_test(ALICE, BOB, true, Ok(()));
+        <system::Module<ERC721Test>>::set_block_number(20);
+        transfer_token_test(token_id, BOB, ALICE, CHARLIE, Ok(()));
+    });
+}
+
+fn permit_signature(
+    signer: u64,
+    spender: u64,
+    token_id: H256,
+    deadline: u64,
+    nonce: u64,
+) -> TestSignature {
+    TestSignature(
+        signer,
+        ERC721::permit_payload(
+            &DEFAULT_COLLECTION,
+            &ALICE,
+            &spender,
+            &token_id,
+            deadline,
+            nonce,
+        ),
+    )
+}
+
+fn permit_test(
+    spender: u64,
+    token_id: H256,
+    deadline: u64,
+    nonce: u64,
+    signature: TestSignature,
+    result: DispatchResult,
+) {
+    assert_eq!(
+        ERC721::permit(
+            Origin::signed(CHARLIE),
+            DEFAULT_COLLECTION,
+            ALICE,
+            spender,
+            token_id,
+            deadline,
+            nonce,
+            signature
+        ),
+        result
+    );
+}
+
+#[test]
+fn permit_approves_token() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+
+        // Any account submits a permit signed by the owner
+        let signature = permit_signature(ALICE, BOB, token_id, 10, 0);
+        permit_test(BOB, token_id, 10, 0, signature.clone(), Ok(()));
+        assert_eq!(ERC721::get_approved(token_id), Some(BOB));
+        assert_eq!(ERC721::permit_nonce(ALICE), 1);
+
+        // Spent nonce can not be replayed
+        permit_test(
+            BOB,
+            token_id,
+            10,
+            0,
+            signature,
+            Err(Error::<ERC721Test>::InvalidPermitNonce.into()),
+        );
+
+        transfer_token_test(token_id, BOB, ALICE, BOB, Ok(()));
+    });
+}
+
+#[test]
+fn permit_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+
+        // Signed by other account
+        permit_test(
+            BOB,
+            token_id,
+            10,
+            0,
+            permit_signature(BOB, BOB, token_id, 10, 0),
+            Err(Error::<ERC721Test>::InvalidPermitSignature.into()),
+        );
+
+        // Signed for other spender or deadline
+        permit_test(
+            CHARLIE,
+            token_id,
+            10,
+            0,
+            permit_signature(ALICE, BOB, token_id, 10, 0),
+            Err(Error::<ERC721Test>::InvalidPermitSignature.into()),
+        );
+        permit_test(
+            BOB,
+            token_id,
+            20,
+            0,
+            permit_signature(ALICE, BOB, token_id, 10, 0),
+            Err(Error::<ERC721Test>::InvalidPermitSignature.into()),
+        );
+
+        // Nonce must be the next one
+        permit_test(
+            BOB,
+            token_id,
+            10,
+            1,
+            permit_signature(ALICE, BOB, token_id, 10, 1),
+            Err(Error::<ERC721Test>::InvalidPermitNonce.into()),
+        );
+
+        <system::Module<ERC721Test>>::set_block_number(11);
+        permit_test(
+            BOB,
+            token_id,
+            10,
+            0,
+            permit_signature(ALICE, BOB, token_id, 10, 0),
+            Err(Error::<ERC721Test>::PermitExpired.into()),
+        );
+
+        // Signer must be able to approve the token, the nonce is not spent
+        transfer_token_test(token_id, ALICE, ALICE, DJANGO, Ok(()));
+        permit_test(
+            BOB,
+            token_id,
+            20,
+            0,
+            permit_signature(ALICE, BOB, token_id, 20, 0),
+            Err(Error::<ERC721Test>::NotOwnerOrOperator.into()),
+        );
+        assert_eq!(ERC721::get_approved(token_id), None);
+        assert_eq!(ERC721::permit_nonce(ALICE), 0);
+    });
+}
+
+#[test]
+fn set_user_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(
+            ERC721::set_user(
+                Origin::signed(ALICE),
+                DEFAULT_COLLECTION,
+                H256::repeat_byte(1),
+                BOB,
+                10
+            ),
+            Err(Error::<ERC721Test>::TokenNotExisted.into())
+        );
+
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+        assert_eq!(
+            ERC721::set_user(Origin::signed(BOB), DEFAULT_COLLECTION, token_id, BOB, 10),
+            Err(Error::<ERC721Test>::NotOwnerOrApprover.into())
+        );
+
+        // Approved account can set the user
+        assert_ok!(ERC721::_approve(&ALICE, &BOB, &token_id));
+        assert_ok!(ERC721::set_user(
+            Origin::signed(BOB),
+            DEFAULT_COLLECTION,
+            token_id,
+            CHARLIE,
+            10
+        ));
+        assert_eq!(ERC721::user_of(token_id), Some(CHARLIE));
+        assert_eq!(ERC721::owner_of(token_id), Some(ALICE));
+    });
+}
+
+#[test]
+fn user_expires() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+        assert_eq!(ERC721::user_of(token_id), None);
+
+        assert_ok!(ERC721::set_user(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            token_id,
+            BOB,
+            10
+        ));
+        assert_eq!(ERC721::user_of(token_id), Some(BOB));
+        assert!(<system::Module<ERC721Test>>::events()
+            .iter()
+            .find(|e| match e.event {
+                MetaEvent::erc721(RawEvent::TokenUserSet(_, t, u, 10)) => t == token_id && u == BOB,
+                _ => false,
+            })
+            .is_some());
+
+        // User has no rights from its expiry block
+        <system::Module<ERC721Test>>::set_block_number(10);
+        assert_eq!(ERC721::user_of(token_id), None);
+
+        // Expiry already passed removes the user
+        assert_ok!(ERC721::set_user(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            token_id,
+            BOB,
+            10
+        ));
+        assert_eq!(ERC721::token_user_in((DEFAULT_COLLECTION, token_id)), None);
+    });
+}
+
+#[test]
+fn user_cleared_on_transfer_and_burn() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = ERC721::_create_token(&ALICE).unwrap();
+        assert_ok!(ERC721::_set_user(&ALICE, &token_id, &BOB, 10));
+
+        transfer_token_test(token_id, ALICE, ALICE, CHARLIE, Ok(()));
+        assert_eq!(ERC721::user_of(token_id), None);
+
+        assert_ok!(ERC721::_set_user(&CHARLIE, &token_id, &BOB, 10));
+        burn_token_test(CHARLIE, token_id, Ok(()));
+        assert_eq!(ERC721::token_user_in((DEFAULT_COLLECTION, token_id)), None);
+    });
+}
+
+fn nest_test(sender: u64, child: H256, parent: H256, result: DispatchResult) {
+    assert_eq!(
+        ERC721::nest(Origin::signed(sender), DEFAULT_COLLECTION, child, parent),
+        result
+    );
+
+    if result.is_ok() {
+        assert_eq!(ERC721::parent_of(child), Some(parent));
+        assert!(ERC721::children_of(parent).contains(&child));
+    }
+}
+
+#[test]
+fn nest_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        let parent = ERC721::_create_token(&ALICE).unwrap();
+        let child = ERC721::_create_token(&ALICE).unwrap();
+        let other = ERC721::_create_token(&BOB).unwrap();
+
+        nest_test(
+            ALICE,
+            H256::repeat_byte(1),
+            parent,
+            Err(Error::<ERC721Test>::TokenNotExisted.into()),
+        );
+        nest_test(
+            ALICE,
+            child,
+            other,
+            Err(Error::<ERC721Test>::NotTokenOwner.into()),
+        );
+        nest_test(
+            BOB,
+            child,
+            parent,
+            Err(Error::<ERC721Test>::NotTokenOwner.into()),
+        );
+        nest_test(
+            ALICE,
+            child,
+            child,
+            Err(Error::<ERC721Test>::NestingCycle.into()),
+        );
+
+        nest_test(ALICE, child, parent, Ok(()));
+        nest_test(
+            ALICE,
+            child,
+            parent,
+            Err(Error::<ERC721Test>::TokenNested.into()),
+        );
+
+        // Parent can not be nested in its own child
+        nest_test(
+            ALICE,
+            parent,
+            child,
+            Err(Error::<ERC721Test>::NestingCycle.into()),
+        );
+    });
+}
+
+#[test]
+fn nesting_depth_is_limited() {
+    ExtBuilder::default().build().execute_with(|| {
+        let max_depth = MaxNestingDepth::get() as usize;
+        let tokens: Vec<H256> = (0..max_depth + 2)
+            .map(|_| ERC721::_create_token(&ALICE).unwrap())
+            .collect();
+
+        // A chain of tokens, each nested in the previous one, up to the max depth
+        for depth in 1..=max_depth {
+            nest_test(ALICE, tokens[depth], tokens[depth - 1], Ok(()));
+        }
+        nest_test(
+            ALICE,
+            tokens[max_depth + 1],
+            tokens[max_depth],
+            Err(Error::<ERC721Test>::NestingTooDeep.into()),
+        );
+
+        // Nesting a token with children counts their levels too
+        assert_ok!(ERC721::_unnest(&ALICE, &tokens[max_depth]));
+        nest_test(ALICE, tokens[max_depth + 1], tokens[max_depth], Ok(()));
+        nest_test(
+            ALICE,
+            tokens[max_depth],
+            tokens[max_depth - 1],
+            Err(Error::<ERC721Test>::NestingTooDeep.into()),
+        );
+    });
+}
+
+#[test]
+fn transfer_carries_nested_tokens() {
+    ExtBuilder::default().build().execute_with(|| {
+        let order = ERC721::_create_token(&ALICE).unwrap();
+        let invoice = ERC721::_create_token(&ALICE).unwrap();
+        let note = ERC721::_create_token(&ALICE).unwrap();
+        nest_test(ALICE, invoice, order, Ok(()));
+        nest_test(ALICE, note, invoice, Ok(()));
+        assert_eq!(ERC721::root_owner_of(note), Some(ALICE));
+
+        // Nested tokens only move with their parent
+        transfer_token_test(
+            invoice,
+            ALICE,
+            ALICE,
+            BOB,
+            Err(Error::<ERC721Test>::TokenNested.into()),
+        );
+        burn_token_test(ALICE, note, Err(Error::<ERC721Test>::TokenNested.into()));
+        burn_token_test(
+            ALICE,
+            order,
+            Err(Error::<ERC721Test>::TokenHasChildren.into()),
+        );
+
+        transfer_token_test(order, ALICE, ALICE, BOB, Ok(()));
+        for token_id in &[order, invoice, note] {
+            assert_eq!(ERC721::owner_of(token_id), Some(BOB));
+            assert_eq!(ERC721::root_owner_of(token_id), Some(BOB));
+        }
+        assert_eq!(ERC721::balance_of(ALICE), 0);
+        assert_eq!(ERC721::balance_of(BOB), 3);
+        assert_eq!(ERC721::children_of(order), vec![invoice]);
+    });
+}
+
+#[test]
+fn unnest_token() {
+    ExtBuilder::default().build().execute_with(|| {
+        let parent = ERC721::_create_token(&ALICE).unwrap();
+        let child = ERC721::_create_token(&ALICE).unwrap();
+        nest_test(ALICE, child, parent, Ok(()));
+
+        assert_eq!(
+            ERC721::unnest(Origin::signed(BOB), DEFAULT_COLLECTION, child),
+            Err(Error::<ERC721Test>::NotTokenOwner.into())
+        );
+        assert_ok!(ERC721::unnest(
+            Origin::signed(ALICE),
+            DEFAULT_COLLECTION,
+            child
+        ));
+        assert_eq!(ERC721::parent_of(child), None);
+        assert!(ERC721::children_of(parent).is_empty());
+        assert!(<system::Module<ERC721Test>>::events()
+            .iter()
+            .find(|e| match e.event {
+                MetaEvent::erc721(RawEvent::Unnested(_, c, p)) => c == child && p == parent,
+                _ => false,
+            })
+            .is_some());
+        assert_eq!(
+            ERC721::unnest(Origin::signed(ALICE), DEFAULT_COLLECTION, child),
+            Err(Error::<ERC721Test>::TokenNotNested.into())
+        );
+
+        // Unnested token moves on its own again
+        transfer_token_test(child, ALICE, ALICE, BOB, Ok(()));
+        assert_eq!(ERC721::owner_of(parent), Some(ALICE));
+    });
+}
@@ -0,0 +1,181 @@
+use super::*;
+
+use sp_core::H256;
+use sp_runtime::{
+    testing::{Header, TestSignature, UintAuthorityId},
+    traits::{BlakeTwo256, IdentityLookup},
+    Perbill,
+};
+use support::{impl_outer_event, impl_outer_origin, parameter_types, weights::Weight};
+
+pub mod fractional {
+    pub use super::super::*;
+}
+
+#[derive(Eq, Clone, PartialEq)]
+pub struct FractionalTest;
+
+impl_outer_origin! {
+    pub enum Origin for FractionalTest {}
+}
+
+impl_outer_event! {
+    pub enum MetaEvent for FractionalTest {
+        erc721<T>, erc1155<T>, fractional<T>,
+    }
+}
+
+type BlockNumber = u64;
+
+parameter_types! {
+    pub const BlockHashCount: BlockNumber = 250;
+    pub const MaximumBlockWeight: Weight = 1_000_000;
+    pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    pub const MaximumBlockLength: u32 = 5 * 1024 * 1024;
+}
+
+impl system::Trait for FractionalTest {
+    type Call = ();
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Index = u64;
+    type BlockNumber = BlockNumber;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type Header = Header;
+    type Event = MetaEvent;
+    type Origin = Origin;
+    type BlockHashCount = BlockHashCount;
+    type MaximumBlockWeight = MaximumBlockWeight;
+    type MaximumBlockLength = MaximumBlockLength;
+    type AvailableBlockRatio = AvailableBlockRatio;
+    type Version = ();
+    type ModuleToIndex = ();
+}
+
+parameter_types! {
+    pub const MaxNameLength: u32 = 64;
+    pub const MaxSymbolLength: u32 = 16;
+    pub const MaxUriLength: u32 = 256;
+    pub const MaxBatchSize: u32 = 100;
+    pub const MaxNestingDepth: u32 = 8;
+    pub const MaxApprovalsExpiring: u32 = 100;
+}
+
+impl erc721::Trait for FractionalTest {
+    type Event = MetaEvent;
+    type Randomness = randomness_collective_flip::Module<FractionalTest>;
+    type TokenIndex = u64;
+    type CollectionId = u32;
+    type MetadataOrigin = system::EnsureRoot<u64>;
+    type MaxNameLength = MaxNameLength;
+    type MaxSymbolLength = MaxSymbolLength;
+    type MaxUriLength = MaxUriLength;
+    type MaxBatchSize = MaxBatchSize;
+    type MaxNestingDepth = MaxNestingDepth;
+    type MaxApprovalsExpiring = MaxApprovalsExpiring;
+    type TokenReceiver = ();
+    type TokenHooks = BlockingHooks;
+    type Signature = TestSignature;
+    type Signer = UintAuthorityId;
+}
+
+impl erc1155::Trait for FractionalTest {
+    type Event = MetaEvent;
+    type TokenBalance = u64;
+    type TokenHooks = ();
+}
+
+impl Trait for FractionalTest {
+    type Event = MetaEvent;
+}
+
+#[derive(Default)]
+pub struct ExtBuilder {}
+
+impl ExtBuilder {
+    pub fn build(self) -> sp_io::TestExternalities {
+        let t = system::GenesisConfig::default()
+            .build_storage::<FractionalTest>()
+            .unwrap();
+
+        sp_io::TestExternalities::new(t)
+    }
+}
+
+pub type ERC721 = erc721::Module<FractionalTest>;
+pub type ERC1155 = erc1155::Module<FractionalTest>;
+pub type Fractional = Module<FractionalTest>;
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+
+// Account tokens can not be transferred to
+pub const BLOCKED: u64 = 10;
+
+// Vetoes tokens going to the blocked account
+pub struct BlockingHooks;
+
+impl erc721::OnTokenTransfer<u32, u64, H256> for BlockingHooks {
+    fn can_transfer(_: &u32, _: &u64, to: &u64, _: &H256) -> DispatchResult {
+        if *to == BLOCKED {
+            Err("blocked account".into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl erc721::OnMint<u32, u64, H256> for BlockingHooks {}
+
+impl erc721::OnBurn<u32, u64, H256> for BlockingHooks {}
+
+pub const SHARES: u64 = 100;
+
+pub fn token_id(byte: u8) -> H256 {
+    H256::repeat_byte(byte)
+}
+
+// Fractionalise a token and return its share id
+pub fn fractionalise_test(
+    sender: u64,
+    token_id: H256,
+    shares: u64,
+    result: DispatchResult,
+) -> Option<H256> {
+    assert_eq!(
+        Fractional::fractionalise(Origin::signed(sender), token_id, shares),
+        result
+    );
+
+    if result.is_err() {
+        return None;
+    }
+
+    let (share_id, minted) = Fractional::fractions(token_id).unwrap();
+    assert_eq!(minted, shares);
+    assert_eq!(ERC1155::balance_of(sender, share_id), shares);
+    assert_eq!(ERC721::owner_of(token_id), Some(Fractional::account_id()));
+    assert!(ERC721::is_locked(token_id));
+
+    assert!(<system::Module<FractionalTest>>::events()
+        .iter()
+        .find(|e| match e.event {
+            MetaEvent::fractional(RawEvent::Fractionalised(_, _, _, _)) => true,
+            _ => false,
+        })
+        .is_some());
+
+    Some(share_id)
+}
+
+pub fn redeem_test(sender: u64, token_id: H256, result: DispatchResult) {
+    assert_eq!(Fractional::redeem(Origin::signed(sender), token_id), result);
+
+    if result.is_ok() {
+        assert_eq!(Fractional::fractions(token_id), None);
+        assert_eq!(ERC721::owner_of(token_id), Some(sender));
+        assert!(!ERC721::is_locked(token_id));
+    }
+}
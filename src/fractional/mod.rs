@@ -0,0 +1,166 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+use sp_runtime::traits::{AccountIdConversion, Hash as HashT, Zero};
+use sp_runtime::ModuleId;
+use support::{decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult};
+use system::ensure_signed;
+
+use crate::{erc1155, erc721};
+
+// Encoding library
+use codec::Encode;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+// Vault account holding the locked tokens
+const MODULE_ID: ModuleId = ModuleId(*b"nft/frac");
+
+pub trait Trait: erc721::Trait + erc1155::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+}
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        // Token needs at least one share
+        ZeroShares,
+
+        // Token not fractionalised
+        NotFractionalised,
+
+        // Redeem needs all shares of the token
+        NotAllShares,
+
+        // Share id already minted
+        SharesAlreadyExist,
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+        where
+        <T as system::Trait>::AccountId,
+        <T as system::Trait>::Hash,
+        <T as erc1155::Trait>::TokenBalance {
+        // Token locked in vault, owner received all shares of share id
+        Fractionalised(AccountId, Hash, Hash, TokenBalance),
+
+        // Shares burned, token released from vault to account
+        Redeemed(AccountId, Hash, Hash),
+    }
+);
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Fractional {
+        // Share id and number of shares of a token locked in vault
+        pub Fractions get(fractions): map T::Hash => Option<(T::Hash, <T as erc1155::Trait>::TokenBalance)>;
+
+        // Value used to generate share ids
+        pub Nonce: u64;
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin  {
+        fn deposit_event() = default;
+
+        // Lock owned token in vault and mint shares to owner
+        fn fractionalise(origin, token_id: T::Hash, shares: <T as erc1155::Trait>::TokenBalance) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_fractionalise(&sender, &token_id, shares)?;
+
+            Ok(())
+        }
+
+        // Burn all shares of token and release it from vault to sender
+        fn redeem(origin, token_id: T::Hash) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_redeem(&sender, &token_id)?;
+
+            Ok(())
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    // Account of the vault
+    pub fn account_id() -> T::AccountId {
+        MODULE_ID.into_account()
+    }
+
+    // Lock token in vault and mint shares to owner
+    pub fn _fractionalise(
+        owner: &T::AccountId,
+        token_id: &T::Hash,
+        shares: <T as erc1155::Trait>::TokenBalance,
+    ) -> DispatchResult {
+        if shares.is_zero() {
+            return Err(Error::<T>::ZeroShares.into());
+        }
+
+        // Share id is new for each fractionalisation, burned ids can not be minted again
+        let nonce = Nonce::get();
+        let share_id =
+            (MODULE_ID.0, *token_id, nonce).using_encoded(<T as system::Trait>::Hashing::hash);
+        if <erc1155::Module<T>>::total_supply(&share_id).is_some() {
+            return Err(Error::<T>::SharesAlreadyExist.into());
+        }
+
+        // Only owner can move token to vault, fails if token is locked
+        let vault = Self::account_id();
+        <erc721::Module<T>>::_transfer_from(owner, owner, &vault, token_id)?;
+        <erc721::Module<T>>::_lock(token_id)?;
+
+        <erc1155::Module<T>>::_mint(&vault, owner, &share_id, shares)?;
+
+        <Fractions<T>>::insert(token_id, (share_id, shares));
+        Nonce::mutate(|n| *n = n.saturating_add(1));
+
+        Self::deposit_event(RawEvent::Fractionalised(
+            owner.clone(),
+            *token_id,
+            share_id,
+            shares,
+        ));
+
+        Ok(())
+    }
+
+    // Burn all shares and release token from vault
+    pub fn _redeem(account_id: &T::AccountId, token_id: &T::Hash) -> DispatchResult {
+        let (share_id, shares) = Self::fractions(token_id).ok_or(Error::<T>::NotFractionalised)?;
+
+        if <erc1155::Module<T>>::balance_of(account_id, &share_id) != shares {
+            return Err(Error::<T>::NotAllShares.into());
+        }
+
+        // No transactional storage, check the release before burning shares
+        Self::ensure_can_release(token_id, account_id)?;
+
+        <erc1155::Module<T>>::_burn(account_id, account_id, &share_id, shares)?;
+
+        let vault = Self::account_id();
+        <erc721::Module<T>>::_unlock(token_id);
+        <erc721::Module<T>>::_transfer_from(&vault, &vault, account_id, token_id)?;
+
+        <Fractions<T>>::remove(token_id);
+
+        Self::deposit_event(RawEvent::Redeemed(account_id.clone(), *token_id, share_id));
+
+        Ok(())
+    }
+
+    // Check the token in vault can be moved to account, it is locked until released
+    fn ensure_can_release(token_id: &T::Hash, to: &T::AccountId) -> DispatchResult {
+        let vault = Self::account_id();
+
+        <erc721::Module<T>>::_unlock(token_id);
+        let result = <erc721::Module<T>>::ensure_can_transfer(&vault, &vault, to, token_id);
+        <erc721::Module<T>>::_lock(token_id)?;
+
+        result
+    }
+}
@@ -0,0 +1,114 @@
+#![cfg(test)]
+
+use super::*;
+use mock::*;
+use support::assert_ok;
+
+#[test]
+fn fractionalise_and_redeem() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(ERC721::_mint(&ALICE, &token_id(1)));
+
+        let share_id = fractionalise_test(ALICE, token_id(1), SHARES, Ok(())).unwrap();
+
+        // Shares are traded, the token stays in vault
+        assert_ok!(ERC1155::_safe_transfer_from(
+            &ALICE, &ALICE, &BOB, &share_id, 40
+        ));
+        redeem_test(
+            ALICE,
+            token_id(1),
+            Err(Error::<FractionalTest>::NotAllShares.into()),
+        );
+
+        assert_ok!(ERC1155::_safe_transfer_from(
+            &ALICE, &ALICE, &BOB, &share_id, 60
+        ));
+        redeem_test(BOB, token_id(1), Ok(()));
+        assert_eq!(ERC1155::balance_of(BOB, share_id), 0);
+        assert_eq!(ERC1155::total_supply(share_id), Some(0));
+
+        // Redeemed token can be fractionalised again with a new share id
+        let new_share_id = fractionalise_test(BOB, token_id(1), SHARES, Ok(())).unwrap();
+        assert_ne!(new_share_id, share_id);
+
+        assert_ok!(ERC721::check_integrity());
+    });
+}
+
+#[test]
+fn fractionalise_not_owner() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(ERC721::_mint(&ALICE, &token_id(1)));
+
+        fractionalise_test(
+            BOB,
+            token_id(1),
+            SHARES,
+            Err(erc721::Error::<FractionalTest>::NotTokenOwner.into()),
+        );
+        fractionalise_test(
+            ALICE,
+            token_id(1),
+            0,
+            Err(Error::<FractionalTest>::ZeroShares.into()),
+        );
+        fractionalise_test(
+            ALICE,
+            token_id(2),
+            SHARES,
+            Err(erc721::Error::<FractionalTest>::TokenNotExisted.into()),
+        );
+    });
+}
+
+#[test]
+fn locked_token_can_not_move() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(ERC721::_mint(&ALICE, &token_id(1)));
+        fractionalise_test(ALICE, token_id(1), SHARES, Ok(()));
+
+        let vault = Fractional::account_id();
+        assert_eq!(
+            ERC721::_transfer_from(&vault, &vault, &CHARLIE, &token_id(1)),
+            Err(erc721::Error::<FractionalTest>::TokenLocked.into())
+        );
+        assert_eq!(
+            ERC721::_burn(&vault, &token_id(1)),
+            Err(erc721::Error::<FractionalTest>::TokenLocked.into())
+        );
+        assert_eq!(ERC721::owner_of(token_id(1)), Some(vault));
+    });
+}
+
+#[test]
+fn vetoed_redeem_keeps_shares() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(ERC721::_mint(&ALICE, &token_id(1)));
+        let share_id = fractionalise_test(ALICE, token_id(1), SHARES, Ok(())).unwrap();
+        assert_ok!(ERC1155::_safe_transfer_from(
+            &ALICE, &ALICE, &BLOCKED, &share_id, SHARES
+        ));
+
+        redeem_test(BLOCKED, token_id(1), Err("blocked account".into()));
+        assert_eq!(ERC1155::balance_of(BLOCKED, share_id), SHARES);
+        assert_eq!(
+            ERC721::owner_of(token_id(1)),
+            Some(Fractional::account_id())
+        );
+        assert!(ERC721::is_locked(token_id(1)));
+    });
+}
+
+#[test]
+fn redeem_not_fractionalised() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(ERC721::_mint(&ALICE, &token_id(1)));
+
+        redeem_test(
+            ALICE,
+            token_id(1),
+            Err(Error::<FractionalTest>::NotFractionalised.into()),
+        );
+    });
+}
@@ -0,0 +1,369 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+use sp_runtime::traits::{
+    AccountIdConversion, SaturatedConversion, Saturating, UniqueSaturatedInto, Zero,
+};
+use sp_runtime::{ModuleId, Perbill, Permill};
+use sp_std::prelude::*;
+use support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::DispatchResult,
+    traits::{Currency, ExistenceRequirement},
+};
+use system::ensure_signed;
+
+use crate::{erc721, BalanceOf};
+
+// Encoding library
+use codec::{Decode, Encode};
+
+#[cfg(test)]
+mod tests;
+
+// Escrow account holding the collateral tokens
+const MODULE_ID: ModuleId = ModuleId(*b"nft/lend");
+
+// Milliseconds in a year of 365 days, the period of loan interest rates
+pub const MILLISECS_PER_YEAR: u64 = 365 * 24 * 60 * 60 * 1000;
+
+// Loan requested by a borrower against a token escrowed until it is repaid or claimed
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Loan<AccountId, Balance, BlockNumber, Moment> {
+    pub borrower: AccountId,
+    pub principal: Balance,
+    // Yearly interest rate on the principal
+    pub interest_rate: Permill,
+    pub due_block: BlockNumber,
+    // Lender and funding time of a funded loan
+    pub funding: Option<(AccountId, Moment)>,
+}
+
+type LoanOf<T> = Loan<
+    <T as system::Trait>::AccountId,
+    BalanceOf<T>,
+    <T as system::Trait>::BlockNumber,
+    <T as pallet_timestamp::Trait>::Moment,
+>;
+
+pub trait Trait: crate::Trait + pallet_timestamp::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+}
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        // No loan requested against the token
+        LoanNotFound,
+
+        // Principal must be more than zero
+        ZeroPrincipal,
+
+        // Due block already passed
+        InvalidDueBlock,
+
+        // Loan already funded by a lender
+        LoanAlreadyFunded,
+
+        // Loan not funded yet
+        LoanNotFunded,
+
+        // Sender is not the borrower of the loan
+        NotBorrower,
+
+        // Sender is not the lender of the loan
+        NotLender,
+
+        // Borrower can not fund own loan
+        LenderIsBorrower,
+
+        // Loan is not overdue yet
+        LoanNotDue,
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+        where
+        <T as system::Trait>::AccountId,
+        <T as system::Trait>::Hash,
+        <T as system::Trait>::BlockNumber,
+        Moment = <T as pallet_timestamp::Trait>::Moment,
+        Balance = BalanceOf<T> {
+        // Borrower escrowed token for a loan of principal at a yearly rate until due block
+        LoanRequested(AccountId, Hash, Balance, Permill, BlockNumber),
+
+        // Loan request cancelled, token returned to borrower
+        LoanCancelled(Hash),
+
+        // Lender paid the principal to the borrower at moment
+        LoanFunded(AccountId, Hash, Moment),
+
+        // Borrower repaid amount with interest to lender, token returned to borrower
+        LoanRepaid(Hash, AccountId, Balance),
+
+        // Lender claimed the token of an overdue loan
+        LoanDefaulted(Hash, AccountId),
+    }
+);
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Lending {
+        // Loan of each escrowed token
+        pub Loans get(loans): map T::Hash => Option<LoanOf<T>>;
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin  {
+        fn deposit_event() = default;
+
+        // Escrow owned token for a loan of principal at a yearly interest rate until due block
+        fn request_loan(
+            origin,
+            token_id: T::Hash,
+            principal: BalanceOf<T>,
+            interest_rate: Permill,
+            due_block: T::BlockNumber
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_request_loan(&sender, &token_id, principal, interest_rate, due_block)?;
+
+            Ok(())
+        }
+
+        // Cancel a loan request not funded yet, token returned to borrower
+        fn cancel_request(origin, token_id: T::Hash) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_cancel_request(&sender, &token_id)?;
+
+            Ok(())
+        }
+
+        // Fund a requested loan, paying the principal to the borrower
+        fn fund(origin, token_id: T::Hash) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_fund(&sender, &token_id)?;
+
+            Ok(())
+        }
+
+        // Repay principal and interest of a funded loan to reclaim the token
+        fn repay(origin, token_id: T::Hash) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_repay(&sender, &token_id)?;
+
+            Ok(())
+        }
+
+        // Claim the token of a loan not repaid after its due block
+        fn claim(origin, token_id: T::Hash) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_claim(&sender, &token_id)?;
+
+            Ok(())
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    // Account of the escrow
+    pub fn account_id() -> T::AccountId {
+        MODULE_ID.into_account()
+    }
+
+    // Move token from borrower to escrow and lock it until the loan is closed
+    pub fn _request_loan(
+        borrower: &T::AccountId,
+        token_id: &T::Hash,
+        principal: BalanceOf<T>,
+        interest_rate: Permill,
+        due_block: T::BlockNumber,
+    ) -> DispatchResult {
+        if principal.is_zero() {
+            return Err(Error::<T>::ZeroPrincipal.into());
+        }
+
+        if due_block <= <system::Module<T>>::block_number() {
+            return Err(Error::<T>::InvalidDueBlock.into());
+        }
+
+        // Only owner can move token to escrow, fails if token is locked
+        let escrow = Self::account_id();
+        <erc721::Module<T>>::_transfer_from(borrower, borrower, &escrow, token_id)?;
+        <erc721::Module<T>>::_lock(token_id)?;
+
+        <Loans<T>>::insert(
+            token_id,
+            Loan {
+                borrower: borrower.clone(),
+                principal,
+                interest_rate,
+                due_block,
+                funding: None,
+            },
+        );
+
+        Self::deposit_event(RawEvent::LoanRequested(
+            borrower.clone(),
+            *token_id,
+            principal,
+            interest_rate,
+            due_block,
+        ));
+
+        Ok(())
+    }
+
+    pub fn _cancel_request(borrower: &T::AccountId, token_id: &T::Hash) -> DispatchResult {
+        let loan = Self::loans(token_id).ok_or(Error::<T>::LoanNotFound)?;
+
+        if loan.borrower != *borrower {
+            return Err(Error::<T>::NotBorrower.into());
+        }
+
+        if loan.funding.is_some() {
+            return Err(Error::<T>::LoanAlreadyFunded.into());
+        }
+
+        Self::release(token_id, borrower)?;
+        <Loans<T>>::remove(token_id);
+
+        Self::deposit_event(RawEvent::LoanCancelled(*token_id));
+
+        Ok(())
+    }
+
+    pub fn _fund(lender: &T::AccountId, token_id: &T::Hash) -> DispatchResult {
+        let mut loan = Self::loans(token_id).ok_or(Error::<T>::LoanNotFound)?;
+
+        if loan.funding.is_some() {
+            return Err(Error::<T>::LoanAlreadyFunded.into());
+        }
+
+        if loan.borrower == *lender {
+            return Err(Error::<T>::LenderIsBorrower.into());
+        }
+
+        if loan.due_block <= <system::Module<T>>::block_number() {
+            return Err(Error::<T>::InvalidDueBlock.into());
+        }
+
+        <T as crate::Trait>::Currency::transfer(
+            lender,
+            &loan.borrower,
+            loan.principal,
+            ExistenceRequirement::AllowDeath,
+        )?;
+
+        let now = <pallet_timestamp::Module<T>>::get();
+        loan.funding = Some((lender.clone(), now));
+        <Loans<T>>::insert(token_id, loan);
+
+        Self::deposit_event(RawEvent::LoanFunded(lender.clone(), *token_id, now));
+
+        Ok(())
+    }
+
+    // Pay principal and interest to the lender and return token to the borrower,
+    // an overdue loan can be repaid until the lender claims it
+    pub fn _repay(borrower: &T::AccountId, token_id: &T::Hash) -> DispatchResult {
+        let loan = Self::loans(token_id).ok_or(Error::<T>::LoanNotFound)?;
+
+        if loan.borrower != *borrower {
+            return Err(Error::<T>::NotBorrower.into());
+        }
+
+        let lender = match &loan.funding {
+            Some((lender, _)) => lender.clone(),
+            None => return Err(Error::<T>::LoanNotFunded.into()),
+        };
+
+        // No transactional storage, check the release before moving currency
+        Self::ensure_can_release(token_id, borrower)?;
+
+        let amount = loan.principal.saturating_add(Self::interest(&loan));
+        <T as crate::Trait>::Currency::transfer(
+            borrower,
+            &lender,
+            amount,
+            ExistenceRequirement::AllowDeath,
+        )?;
+
+        Self::release(token_id, borrower)?;
+        <Loans<T>>::remove(token_id);
+
+        Self::deposit_event(RawEvent::LoanRepaid(*token_id, lender, amount));
+
+        Ok(())
+    }
+
+    pub fn _claim(lender: &T::AccountId, token_id: &T::Hash) -> DispatchResult {
+        let loan = Self::loans(token_id).ok_or(Error::<T>::LoanNotFound)?;
+
+        match &loan.funding {
+            Some((funder, _)) if funder == lender => (),
+            Some(_) => return Err(Error::<T>::NotLender.into()),
+            None => return Err(Error::<T>::LoanNotFunded.into()),
+        }
+
+        if <system::Module<T>>::block_number() <= loan.due_block {
+            return Err(Error::<T>::LoanNotDue.into());
+        }
+
+        Self::release(token_id, lender)?;
+        <Loans<T>>::remove(token_id);
+
+        Self::deposit_event(RawEvent::LoanDefaulted(*token_id, lender.clone()));
+
+        Ok(())
+    }
+
+    // Interest accrued on a funded loan, the yearly rate over the time since it was funded
+    pub fn interest(loan: &LoanOf<T>) -> BalanceOf<T> {
+        let funded_at = match &loan.funding {
+            Some((_, funded_at)) => *funded_at,
+            None => return Zero::zero(),
+        };
+
+        let elapsed: u64 = <pallet_timestamp::Module<T>>::get()
+            .saturating_sub(funded_at)
+            .unique_saturated_into();
+        let yearly = loan.interest_rate * loan.principal;
+        let years: BalanceOf<T> = (elapsed / MILLISECS_PER_YEAR).saturated_into();
+        let part_year =
+            Perbill::from_rational_approximation(elapsed % MILLISECS_PER_YEAR, MILLISECS_PER_YEAR);
+
+        yearly
+            .saturating_mul(years)
+            .saturating_add(part_year * yearly)
+    }
+
+    // Amount the borrower repays now, principal and interest
+    pub fn repayment(token_id: &T::Hash) -> Option<BalanceOf<T>> {
+        Self::loans(token_id).map(|loan| loan.principal.saturating_add(Self::interest(&loan)))
+    }
+
+    // Check the escrowed token can be moved to account, it is locked until released
+    fn ensure_can_release(token_id: &T::Hash, to: &T::AccountId) -> DispatchResult {
+        let escrow = Self::account_id();
+
+        <erc721::Module<T>>::_unlock(token_id);
+        let result = <erc721::Module<T>>::ensure_can_transfer(&escrow, &escrow, to, token_id);
+        <erc721::Module<T>>::_lock(token_id)?;
+
+        result
+    }
+
+    // Unlock token and move it from escrow to account
+    fn release(token_id: &T::Hash, to: &T::AccountId) -> DispatchResult {
+        Self::ensure_can_release(token_id, to)?;
+
+        let escrow = Self::account_id();
+        <erc721::Module<T>>::_unlock(token_id);
+        <erc721::Module<T>>::_transfer_from(&escrow, &escrow, to, token_id)
+    }
+}
@@ -0,0 +1,245 @@
+#![cfg(test)]
+
+use super::*;
+use crate::mock::*;
+use sp_core::H256;
+use support::assert_ok;
+
+const PRINCIPAL: u64 = 1_000_000;
+const DUE_BLOCK: u64 = 20;
+
+fn rate() -> Permill {
+    Permill::from_percent(10)
+}
+
+fn request_loan_test(borrower: u64, token_id: H256, principal: u64, result: DispatchResult) {
+    assert_eq!(
+        Lending::request_loan(
+            Origin::signed(borrower),
+            token_id,
+            principal,
+            rate(),
+            DUE_BLOCK
+        ),
+        result
+    );
+
+    if result.is_ok() {
+        assert_eq!(ERC721::owner_of(token_id), Some(Lending::account_id()));
+        assert_eq!(Lending::loans(token_id).unwrap().borrower, borrower);
+    }
+}
+
+fn has_event(event: RawEvent<u64, H256, u64, u64, u64>) -> bool {
+    <system::Module<NftRegistryTest>>::events()
+        .iter()
+        .any(|e| e.event == MetaEvent::lending(event.clone()))
+}
+
+#[test]
+fn request_loan_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = mint_test(ALICE, 1);
+
+        request_loan_test(
+            BOB,
+            token_id,
+            PRINCIPAL,
+            Err(erc721::Error::<NftRegistryTest>::NotTokenOwner.into()),
+        );
+        request_loan_test(
+            ALICE,
+            token_id,
+            0,
+            Err(Error::<NftRegistryTest>::ZeroPrincipal.into()),
+        );
+
+        <system::Module<NftRegistryTest>>::set_block_number(DUE_BLOCK);
+        request_loan_test(
+            ALICE,
+            token_id,
+            PRINCIPAL,
+            Err(Error::<NftRegistryTest>::InvalidDueBlock.into()),
+        );
+        assert_eq!(ERC721::owner_of(token_id), Some(ALICE));
+    });
+}
+
+#[test]
+fn cancel_request_returns_token() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = mint_test(ALICE, 1);
+        request_loan_test(ALICE, token_id, PRINCIPAL, Ok(()));
+
+        assert_eq!(
+            Lending::cancel_request(Origin::signed(BOB), token_id),
+            Err(Error::<NftRegistryTest>::NotBorrower.into())
+        );
+        assert_ok!(Lending::cancel_request(Origin::signed(ALICE), token_id));
+        assert_eq!(ERC721::owner_of(token_id), Some(ALICE));
+        assert_eq!(Lending::loans(token_id), None);
+        assert!(has_event(RawEvent::LoanCancelled(token_id)));
+
+        // Token is unlocked again
+        assert_ok!(ERC721::_transfer_from(&ALICE, &ALICE, &BOB, &token_id));
+    });
+}
+
+#[test]
+fn escrowed_token_is_locked() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = mint_test(ALICE, 1);
+        request_loan_test(ALICE, token_id, PRINCIPAL, Ok(()));
+
+        let escrow = Lending::account_id();
+        assert_eq!(
+            ERC721::_transfer_from(&escrow, &escrow, &BOB, &token_id),
+            Err(erc721::Error::<NftRegistryTest>::TokenLocked.into())
+        );
+        assert_eq!(
+            ERC721::_burn(&escrow, &token_id),
+            Err(erc721::Error::<NftRegistryTest>::TokenLocked.into())
+        );
+        assert_eq!(
+            ERC721::_burn(&ALICE, &token_id),
+            Err(erc721::Error::<NftRegistryTest>::TokenLocked.into())
+        );
+    });
+}
+
+#[test]
+fn fund_and_repay_with_interest() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = mint_test(ALICE, 1);
+        create_account_test(BOB);
+        request_loan_test(ALICE, token_id, PRINCIPAL, Ok(()));
+
+        assert_eq!(
+            Lending::repay(Origin::signed(ALICE), token_id),
+            Err(Error::<NftRegistryTest>::LoanNotFunded.into())
+        );
+        assert_eq!(
+            Lending::fund(Origin::signed(ALICE), token_id),
+            Err(Error::<NftRegistryTest>::LenderIsBorrower.into())
+        );
+
+        let borrower_balance = Balances::free_balance(ALICE);
+        let lender_balance = Balances::free_balance(BOB);
+
+        Timestamp::set_timestamp(1_000);
+        assert_ok!(Lending::fund(Origin::signed(BOB), token_id));
+        assert_eq!(Balances::free_balance(ALICE), borrower_balance + PRINCIPAL);
+        assert_eq!(Balances::free_balance(BOB), lender_balance - PRINCIPAL);
+        assert_eq!(
+            Lending::loans(token_id).unwrap().funding,
+            Some((BOB, 1_000))
+        );
+        assert!(has_event(RawEvent::LoanFunded(BOB, token_id, 1_000)));
+
+        assert_eq!(
+            Lending::fund(Origin::signed(CHARLIE), token_id),
+            Err(Error::<NftRegistryTest>::LoanAlreadyFunded.into())
+        );
+        assert_eq!(
+            Lending::cancel_request(Origin::signed(ALICE), token_id),
+            Err(Error::<NftRegistryTest>::LoanAlreadyFunded.into())
+        );
+
+        // Half a year at 10% a year
+        Timestamp::set_timestamp(1_000 + MILLISECS_PER_YEAR / 2);
+        let amount = PRINCIPAL + PRINCIPAL / 20;
+        assert_eq!(Lending::repayment(&token_id), Some(amount));
+
+        assert_eq!(
+            Lending::repay(Origin::signed(BOB), token_id),
+            Err(Error::<NftRegistryTest>::NotBorrower.into())
+        );
+        assert_ok!(Lending::repay(Origin::signed(ALICE), token_id));
+        assert_eq!(ERC721::owner_of(token_id), Some(ALICE));
+        assert_eq!(
+            Balances::free_balance(ALICE),
+            borrower_balance + PRINCIPAL - amount
+        );
+        assert_eq!(
+            Balances::free_balance(BOB),
+            lender_balance - PRINCIPAL + amount
+        );
+        assert_eq!(Lending::loans(token_id), None);
+        assert!(has_event(RawEvent::LoanRepaid(token_id, BOB, amount)));
+    });
+}
+
+#[test]
+fn interest_accrues_over_years() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = mint_test(ALICE, 1);
+        create_account_test(BOB);
+        request_loan_test(ALICE, token_id, PRINCIPAL, Ok(()));
+        assert_ok!(Lending::fund(Origin::signed(BOB), token_id));
+
+        let loan = Lending::loans(token_id).unwrap();
+        assert_eq!(Lending::interest(&loan), 0);
+
+        Timestamp::set_timestamp(MILLISECS_PER_YEAR * 2 + MILLISECS_PER_YEAR / 4);
+        assert_eq!(
+            Lending::interest(&loan),
+            PRINCIPAL / 10 * 2 + PRINCIPAL / 40
+        );
+    });
+}
+
+#[test]
+fn lender_claims_overdue_loan() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = mint_test(ALICE, 1);
+        create_account_test(BOB);
+        request_loan_test(ALICE, token_id, PRINCIPAL, Ok(()));
+
+        assert_eq!(
+            Lending::claim(Origin::signed(BOB), token_id),
+            Err(Error::<NftRegistryTest>::LoanNotFunded.into())
+        );
+        assert_ok!(Lending::fund(Origin::signed(BOB), token_id));
+
+        <system::Module<NftRegistryTest>>::set_block_number(DUE_BLOCK);
+        assert_eq!(
+            Lending::claim(Origin::signed(BOB), token_id),
+            Err(Error::<NftRegistryTest>::LoanNotDue.into())
+        );
+
+        <system::Module<NftRegistryTest>>::set_block_number(DUE_BLOCK + 1);
+        assert_eq!(
+            Lending::claim(Origin::signed(CHARLIE), token_id),
+            Err(Error::<NftRegistryTest>::NotLender.into())
+        );
+        assert_ok!(Lending::claim(Origin::signed(BOB), token_id));
+        assert_eq!(ERC721::owner_of(token_id), Some(BOB));
+        assert_eq!(Lending::loans(token_id), None);
+        assert!(has_event(RawEvent::LoanDefaulted(token_id, BOB)));
+
+        // Claimed token is unlocked
+        assert_ok!(ERC721::_transfer_from(&BOB, &BOB, &CHARLIE, &token_id));
+        assert_eq!(
+            Lending::repay(Origin::signed(ALICE), token_id),
+            Err(Error::<NftRegistryTest>::LoanNotFound.into())
+        );
+    });
+}
+
+#[test]
+fn overdue_loan_can_be_repaid_until_claimed() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = mint_test(ALICE, 1);
+        create_account_test(BOB);
+        request_loan_test(ALICE, token_id, PRINCIPAL, Ok(()));
+        assert_ok!(Lending::fund(Origin::signed(BOB), token_id));
+
+        <system::Module<NftRegistryTest>>::set_block_number(DUE_BLOCK + 1);
+        assert_ok!(Lending::repay(Origin::signed(ALICE), token_id));
+        assert_eq!(ERC721::owner_of(token_id), Some(ALICE));
+        assert_eq!(
+            Lending::claim(Origin::signed(BOB), token_id),
+            Err(Error::<NftRegistryTest>::LoanNotFound.into())
+        );
+    });
+}
@@ -2,25 +2,41 @@
 
 use proofs::Proof;
 use sp_core::H256;
-use sp_runtime::traits::{Saturating, StaticLookup};
+use sp_runtime::{
+    traits::{
+        AccountIdConversion, Bounded, CheckedSub, Hash as HashT, SaturatedConversion, Saturating,
+        StaticLookup, Verify, Zero,
+    },
+    ModuleId, Permill,
+};
 use sp_std::collections::btree_set::BTreeSet;
-use sp_std::{result::Result, vec::Vec};
+use sp_std::{marker::PhantomData, result::Result, vec::Vec};
 use support::{
     decl_error, decl_event, decl_module, decl_storage,
     dispatch::{DispatchError, DispatchResult},
     ensure,
-    traits::{Currency, Get, LockableCurrency, Randomness, ReservableCurrency},
-    weights::SimpleDispatchInfo,
+    traits::{
+        Currency, ExistenceRequirement, Get, LockIdentifier, LockableCurrency, Randomness,
+        ReservableCurrency, WithdrawReason, WithdrawReasons,
+    },
+    weights::{SimpleDispatchInfo, Weight},
 };
 use system::{ensure_signed, RawOrigin};
 
 // Encoding library
-use codec::Encode;
+use codec::{Decode, Encode};
 
-mod anchor;
-mod erc721;
-mod proofs;
+pub mod anchor;
+pub mod auction;
+pub mod erc1155;
+pub mod erc721;
+pub mod fractional;
+pub mod lending;
+pub mod marketplace;
+pub mod proofs;
 
+#[cfg(test)]
+mod e2e_mock;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -33,12 +49,151 @@ struct ContractParameter<Hash, Account> {
     token_owner: Account,
     metadata: Vec<u8>,
     proof_leaves: Vec<H256>,
+    // User the token is minted with, appended so older contracts still decode the parameter
+    token_user: Option<Account>,
+    // Tokens finished minting to the token owner in the registry, for contract mint quotas
+    owner_minted: u32,
 }
 
 type RegistryUid = u64;
+
+// Royalty rate in basis points, 10_000 is the whole price
+pub type BasisPoints = u16;
+
+const MAX_BASIS_POINTS: BasisPoints = 10_000;
+
+// Royalty of a registry or a token, paid to recipient when the token is sold
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct RoyaltyInfo<AccountId> {
+    pub recipient: AccountId,
+    pub rate: BasisPoints,
+}
+
+pub type SwapId = u64;
+
+// Escrow account holding the tokens offered in swaps
+const SWAP_MODULE_ID: ModuleId = ModuleId(*b"nft/swap");
+
+// Lock of the currency offered in swaps, apart from the reserved token deposits
+const SWAP_LOCK_ID: LockIdentifier = *b"nft/swap";
+
+// Tokens and currency offered by proposer for tokens and currency of counterparty
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Swap<AccountId, Hash, Balance, BlockNumber> {
+    pub proposer: AccountId,
+    pub counterparty: AccountId,
+    pub offer_tokens: Vec<Hash>,
+    pub offer_amount: Balance,
+    pub want_tokens: Vec<Hash>,
+    pub want_amount: Balance,
+    pub expiry: BlockNumber,
+}
+
+// Domain of signed mint requests, so a request signature is never valid for other messages
+pub const MINT_DOMAIN: [u8; 16] = *b"nftregistry/mint";
+
+// Mint parameters signed by the token owner, submitted by a relayer and paid by a sponsor.
+// The sponsor pays the fee to the relayer and reserves the deposit of the token.
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct MintRequest<AccountId, Hash, Balance, BlockNumber> {
+    pub owner: AccountId,
+    pub sponsor: AccountId,
+    pub registry_uid: RegistryUid,
+    pub token_id: Hash,
+    pub metadata: Vec<u8>,
+    pub anchor_id: Hash,
+    pub proofs: Vec<Proof>,
+    pub static_proofs: [H256; 3],
+    pub fee: Balance,
+    pub nonce: u64,
+    pub deadline: BlockNumber,
+}
+
+// Signed mints a sponsor pays for a user, the number of mints and the amount of fees and deposits
+#[derive(Encode, Decode, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct SponsorLimit<Balance> {
+    pub mints: u32,
+    pub amount: Balance,
+}
+
+pub type ProposalId = u64;
+
+// Domain of multi-account ids, so they never collide with other derived accounts
+const MULTI_ACCOUNT_DOMAIN: [u8; 17] = *b"nftregistry/multi";
+
+// Max accounts co-owning a token
+const MAX_CO_OWNERS: usize = 16;
+
+// Accounts jointly owning the tokens of their multi-account, an action needs threshold confirmations
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct CoOwnership<AccountId> {
+    pub owners: Vec<AccountId>,
+    pub threshold: u32,
+}
+
+// Action on a co-owned token, executed by the multi-account owning it
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum CoOwnerAction<AccountId> {
+    // Transfer the token to an account
+    TransferFrom(AccountId),
+
+    // Burn the token, releasing its deposit
+    Burn,
+
+    // Approve the token to an account
+    Approve(AccountId),
+}
+
+// Action proposed by a co-owner, executed once enough co-owners confirmed it before expiry
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Proposal<AccountId, Hash, BlockNumber> {
+    pub proposer: AccountId,
+    // Multi-account owning the token when proposed
+    pub owner: AccountId,
+    pub token_id: Hash,
+    pub action: CoOwnerAction<AccountId>,
+    pub confirmations: Vec<AccountId>,
+    pub expiry: BlockNumber,
+}
+
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
 
-pub trait Trait: system::Trait + contracts::Trait + erc721::Trait + anchor::Trait {
+pub type MintRequestOf<T> = MintRequest<
+    <T as system::Trait>::AccountId,
+    <T as system::Trait>::Hash,
+    BalanceOf<T>,
+    <T as system::Trait>::BlockNumber,
+>;
+
+type ProposalOf<T> = Proposal<
+    <T as system::Trait>::AccountId,
+    <T as system::Trait>::Hash,
+    <T as system::Trait>::BlockNumber,
+>;
+
+type SwapOf<T> = Swap<
+    <T as system::Trait>::AccountId,
+    <T as system::Trait>::Hash,
+    BalanceOf<T>,
+    <T as system::Trait>::BlockNumber,
+>;
+
+// Selector of a contract message, first 4 bytes of the method name's hash
+pub(crate) fn message_selector(name: &str) -> [u8; 4] {
+    let keccak = ink_utils::hash::keccak256(name.as_bytes());
+    [keccak[0], keccak[1], keccak[2], keccak[3]]
+}
+
+pub trait Trait:
+    system::Trait + contracts::Trait + erc721::Trait + erc1155::Trait + anchor::Trait
+{
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
     /// Something that provides randomness in the runtime.
     type Randomness: Randomness<Self::Hash>;
@@ -55,6 +210,9 @@ pub trait Trait: system::Trait + contracts::Trait + erc721::Trait + anchor::Trai
     /// Currency type for this module.
     type Currency: ReservableCurrency<Self::AccountId>
         + LockableCurrency<Self::AccountId, Moment = Self::BlockNumber>;
+
+    /// Gas limit of the call to a contract receiving a token by `safe_transfer_from`.
+    type ReceiverGasLimit: Get<contracts::Gas>;
 }
 
 decl_error! {
@@ -73,6 +231,87 @@ decl_error! {
 
         // Proof validation failed
         DocumentNotAnchored,
+
+        // Fungible registry needs at least one share
+        ZeroShares,
+
+        // Sender is not the account registered the registry
+        NotRegistryOwner,
+
+        // Royalty rate is more than 10_000 basis points
+        RoyaltyRateTooHigh,
+
+        // Token is not minted by a registry
+        TokenNotInRegistry,
+
+        // Swap not found
+        SwapNotFound,
+
+        // Swap offers and wants nothing
+        EmptySwap,
+
+        // Swap counterparty is the proposer
+        SwapWithSelf,
+
+        // Expiry block already passed
+        SwapExpired,
+
+        // Sender is not the counterparty of the swap
+        NotSwapCounterparty,
+
+        // Sender is not the proposer of the swap
+        NotSwapProposer,
+
+        // Free balance is less than the currency offered in open swaps
+        InsufficientSwapBalance,
+
+        // Mint request deadline block already passed
+        MintRequestExpired,
+
+        // Mint request nonce is not the next nonce of the owner
+        InvalidMintNonce,
+
+        // Mint request not signed by the owner
+        InvalidMintSignature,
+
+        // Sponsor allows no more mints or a lower amount for the owner
+        SponsorAllowanceExceeded,
+
+        // Validation contract did not mint the token
+        SponsoredMintFailed,
+
+        // Co-owners must be 2 to MAX_CO_OWNERS distinct accounts, threshold 1 to their number
+        InvalidCoOwners,
+
+        // Token is not owned by a multi-account of co-owners
+        NotCoOwned,
+
+        // Sender is not a co-owner of the token
+        NotCoOwner,
+
+        // Proposal not existed
+        ProposalNotFound,
+
+        // Proposal expiry block already passed
+        ProposalExpired,
+
+        // Co-owner already confirmed the proposal
+        AlreadyConfirmed,
+
+        // Sender is not the proposer
+        NotProposer,
+
+        // Buyer and seller of a paid transfer are the same account
+        BuyerIsSeller,
+
+        // Shares of a fungible registry have no user
+        TokenUserNotSupported,
+
+        // Seller can not pay the royalty out of the price
+        RoyaltyNotPayable,
+
+        // A side of the swap can not pay its currency
+        SwapPaymentFailed,
     }
 }
 
@@ -80,12 +319,53 @@ decl_event!(
     pub enum Event<T>
         where
         <T as system::Trait>::AccountId,
-        <T as system::Trait>::Hash {
+        <T as system::Trait>::Hash,
+        <T as erc1155::Trait>::TokenBalance,
+        Balance = BalanceOf<T> {
         // Account register a new Uid with smart contract
         NewRegistry(AccountId, RegistryUid),
 
         // New NFT created
         MintNft(RegistryUid, Hash),
+
+        // Document minted as fungible shares of one token id
+        MintShares(RegistryUid, Hash, TokenBalance),
+
+        // Royalty split off the price of a sold token, paid to recipient
+        RoyaltyPaid(Hash, AccountId, Balance),
+
+        // Proposer escrowed an offer for counterparty
+        SwapProposed(SwapId, AccountId, AccountId),
+
+        // Counterparty accepted swap, both sides are settled
+        SwapAccepted(SwapId),
+
+        // Swap cancelled, offer returned to proposer
+        SwapCancelled(SwapId),
+
+        // Sponsor allows signed mints of user, up to a number of mints and an amount
+        SponsorAllowanceSet(AccountId, AccountId, u32, Balance),
+
+        // Token minted by a signed request of owner, sponsor paid the fee to the relayer
+        SponsoredMint(AccountId, AccountId, Hash, Balance),
+
+        // Token moved to the multi-account of its co-owners, with the confirmation threshold
+        CoOwned(Hash, AccountId, u32),
+
+        // Co-owner proposed an action on token
+        ProposalCreated(ProposalId, AccountId, Hash),
+
+        // Co-owner confirmed a proposal
+        ProposalConfirmed(ProposalId, AccountId),
+
+        // Proposal reached its threshold and the action was executed
+        ProposalExecuted(ProposalId),
+
+        // Proposal cancelled by its proposer or after expiry
+        ProposalCancelled(ProposalId),
+
+        // New owner can not reserve the deposit of token, it is kept by the holder
+        DepositKept(Hash, AccountId),
     }
 );
 
@@ -106,8 +386,59 @@ decl_storage! {
         // Reserved currency for each token
         pub DepositByTokenId get(deposit_by_token_id): map T::Hash => BalanceOf<T>;
 
+        // Account the deposit of a token is reserved from
+        pub DepositHolder get(deposit_holder): map T::Hash => Option<T::AccountId>;
+
         // Validation function map to avoid register again
         pub ValidationFunctionMap get(validation_function_map): map T::AccountId => bool;
+
+        // Number of shares minted per document, only set for fungible registries
+        pub RegistryShares get(registry_shares): map RegistryUid => Option<<T as erc1155::Trait>::TokenBalance>;
+
+        // Account registered each registry, it sets the royalties of the registry
+        pub RegistryOwner get(registry_owner): map hasher(blake2_256) RegistryUid => Option<T::AccountId>;
+
+        // Royalty of all tokens in a registry
+        pub RegistryRoyalty get(registry_royalty): map hasher(blake2_256) RegistryUid => Option<RoyaltyInfo<T::AccountId>>;
+
+        // Royalty of a token, overrides the royalty of its registry
+        pub TokenRoyalty get(token_royalty): map T::Hash => Option<RoyaltyInfo<T::AccountId>>;
+
+        // Open swaps
+        pub Swaps get(swaps): map hasher(blake2_256) SwapId => Option<SwapOf<T>>;
+
+        // Next swap id
+        pub NextSwapId: SwapId;
+
+        // Currency each proposer offers in open swaps, locked in its free balance
+        pub SwapLocked get(swap_locked): map T::AccountId => BalanceOf<T>;
+
+        // Limits of the signed mints a sponsor pays for each user
+        pub SponsorAllowance get(sponsor_allowance): double_map T::AccountId, blake2_256(T::AccountId) => Option<SponsorLimit<BalanceOf<T>>>;
+
+        // Next signed mint nonce of each owner, a request can be used only once
+        pub MintNonces get(mint_nonce): map T::AccountId => u64;
+
+        // Sponsor of a signed mint in progress, finish_mint reserves the deposit from it
+        pub PendingSponsor get(pending_sponsor): map T::Hash => Option<T::AccountId>;
+
+        // Tokens minted to each owner by finish_mint in each registry
+        pub MintedCount get(minted_count): double_map RegistryUid, blake2_256(T::AccountId) => u32;
+
+        // User and expiry of a mint in progress, finish_mint sets it on the minted token
+        pub PendingUser get(pending_user): map T::Hash => Option<(T::AccountId, T::BlockNumber)>;
+
+        // Sponsor reserved the deposit of a token, it keeps the deposit until the token is burned
+        pub SponsorOf get(sponsor_of): map T::Hash => Option<T::AccountId>;
+
+        // Co-owners of each multi-account
+        pub CoOwners get(co_owners): map T::AccountId => Option<CoOwnership<T::AccountId>>;
+
+        // Open proposals of co-owners
+        pub Proposals get(proposals): map hasher(blake2_256) ProposalId => Option<ProposalOf<T>>;
+
+        // Next proposal id
+        pub NextProposalId: ProposalId;
     }
 }
 
@@ -119,28 +450,7 @@ decl_module! {
         fn new_registry(origin, validation_fn_addr: T::AccountId) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            // Ensure function not registered before
-            if <ValidationFunctionMap<T>>::exists(&validation_fn_addr) {
-                return Err(Error::<T>::ValidationFunctionAlreadyExists.into());
-            }
-
-            // Reserve fee for validation function
-            <T as Trait>::Currency::reserve(&sender, T::NFTValidationRegistryDeposit::get())?;
-
-            // Keep old value for event
-            let uid = NextRegistryId::get();
-
-            // Write state
-            <ValidationFn<T>>::insert(&uid, &validation_fn_addr);
-
-            // Update next registry id
-            NextRegistryId::mutate(|value| *value += 1);
-
-            // Put the validation function in set
-            <ValidationFunctionMap<T>>::insert(&validation_fn_addr, true);
-
-            // Store event
-            Self::deposit_event(RawEvent::NewRegistry(sender, uid));
+            Self::register_validation_fn(&sender, &validation_fn_addr)?;
 
             Ok(())
         }
@@ -160,47 +470,18 @@ decl_module! {
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            // Contract registered for the uid
-            let validation_function = Self::ensure_validation_fn_exists(registry_uid)?;
-
-            // Ensure token id not existed
-            <erc721::Module<T>>::ensure_token_not_existed(&token_id)?;
-
-            // Get the doc root
-            let doc_root = Self::get_document_root(&anchor_id)?;
-
-            // Verify the proof against document root
-            Self::validate_proofs(&doc_root, &proofs, &static_proofs)?;
-
-            // Get the hash of validate method in contract
-            let keccak = ink_utils::hash::keccak256("validate".as_bytes());
-
-            // Encode 4 bytes of the method name's hash
-            let selector = [keccak[0], keccak[1], keccak[2], keccak[3]];
-            let mut call = selector.encode();
-
-            // Collect all leaves in proofs
-            let proof_leaves = proofs.iter().map(|proof| proof.leaf_hash).collect();
-
-            // Put parameters into single struct.
-            let contract_parameter = ContractParameter::<T::Hash, T::AccountId> {
-                uid: registry_uid,
-                token_id: token_id,
-                token_owner: sender.clone(),
-                metadata: metadata.clone(),
-                proof_leaves: proof_leaves,
-            };
-
-            // Append the parameter after method
-            call.append(&mut Encode::encode(&contract_parameter));
-
-            // Call the contract via bare call
-            <contracts::Module<T>>::bare_call(
-                sender,
-                validation_function,
+            Self::_mint(
+                &sender,
+                registry_uid,
+                token_id,
+                metadata,
+                &anchor_id,
+                &proofs,
+                &static_proofs,
+                None,
                 value,
                 gas_limit,
-                call);
+            )?;
 
             Ok(())
         }
@@ -212,23 +493,54 @@ decl_module! {
             // Ensure uid is existed
             Self::ensure_sender_is_validation_function(uid, &sender)?;
 
+            // Ensure token id not existed before reserving
+            Self::ensure_token_not_existed(&token_id)?;
+
             // Get storage fee for metadata
             let total_deposit = Self::compute_metadata_fee(metadata.len() as u32);
 
-            // Reserve fee for token
-            <T as Trait>::Currency::reserve(&token_owner, total_deposit)?;
+            // Reserve fee for token, from the sponsor of a signed mint
+            let sponsor = Self::pending_sponsor(&token_id);
+            let depositor = sponsor.clone().unwrap_or_else(|| token_owner.clone());
+            <T as Trait>::Currency::reserve(&depositor, total_deposit)?;
+
+            // Fungible registry mints all shares of the document to owner, otherwise
+            // use the uid to create a new ERC721 token. BUG owner is not sender.
+            let shares = Self::registry_shares(uid);
+            let minted = match shares {
+                Some(shares) => {
+                    <erc1155::Module<T>>::_mint(&sender, &token_owner, &token_id, shares)
+                }
+                None => <erc721::Module<T>>::_mint(&token_owner, &token_id),
+            };
+
+            // No transactional storage, a mint vetoed by a hook returns the deposit
+            if let Err(e) = minted {
+                <T as Trait>::Currency::unreserve(&depositor, total_deposit);
+                return Err(e);
+            }
+            if let Some(shares) = shares {
+                Self::deposit_event(RawEvent::MintShares(uid, token_id, shares));
+            }
 
-            // Use the uid to create a new ERC721 token, BUG owner is not sender.
-            <erc721::Module<T>>::_mint(&token_owner, &token_id)?;
+            // User requested by the minter
+            if let Some((user, expires)) = Self::pending_user(&token_id) {
+                <erc721::Module<T>>::_set_user(&token_owner, &token_id, &user, expires)?;
+            }
 
             // Insert token id to registry id map
             <RegistryUidForTokenId<T>>::insert(&token_id, uid);
+            <MintedCount<T>>::mutate(uid, &token_owner, |count| *count = count.saturating_add(1));
 
             // Insert token metadata
             <TokenMetadata<T>>::insert(&token_id, metadata);
 
             // Insert deposit into storage
             <DepositByTokenId<T>>::insert(&token_id, total_deposit);
+            <DepositHolder<T>>::insert(&token_id, &depositor);
+            if let Some(sponsor) = sponsor {
+                <SponsorOf<T>>::insert(&token_id, sponsor);
+            }
 
             // Just emit an event
             Self::deposit_event(RawEvent::MintNft(uid, token_id));
@@ -236,40 +548,22 @@ decl_module! {
             Ok(())
         }
 
-        // transfer_from will transfer to addresses even without a balance
+        // transfer_from will transfer to addresses even without a balance,
+        // the deposit is moved by the ERC721 transfer hook
         fn transfer_from(origin, from: T::AccountId, to: T::AccountId, token_id: T::Hash) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            // Get the deposited fee
-            let total_deposit = Self::deposit_by_token_id(&token_id);
-
-            // Reserve the currency
-            <T as Trait>::Currency::repatriate_reserved(&from, &to, total_deposit)?;
-
-            // Transfer token
             <erc721::Module<T>>::_transfer_from(&sender, &from, &to, &token_id)?;
 
             Ok(())
         }
 
-        // Burn the token and unreserve the currency for metadata
+        // Burn the token, the deposit and metadata are released by the ERC721 burn hook
         fn burn(origin, token_id: T::Hash) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            // Burn the token
             <erc721::Module<T>>::_burn(&sender, &token_id)?;
 
-            // Get the total deposit fee
-            let total_deposit = Self::deposit_by_token_id(&token_id);
-
-            // Repay deposit currency to token owner
-            <T as Trait>::Currency::unreserve(&sender, total_deposit);
-
-            // Remove storage related to burned token
-            <TokenMetadata<T>>::remove(&token_id);
-            <DepositByTokenId<T>>::remove(&token_id);
-            <RegistryUidForTokenId<T>>::remove(&token_id);
-
             Ok(())
         }
 
@@ -290,10 +584,1119 @@ decl_module! {
 
             Ok(())
         }
+
+        // Register validation function of a registry minting documents as fungible shares
+        fn new_fungible_registry(
+            origin,
+            validation_fn_addr: T::AccountId,
+            shares: <T as erc1155::Trait>::TokenBalance
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            if shares.is_zero() {
+                return Err(Error::<T>::ZeroShares.into());
+            }
+
+            let uid = Self::register_validation_fn(&sender, &validation_fn_addr)?;
+
+            <RegistryShares<T>>::insert(uid, shares);
+
+            Ok(())
+        }
+
+        // Transfer many tokens, all or nothing
+        #[weight = erc721::BatchWeight(10_000, 10_000)]
+        fn batch_transfer_from(origin, from: T::AccountId, to: T::AccountId, token_ids: Vec<T::Hash>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            <erc721::Module<T>>::_batch_transfer_from(&sender, &from, &to, token_ids)?;
+
+            Ok(())
+        }
+
+        // Approve many tokens to an account, all or nothing
+        #[weight = erc721::BatchWeight(10_000, 10_000)]
+        fn batch_approve(origin, to: T::AccountId, token_ids: Vec<T::Hash>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            <erc721::Module<T>>::_batch_approve(&sender, &to, token_ids)?;
+
+            Ok(())
+        }
+
+        // Burn many tokens, all or nothing
+        #[weight = erc721::BatchWeight(10_000, 10_000)]
+        fn batch_burn(origin, token_ids: Vec<T::Hash>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            <erc721::Module<T>>::_batch_burn(&sender, token_ids)?;
+
+            Ok(())
+        }
+
+        // Set royalty of all tokens in a registry, zero rate removes it
+        fn set_registry_royalty(origin, uid: RegistryUid, recipient: T::AccountId, rate: BasisPoints) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::ensure_registry_owner(uid, &sender)?;
+
+            match Self::royalty(recipient, rate)? {
+                Some(royalty) => <RegistryRoyalty<T>>::insert(uid, royalty),
+                None => <RegistryRoyalty<T>>::remove(uid),
+            }
+
+            Ok(())
+        }
+
+        // Set royalty of a token overriding its registry royalty, zero rate removes it
+        fn set_token_royalty(origin, token_id: T::Hash, recipient: T::AccountId, rate: BasisPoints) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            // Only registry owner sets royalties of its tokens
+            if !<RegistryUidForTokenId<T>>::exists(&token_id) {
+                return Err(Error::<T>::TokenNotInRegistry.into());
+            }
+            Self::ensure_registry_owner(Self::registry_uid_for_token_id(&token_id), &sender)?;
+
+            match Self::royalty(recipient, rate)? {
+                Some(royalty) => <TokenRoyalty<T>>::insert(&token_id, royalty),
+                None => <TokenRoyalty<T>>::remove(&token_id),
+            }
+
+            Ok(())
+        }
+
+        // Transfer a token sold for a price, sender pays the price to the owner and
+        // royalty of the token is split off to its recipient.
+        // Sender must be approved by the owner, the price is agreed between them.
+        fn transfer_with_payment(origin, from: T::AccountId, to: T::AccountId, token_id: T::Hash, price: BalanceOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            // No transactional storage, check the transfer before moving currency
+            <erc721::Module<T>>::ensure_can_transfer(&sender, &from, &to, &token_id)?;
+
+            Self::pay_with_royalty(&sender, &from, &token_id, price)?;
+
+            <erc721::Module<T>>::_transfer_from(&sender, &from, &to, &token_id)?;
+
+            Ok(())
+        }
+
+        // Escrow tokens and currency offered to counterparty for the wanted tokens and currency
+        fn propose_swap(
+            origin,
+            offer_tokens: Vec<T::Hash>,
+            offer_amount: BalanceOf<T>,
+            want_tokens: Vec<T::Hash>,
+            want_amount: BalanceOf<T>,
+            counterparty: T::AccountId,
+            expiry: T::BlockNumber
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_propose_swap(
+                &sender,
+                offer_tokens,
+                offer_amount,
+                want_tokens,
+                want_amount,
+                counterparty,
+                expiry,
+            )?;
+
+            Ok(())
+        }
+
+        // Counterparty settles both sides of a swap, all or nothing
+        fn accept_swap(origin, swap_id: SwapId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_accept_swap(&sender, swap_id)?;
+
+            Ok(())
+        }
+
+        // Return escrowed offer to proposer, by proposer or by anyone once it expired
+        fn cancel_swap(origin, swap_id: SwapId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_cancel_swap(&sender, swap_id)?;
+
+            Ok(())
+        }
+
+        // Allow signed mints of user paid by sender, zero mints removes the allowance
+        fn set_sponsor_allowance(origin, user: T::AccountId, mints: u32, amount: BalanceOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            if mints == 0 {
+                <SponsorAllowance<T>>::remove(&sender, &user);
+            } else {
+                <SponsorAllowance<T>>::insert(&sender, &user, SponsorLimit { mints, amount });
+            }
+
+            Self::deposit_event(RawEvent::SponsorAllowanceSet(sender, user, mints, amount));
+
+            Ok(())
+        }
+
+        // Mint a token by a request signed by its owner, submitted by a relayer.
+        // The sponsor of the request pays the fee to the relayer and the deposit.
+        fn mint_signed(
+            origin,
+            request: MintRequestOf<T>,
+            signature: <T as erc721::Trait>::Signature,
+            gas_limit: contracts::Gas
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_mint_signed(&sender, request, &signature, gas_limit)?;
+
+            Ok(())
+        }
+
+        // Move an owned token to the multi-account of co-owners, their actions need threshold confirmations
+        fn set_co_owners(origin, token_id: T::Hash, owners: Vec<T::AccountId>, threshold: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_set_co_owners(&sender, &token_id, owners, threshold)?;
+
+            Ok(())
+        }
+
+        // Propose a transfer, burn or approval of a co-owned token until expiry block
+        fn propose_action(
+            origin,
+            token_id: T::Hash,
+            action: CoOwnerAction<T::AccountId>,
+            expiry: T::BlockNumber
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_propose_action(&sender, &token_id, action, expiry)?;
+
+            Ok(())
+        }
+
+        // Confirm a proposal, the last confirmation needed executes it
+        fn confirm_proposal(origin, proposal_id: ProposalId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_confirm_proposal(&sender, proposal_id)?;
+
+            Ok(())
+        }
+
+        // Remove a proposal, by proposer or by anyone once it expired
+        fn cancel_proposal(origin, proposal_id: ProposalId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_cancel_proposal(&sender, proposal_id)?;
+
+            Ok(())
+        }
+
+        // Mint a new token with a user until the expiry block, the validation
+        // contract receives the user as `token_user` in its parameter
+        fn mint_with_user(origin,
+            registry_uid: RegistryUid,
+            token_id: T::Hash,
+            metadata: Vec<u8>,
+            anchor_id: T::Hash,
+            proofs: Vec<Proof>,
+            static_proofs: [H256;3],
+            user: T::AccountId,
+            expires: T::BlockNumber,
+            value: contracts::BalanceOf<T>,
+            gas_limit: contracts::Gas,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_mint(
+                &sender,
+                registry_uid,
+                token_id,
+                metadata,
+                &anchor_id,
+                &proofs,
+                &static_proofs,
+                Some((user, expires)),
+                value,
+                gas_limit,
+            )?;
+
+            Ok(())
+        }
+    }
+}
+
+// Deposits and metadata of registry tokens follow the tokens of the default collection
+impl<T: Trait> erc721::OnTokenTransfer<T::CollectionId, T::AccountId, T::Hash> for Module<T> {
+    fn on_transfer(
+        collection_id: &T::CollectionId,
+        _from: &T::AccountId,
+        to: &T::AccountId,
+        token_id: &T::Hash,
+    ) {
+        if *collection_id == <erc721::Module<T>>::default_collection() {
+            Self::move_deposit(token_id, to);
+        }
+    }
+}
+
+impl<T: Trait> erc721::OnMint<T::CollectionId, T::AccountId, T::Hash> for Module<T> {}
+
+impl<T: Trait> erc721::OnBurn<T::CollectionId, T::AccountId, T::Hash> for Module<T> {
+    fn on_burn(collection_id: &T::CollectionId, _owner: &T::AccountId, token_id: &T::Hash) {
+        if *collection_id == <erc721::Module<T>>::default_collection() {
+            Self::release_token(token_id);
+        }
+    }
+}
+
+// Shares of a fungible registry document release its deposit once all are burned
+impl<T: Trait> erc1155::OnBurn<T::AccountId, T::Hash, <T as erc1155::Trait>::TokenBalance>
+    for Module<T>
+{
+    fn on_burn(_owner: &T::AccountId, id: &T::Hash, supply: <T as erc1155::Trait>::TokenBalance) {
+        if supply.is_zero() {
+            Self::release_token(id);
+        }
+    }
+}
+
+// Calls `on_erc721_received` of a contract receiving a token by `safe_transfer_from`
+pub struct ContractReceiver<T>(PhantomData<T>);
+
+impl<T: Trait> erc721::OnErc721Received<T::CollectionId, T::AccountId, T::Hash>
+    for ContractReceiver<T>
+{
+    fn on_erc721_received(
+        collection_id: &T::CollectionId,
+        operator: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        token_id: &T::Hash,
+        data: Vec<u8>,
+    ) -> bool {
+        // Accounts without contract code accept all tokens
+        let is_contract = <contracts::ContractInfoOf<T>>::get(to)
+            .and_then(|info| info.get_alive())
+            .is_some();
+        if !is_contract {
+            return true;
+        }
+
+        // Encode 4 bytes selector of the receiver message and its parameters
+        let mut call = message_selector("on_erc721_received").encode();
+        call.append(&mut (collection_id, operator, from, token_id, data).encode());
+
+        // Contract must return the magic value to accept the token
+        match <contracts::Module<T>>::bare_call(
+            operator.clone(),
+            to.clone(),
+            Zero::zero(),
+            T::ReceiverGasLimit::get(),
+            call,
+        ) {
+            Ok(output) => output.is_success() && output.data == erc721::ERC721_RECEIVED.encode(),
+            Err(_) => false,
+        }
+    }
+
+    fn max_weight() -> Weight {
+        T::ReceiverGasLimit::get().saturated_into()
     }
 }
 
 impl<T: Trait> Module<T> {
+    // Register validation function for a new registry uid
+    fn register_validation_fn(
+        sender: &T::AccountId,
+        validation_fn_addr: &T::AccountId,
+    ) -> Result<RegistryUid, DispatchError> {
+        // Ensure function not registered before
+        if <ValidationFunctionMap<T>>::exists(validation_fn_addr) {
+            return Err(Error::<T>::ValidationFunctionAlreadyExists.into());
+        }
+
+        // Reserve fee for validation function
+        <T as Trait>::Currency::reserve(sender, T::NFTValidationRegistryDeposit::get())?;
+
+        // Keep old value for event
+        let uid = NextRegistryId::get();
+
+        // Write state
+        <ValidationFn<T>>::insert(&uid, validation_fn_addr);
+
+        // Update next registry id
+        NextRegistryId::mutate(|value| *value += 1);
+
+        // Put the validation function in set
+        <ValidationFunctionMap<T>>::insert(validation_fn_addr, true);
+
+        // Registry owner manages royalties
+        <RegistryOwner<T>>::insert(&uid, sender);
+
+        // Store event
+        Self::deposit_event(RawEvent::NewRegistry(sender.clone(), uid));
+
+        Ok(uid)
+    }
+
+    // Ask the validation contract of the registry to mint a token to sender,
+    // with a user until the expiry block if one is given
+    #[allow(clippy::too_many_arguments)]
+    pub fn _mint(
+        sender: &T::AccountId,
+        registry_uid: RegistryUid,
+        token_id: T::Hash,
+        metadata: Vec<u8>,
+        anchor_id: &T::Hash,
+        proofs: &Vec<Proof>,
+        static_proofs: &[H256; 3],
+        token_user: Option<(T::AccountId, T::BlockNumber)>,
+        value: contracts::BalanceOf<T>,
+        gas_limit: contracts::Gas,
+    ) -> DispatchResult {
+        if token_user.is_some() && Self::registry_shares(registry_uid).is_some() {
+            return Err(Error::<T>::TokenUserNotSupported.into());
+        }
+
+        // Collect all leaves in proofs
+        let proof_leaves = proofs.iter().map(|proof| proof.leaf_hash).collect();
+
+        // Put parameters into single struct.
+        let contract_parameter = ContractParameter::<T::Hash, T::AccountId> {
+            uid: registry_uid,
+            token_id: token_id,
+            token_owner: sender.clone(),
+            metadata,
+            proof_leaves: proof_leaves,
+            token_user: token_user.as_ref().map(|(user, _)| user.clone()),
+            owner_minted: Self::minted_count(registry_uid, sender),
+        };
+
+        if let Some(token_user) = &token_user {
+            <PendingUser<T>>::insert(&token_id, token_user);
+        }
+        let result = Self::call_validation_fn(
+            sender,
+            contract_parameter,
+            anchor_id,
+            proofs,
+            static_proofs,
+            value,
+            gas_limit,
+        );
+        <PendingUser<T>>::remove(&token_id);
+
+        result
+    }
+
+    // Validate proofs of a document and call the validation contract of the registry
+    // with the parameter, the contract calls back finish_mint to mint the token
+    fn call_validation_fn(
+        caller: &T::AccountId,
+        parameter: ContractParameter<T::Hash, T::AccountId>,
+        anchor_id: &T::Hash,
+        proofs: &Vec<Proof>,
+        static_proofs: &[H256; 3],
+        value: contracts::BalanceOf<T>,
+        gas_limit: contracts::Gas,
+    ) -> DispatchResult {
+        // Contract registered for the uid
+        let validation_function = Self::ensure_validation_fn_exists(parameter.uid)?;
+
+        // Ensure token id not existed
+        Self::ensure_token_not_existed(&parameter.token_id)?;
+
+        // Get the doc root
+        let doc_root = Self::get_document_root(anchor_id)?;
+
+        // Verify the proof against document root
+        Self::validate_proofs(&doc_root, proofs, static_proofs)?;
+
+        // Encode 4 bytes selector of validate method in contract
+        let mut call = message_selector("validate").encode();
+
+        // Append the parameter after method, as bytes since validate takes a Vec<u8>
+        call.append(&mut Encode::encode(&parameter.encode()));
+
+        // Call the contract via bare call
+        <contracts::Module<T>>::bare_call(
+            caller.clone(),
+            validation_function,
+            value,
+            gas_limit,
+            call,
+        );
+
+        Ok(())
+    }
+
+    // Mint by a signed request, nothing is charged and the nonce is not spent if the mint fails
+    pub fn _mint_signed(
+        relayer: &T::AccountId,
+        request: MintRequestOf<T>,
+        signature: &<T as erc721::Trait>::Signature,
+        gas_limit: contracts::Gas,
+    ) -> DispatchResult {
+        if <system::Module<T>>::block_number() > request.deadline {
+            return Err(Error::<T>::MintRequestExpired.into());
+        }
+
+        if request.nonce != Self::mint_nonce(&request.owner) {
+            return Err(Error::<T>::InvalidMintNonce.into());
+        }
+
+        if !signature.verify(&Self::mint_request_payload(&request)[..], &request.owner) {
+            return Err(Error::<T>::InvalidMintSignature.into());
+        }
+
+        // Sponsor pays the relayer fee and the deposit within the owner's allowance
+        let sponsor = request.sponsor.clone();
+        let mut allowance = Self::sponsor_allowance(&sponsor, &request.owner)
+            .ok_or(Error::<T>::SponsorAllowanceExceeded)?;
+        let charged = request
+            .fee
+            .saturating_add(Self::compute_metadata_fee(request.metadata.len() as u32));
+        if allowance.mints == 0 || charged > allowance.amount {
+            return Err(Error::<T>::SponsorAllowanceExceeded.into());
+        }
+
+        // Fee is paid before the mint and refunded if the contract does not mint
+        if *relayer != sponsor {
+            <T as Trait>::Currency::transfer(
+                &sponsor,
+                relayer,
+                request.fee,
+                ExistenceRequirement::AllowDeath,
+            )?;
+        }
+
+        let token_id = request.token_id;
+        let contract_parameter = ContractParameter::<T::Hash, T::AccountId> {
+            uid: request.registry_uid,
+            token_id,
+            token_owner: request.owner.clone(),
+            metadata: request.metadata,
+            proof_leaves: request.proofs.iter().map(|proof| proof.leaf_hash).collect(),
+            token_user: None,
+            owner_minted: Self::minted_count(request.registry_uid, &request.owner),
+        };
+
+        <PendingSponsor<T>>::insert(&token_id, &sponsor);
+        let result = Self::call_validation_fn(
+            relayer,
+            contract_parameter,
+            &request.anchor_id,
+            &request.proofs,
+            &request.static_proofs,
+            Zero::zero(),
+            gas_limit,
+        );
+        <PendingSponsor<T>>::remove(&token_id);
+
+        if result.is_err() || Self::sponsor_of(&token_id).as_ref() != Some(&sponsor) {
+            if *relayer != sponsor {
+                let _ = <T as Trait>::Currency::transfer(
+                    relayer,
+                    &sponsor,
+                    request.fee,
+                    ExistenceRequirement::AllowDeath,
+                );
+            }
+            result?;
+            return Err(Error::<T>::SponsoredMintFailed.into());
+        }
+
+        allowance.mints -= 1;
+        allowance.amount -= charged;
+        if allowance.mints == 0 {
+            <SponsorAllowance<T>>::remove(&sponsor, &request.owner);
+        } else {
+            <SponsorAllowance<T>>::insert(&sponsor, &request.owner, allowance);
+        }
+        <MintNonces<T>>::insert(&request.owner, request.nonce.saturating_add(1));
+
+        Self::deposit_event(RawEvent::SponsoredMint(
+            sponsor,
+            request.owner,
+            token_id,
+            request.fee,
+        ));
+
+        Ok(())
+    }
+
+    // SCALE encoded message signed by the owner for a mint request, separated by the
+    // mint domain and the genesis hash from other messages and chains
+    pub fn mint_request_payload(request: &MintRequestOf<T>) -> Vec<u8> {
+        (
+            MINT_DOMAIN,
+            <system::Module<T>>::block_hash(T::BlockNumber::zero()),
+            request,
+        )
+            .encode()
+    }
+
+    // Deterministic account of co-owners and threshold, the same for any order of owners
+    pub fn multi_account_id(
+        owners: &[T::AccountId],
+        threshold: u32,
+    ) -> Result<T::AccountId, DispatchError> {
+        let mut owners = owners.to_vec();
+        owners.sort();
+        owners.dedup();
+
+        let hash = (MULTI_ACCOUNT_DOMAIN, owners, threshold)
+            .using_encoded(<T as system::Trait>::Hashing::hash);
+
+        // A failed decode must not fall back to a shared default account
+        T::AccountId::decode(&mut hash.as_ref()).map_err(|_| Error::<T>::InvalidCoOwners.into())
+    }
+
+    // Move token from owner to the multi-account of co-owners
+    pub fn _set_co_owners(
+        owner: &T::AccountId,
+        token_id: &T::Hash,
+        mut owners: Vec<T::AccountId>,
+        threshold: u32,
+    ) -> DispatchResult {
+        let count = owners.len();
+        owners.sort();
+        owners.dedup();
+        if owners.len() != count
+            || count < 2
+            || count > MAX_CO_OWNERS
+            || threshold == 0
+            || threshold as usize > count
+        {
+            return Err(Error::<T>::InvalidCoOwners.into());
+        }
+
+        let multi_account = Self::multi_account_id(&owners, threshold)?;
+        <erc721::Module<T>>::_transfer_from(owner, owner, &multi_account, token_id)?;
+        <CoOwners<T>>::insert(&multi_account, CoOwnership { owners, threshold });
+
+        Self::deposit_event(RawEvent::CoOwned(*token_id, multi_account, threshold));
+
+        Ok(())
+    }
+
+    // Create a proposal confirmed by proposer, executed at once if that meets the threshold
+    pub fn _propose_action(
+        proposer: &T::AccountId,
+        token_id: &T::Hash,
+        action: CoOwnerAction<T::AccountId>,
+        expiry: T::BlockNumber,
+    ) -> DispatchResult {
+        let owner = <erc721::Module<T>>::owner_of(token_id).ok_or(Error::<T>::NotCoOwned)?;
+        let co_owners = Self::ensure_co_owner(proposer, &owner)?;
+
+        if expiry <= <system::Module<T>>::block_number() {
+            return Err(Error::<T>::ProposalExpired.into());
+        }
+
+        let proposal_id = NextProposalId::get();
+        let executed = co_owners.threshold <= 1;
+        if executed {
+            Self::execute_action(&owner, token_id, &action)?;
+        } else {
+            <Proposals<T>>::insert(
+                proposal_id,
+                Proposal {
+                    proposer: proposer.clone(),
+                    owner,
+                    token_id: *token_id,
+                    action,
+                    confirmations: [proposer.clone()].to_vec(),
+                    expiry,
+                },
+            );
+        }
+        NextProposalId::mutate(|id| *id += 1);
+
+        Self::deposit_event(RawEvent::ProposalCreated(
+            proposal_id,
+            proposer.clone(),
+            *token_id,
+        ));
+        if executed {
+            Self::deposit_event(RawEvent::ProposalExecuted(proposal_id));
+        }
+
+        Ok(())
+    }
+
+    // Add a confirmation, the action is executed when the threshold is met.
+    // A failed action keeps the proposal without the confirmation.
+    pub fn _confirm_proposal(co_owner: &T::AccountId, proposal_id: ProposalId) -> DispatchResult {
+        let mut proposal = Self::proposals(proposal_id).ok_or(Error::<T>::ProposalNotFound)?;
+        let co_owners = Self::ensure_co_owner(co_owner, &proposal.owner)?;
+
+        if <system::Module<T>>::block_number() >= proposal.expiry {
+            return Err(Error::<T>::ProposalExpired.into());
+        }
+
+        if proposal.confirmations.contains(co_owner) {
+            return Err(Error::<T>::AlreadyConfirmed.into());
+        }
+
+        proposal.confirmations.push(co_owner.clone());
+        if proposal.confirmations.len() >= co_owners.threshold as usize {
+            Self::execute_action(&proposal.owner, &proposal.token_id, &proposal.action)?;
+            <Proposals<T>>::remove(proposal_id);
+
+            Self::deposit_event(RawEvent::ProposalConfirmed(proposal_id, co_owner.clone()));
+            Self::deposit_event(RawEvent::ProposalExecuted(proposal_id));
+        } else {
+            <Proposals<T>>::insert(proposal_id, proposal);
+
+            Self::deposit_event(RawEvent::ProposalConfirmed(proposal_id, co_owner.clone()));
+        }
+
+        Ok(())
+    }
+
+    pub fn _cancel_proposal(sender: &T::AccountId, proposal_id: ProposalId) -> DispatchResult {
+        let proposal = Self::proposals(proposal_id).ok_or(Error::<T>::ProposalNotFound)?;
+
+        if proposal.proposer != *sender && <system::Module<T>>::block_number() < proposal.expiry {
+            return Err(Error::<T>::NotProposer.into());
+        }
+
+        <Proposals<T>>::remove(proposal_id);
+
+        Self::deposit_event(RawEvent::ProposalCancelled(proposal_id));
+
+        Ok(())
+    }
+
+    // Ensure account is a co-owner of the multi-account, returns its co-owners
+    fn ensure_co_owner(
+        account: &T::AccountId,
+        multi_account: &T::AccountId,
+    ) -> Result<CoOwnership<T::AccountId>, DispatchError> {
+        let co_owners = Self::co_owners(multi_account).ok_or(Error::<T>::NotCoOwned)?;
+
+        if !co_owners.owners.contains(account) {
+            return Err(Error::<T>::NotCoOwner.into());
+        }
+
+        Ok(co_owners)
+    }
+
+    // Execute an action on token as its multi-account owner
+    fn execute_action(
+        owner: &T::AccountId,
+        token_id: &T::Hash,
+        action: &CoOwnerAction<T::AccountId>,
+    ) -> DispatchResult {
+        match action {
+            CoOwnerAction::TransferFrom(to) => {
+                <erc721::Module<T>>::_transfer_from(owner, owner, to, token_id)
+            }
+            CoOwnerAction::Burn => <erc721::Module<T>>::_burn(owner, token_id),
+            CoOwnerAction::Approve(to) => <erc721::Module<T>>::_approve(owner, to, token_id),
+        }
+    }
+
+    // Royalty of a token sold for price, the token royalty or else its registry royalty.
+    // Returns the recipient and the amount split off the price.
+    pub fn royalty_info(
+        token_id: &T::Hash,
+        price: BalanceOf<T>,
+    ) -> Option<(T::AccountId, BalanceOf<T>)> {
+        let royalty = Self::token_royalty(token_id).or_else(|| {
+            if <RegistryUidForTokenId<T>>::exists(token_id) {
+                Self::registry_royalty(Self::registry_uid_for_token_id(token_id))
+            } else {
+                None
+            }
+        })?;
+
+        // Basis points to parts per million
+        let amount = Permill::from_parts(u32::from(royalty.rate) * 100) * price;
+
+        Some((royalty.recipient, amount))
+    }
+
+    // Pay price of a token from buyer to seller, and royalty from seller to its recipient.
+    // The royalty is checked before the price is paid, if it still fails the price is refunded to buyer.
+    // Buyer can not be the seller, royalty is only charged on a price actually paid.
+    pub fn pay_with_royalty(
+        buyer: &T::AccountId,
+        seller: &T::AccountId,
+        token_id: &T::Hash,
+        price: BalanceOf<T>,
+    ) -> DispatchResult {
+        if buyer == seller {
+            return Err(Error::<T>::BuyerIsSeller.into());
+        }
+
+        let royalty_info = match Self::royalty_info(token_id, price) {
+            Some((recipient, royalty)) if !royalty.is_zero() => Some((recipient, royalty)),
+            _ => None,
+        };
+
+        // No transactional storage, check the royalty before moving currency
+        if let Some((recipient, royalty)) = &royalty_info {
+            if recipient != seller && !Self::can_pay(seller, recipient, *royalty, price) {
+                return Err(Error::<T>::RoyaltyNotPayable.into());
+            }
+        }
+
+        <T as Trait>::Currency::transfer(buyer, seller, price, ExistenceRequirement::AllowDeath)?;
+
+        let (recipient, royalty) = match royalty_info {
+            Some(royalty_info) => royalty_info,
+            None => return Ok(()),
+        };
+
+        if recipient != *seller {
+            if let Err(e) = <T as Trait>::Currency::transfer(
+                seller,
+                &recipient,
+                royalty,
+                ExistenceRequirement::AllowDeath,
+            ) {
+                <T as Trait>::Currency::transfer(
+                    seller,
+                    buyer,
+                    price,
+                    ExistenceRequirement::AllowDeath,
+                )?;
+                return Err(e);
+            }
+        }
+
+        Self::deposit_event(RawEvent::RoyaltyPaid(*token_id, recipient, royalty));
+
+        Ok(())
+    }
+
+    // Account of the swap escrow
+    pub fn swap_account_id() -> T::AccountId {
+        SWAP_MODULE_ID.into_account()
+    }
+
+    // Lock offered currency and move offered tokens to escrow
+    pub fn _propose_swap(
+        proposer: &T::AccountId,
+        offer_tokens: Vec<T::Hash>,
+        offer_amount: BalanceOf<T>,
+        want_tokens: Vec<T::Hash>,
+        want_amount: BalanceOf<T>,
+        counterparty: T::AccountId,
+        expiry: T::BlockNumber,
+    ) -> DispatchResult {
+        if counterparty == *proposer {
+            return Err(Error::<T>::SwapWithSelf.into());
+        }
+
+        if offer_tokens.is_empty()
+            && offer_amount.is_zero()
+            && want_tokens.is_empty()
+            && want_amount.is_zero()
+        {
+            return Err(Error::<T>::EmptySwap.into());
+        }
+
+        if expiry <= <system::Module<T>>::block_number() {
+            return Err(Error::<T>::SwapExpired.into());
+        }
+
+        // Wanted tokens are checked on accept, counterparty may not own them yet
+        let escrow = Self::swap_account_id();
+        Self::ensure_can_move_tokens(proposer, proposer, &escrow, &offer_tokens)?;
+        <erc721::Module<T>>::ensure_batch_size(&want_tokens)?;
+        <erc721::Module<T>>::ensure_no_duplicate_tokens(&want_tokens)?;
+
+        let locked = Self::swap_locked(proposer).saturating_add(offer_amount);
+        if <T as Trait>::Currency::free_balance(proposer) < locked {
+            return Err(Error::<T>::InsufficientSwapBalance.into());
+        }
+
+        Self::move_tokens(proposer, proposer, &escrow, offer_tokens.clone())?;
+        Self::set_swap_lock(proposer, locked);
+
+        let swap_id = NextSwapId::get();
+        <Swaps<T>>::insert(
+            swap_id,
+            Swap {
+                proposer: proposer.clone(),
+                counterparty: counterparty.clone(),
+                offer_tokens,
+                offer_amount,
+                want_tokens,
+                want_amount,
+                expiry,
+            },
+        );
+        NextSwapId::mutate(|id| *id += 1);
+
+        Self::deposit_event(RawEvent::SwapProposed(
+            swap_id,
+            proposer.clone(),
+            counterparty,
+        ));
+
+        Ok(())
+    }
+
+    // Move wanted side to proposer and offered side to counterparty
+    pub fn _accept_swap(counterparty: &T::AccountId, swap_id: SwapId) -> DispatchResult {
+        let swap = Self::swaps(swap_id).ok_or(Error::<T>::SwapNotFound)?;
+
+        if swap.counterparty != *counterparty {
+            return Err(Error::<T>::NotSwapCounterparty.into());
+        }
+
+        if <system::Module<T>>::block_number() >= swap.expiry {
+            return Err(Error::<T>::SwapExpired.into());
+        }
+
+        // No transactional storage, check both sides before changing any
+        let escrow = Self::swap_account_id();
+        let proposer = &swap.proposer;
+        Self::ensure_can_move_tokens(counterparty, counterparty, proposer, &swap.want_tokens)?;
+        Self::ensure_can_move_tokens(&escrow, &escrow, counterparty, &swap.offer_tokens)?;
+
+        // Offered currency is paid out of the swap lock, the wanted currency may be paid out of it
+        let (offer_amount, want_amount) = (swap.offer_amount, swap.want_amount);
+        let locked = Self::swap_locked(proposer);
+        let unlocked = locked.saturating_sub(offer_amount);
+        Self::set_swap_lock(proposer, unlocked);
+        let can_pay = Self::can_pay(proposer, counterparty, offer_amount, Zero::zero())
+            && Self::can_pay(counterparty, proposer, want_amount, offer_amount);
+        Self::set_swap_lock(proposer, locked);
+        if !can_pay {
+            return Err(Error::<T>::SwapPaymentFailed.into());
+        }
+
+        // Checked above, tokens move before currency so nothing fails after a payment.
+        // The want side does not touch the escrow.
+        Self::move_tokens(counterparty, counterparty, proposer, swap.want_tokens)?;
+        Self::move_tokens(&escrow, &escrow, counterparty, swap.offer_tokens)?;
+
+        Self::set_swap_lock(proposer, unlocked);
+        Self::pay(proposer, counterparty, offer_amount)?;
+        Self::pay(counterparty, proposer, want_amount)?;
+
+        <Swaps<T>>::remove(swap_id);
+
+        Self::deposit_event(RawEvent::SwapAccepted(swap_id));
+
+        Ok(())
+    }
+
+    // Return offered tokens from escrow and unlock offered currency
+    pub fn _cancel_swap(sender: &T::AccountId, swap_id: SwapId) -> DispatchResult {
+        let swap = Self::swaps(swap_id).ok_or(Error::<T>::SwapNotFound)?;
+
+        if swap.proposer != *sender && <system::Module<T>>::block_number() < swap.expiry {
+            return Err(Error::<T>::NotSwapProposer.into());
+        }
+
+        let escrow = Self::swap_account_id();
+        Self::ensure_can_move_tokens(&escrow, &escrow, &swap.proposer, &swap.offer_tokens)?;
+
+        Self::move_tokens(&escrow, &escrow, &swap.proposer, swap.offer_tokens)?;
+        let locked = Self::swap_locked(&swap.proposer);
+        Self::set_swap_lock(&swap.proposer, locked.saturating_sub(swap.offer_amount));
+
+        <Swaps<T>>::remove(swap_id);
+
+        Self::deposit_event(RawEvent::SwapCancelled(swap_id));
+
+        Ok(())
+    }
+
+    // Ensure sender can move all tokens of a swap side
+    fn ensure_can_move_tokens(
+        sender: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        token_ids: &[T::Hash],
+    ) -> DispatchResult {
+        <erc721::Module<T>>::ensure_batch_size(token_ids)?;
+        <erc721::Module<T>>::ensure_no_duplicate_tokens(token_ids)?;
+
+        for token_id in token_ids.iter() {
+            <erc721::Module<T>>::ensure_can_transfer(sender, from, to, token_id)?;
+        }
+
+        Ok(())
+    }
+
+    // Lock currency offered by proposer in open swaps, removed when nothing is offered
+    fn set_swap_lock(proposer: &T::AccountId, amount: BalanceOf<T>) {
+        if amount.is_zero() {
+            <T as Trait>::Currency::remove_lock(SWAP_LOCK_ID, proposer);
+            <SwapLocked<T>>::remove(proposer);
+        } else {
+            <T as Trait>::Currency::set_lock(
+                SWAP_LOCK_ID,
+                proposer,
+                amount,
+                T::BlockNumber::max_value(),
+                WithdrawReasons::all(),
+            );
+            <SwapLocked<T>>::insert(proposer, amount);
+        }
+    }
+
+    // Pay currency of a swap side, a side may have no currency
+    fn pay(from: &T::AccountId, to: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+        if amount.is_zero() {
+            return Ok(());
+        }
+
+        <T as Trait>::Currency::transfer(from, to, amount, ExistenceRequirement::AllowDeath)
+    }
+
+    // Check an account can pay an amount once it received another, the payment is
+    // not frozen by a lock of the account and creates the recipient
+    fn can_pay(
+        from: &T::AccountId,
+        to: &T::AccountId,
+        amount: BalanceOf<T>,
+        received: BalanceOf<T>,
+    ) -> bool {
+        if amount.is_zero() {
+            return true;
+        }
+
+        let free = <T as Trait>::Currency::free_balance(from).saturating_add(received);
+
+        match free.checked_sub(&amount) {
+            Some(new_balance) => {
+                (!<T as Trait>::Currency::total_balance(to).is_zero()
+                    || amount >= <T as Trait>::Currency::minimum_balance())
+                    && <T as Trait>::Currency::ensure_can_withdraw(
+                        from,
+                        amount,
+                        WithdrawReason::Transfer.into(),
+                        new_balance,
+                    )
+                    .is_ok()
+            }
+            None => false,
+        }
+    }
+
+    // Move tokens of a swap side, a side may have no tokens
+    fn move_tokens(
+        sender: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        token_ids: Vec<T::Hash>,
+    ) -> DispatchResult {
+        if token_ids.is_empty() {
+            return Ok(());
+        }
+
+        <erc721::Module<T>>::_batch_transfer_from(sender, from, to, token_ids)
+    }
+
+    // Royalty to store, none if the rate is zero
+    fn royalty(
+        recipient: T::AccountId,
+        rate: BasisPoints,
+    ) -> Result<Option<RoyaltyInfo<T::AccountId>>, DispatchError> {
+        if rate > MAX_BASIS_POINTS {
+            return Err(Error::<T>::RoyaltyRateTooHigh.into());
+        }
+
+        if rate == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(RoyaltyInfo { recipient, rate }))
+    }
+
+    // Ensure sender registered the registry
+    fn ensure_registry_owner(uid: RegistryUid, sender: &T::AccountId) -> DispatchResult {
+        match Self::registry_owner(uid) {
+            Some(owner) if owner == *sender => Ok(()),
+            _ => Err(Error::<T>::NotRegistryOwner.into()),
+        }
+    }
+
+    // Move deposit of a token to its new owner. It is kept by the current holder
+    // if new owner has no balance, like the fractional vault, or can not reserve it.
+    fn move_deposit(token_id: &T::Hash, to: &T::AccountId) {
+        // Sponsor keeps the deposit it paid until the token is burned
+        if <SponsorOf<T>>::exists(token_id) {
+            return;
+        }
+
+        let holder = match Self::deposit_holder(token_id) {
+            Some(holder) => holder,
+            None => return,
+        };
+        if holder == *to {
+            return;
+        }
+
+        let total_deposit = Self::deposit_by_token_id(token_id);
+        if !Self::can_take_deposit(&holder, to, total_deposit) {
+            Self::deposit_event(RawEvent::DepositKept(*token_id, holder));
+            return;
+        }
+
+        // Repatriated deposit is free balance of new owner, reserve it again
+        let _ = <T as Trait>::Currency::repatriate_reserved(&holder, to, total_deposit);
+        if <T as Trait>::Currency::reserve(to, total_deposit).is_ok() {
+            <DepositHolder<T>>::insert(token_id, to);
+        } else {
+            // Checked above, the deposit is paid out to the new owner and nothing is held
+            <DepositHolder<T>>::remove(token_id);
+        }
+    }
+
+    // Check the whole deposit can be repatriated from holder to account and reserved again,
+    // the repatriated funds are not frozen by a lock of the account
+    fn can_take_deposit(holder: &T::AccountId, to: &T::AccountId, deposit: BalanceOf<T>) -> bool {
+        let free = <T as Trait>::Currency::free_balance(to);
+
+        !<T as Trait>::Currency::total_balance(to).is_zero()
+            && <T as Trait>::Currency::reserved_balance(holder) >= deposit
+            && <T as Trait>::Currency::ensure_can_withdraw(
+                to,
+                deposit,
+                WithdrawReason::Reserve.into(),
+                free,
+            )
+            .is_ok()
+    }
+
+    // Repay the deposit of a burned token to its holder and remove its storage
+    fn release_token(token_id: &T::Hash) {
+        if let Some(holder) = <DepositHolder<T>>::take(token_id) {
+            <T as Trait>::Currency::unreserve(&holder, Self::deposit_by_token_id(token_id));
+        }
+
+        <TokenMetadata<T>>::remove(token_id);
+        <DepositByTokenId<T>>::remove(token_id);
+        <RegistryUidForTokenId<T>>::remove(token_id);
+        <TokenRoyalty<T>>::remove(token_id);
+        <SponsorOf<T>>::remove(token_id);
+    }
+
+    // Ensure token id is neither an ERC721 token nor an ERC1155 token id
+    fn ensure_token_not_existed(token_id: &T::Hash) -> DispatchResult {
+        <erc721::Module<T>>::ensure_token_not_existed(token_id)?;
+
+        if <erc1155::Module<T>>::total_supply(token_id).is_some() {
+            return Err(erc1155::Error::<T>::TokenAlreadyExists.into());
+        }
+
+        Ok(())
+    }
+
     // Get the document root via anchor id
     fn get_document_root(anchor_id: &T::Hash) -> Result<T::Hash, DispatchError> {
         match <anchor::Module<T>>::get_anchor_by_id(*anchor_id) {
@@ -0,0 +1,295 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+use sp_runtime::traits::Zero;
+use sp_std::prelude::*;
+use support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::{DispatchError, DispatchResult},
+};
+use system::ensure_signed;
+
+use crate::{erc721, BalanceOf, RegistryUid, RegistryUidForTokenId};
+
+// Encoding library
+use codec::{Decode, Encode};
+
+#[cfg(test)]
+mod tests;
+
+// Token offered for a fixed price until expiry block
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Listing<AccountId, Balance, BlockNumber> {
+    pub registry_uid: RegistryUid,
+    pub seller: AccountId,
+    pub price: Balance,
+    pub expiry: BlockNumber,
+}
+
+type ListingOf<T> =
+    Listing<<T as system::Trait>::AccountId, BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+
+pub trait Trait: crate::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+}
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        // Token not listed
+        NotListed,
+
+        // Token already listed
+        AlreadyListed,
+
+        // Price must be more than zero
+        ZeroPrice,
+
+        // Expiry block already passed
+        InvalidExpiry,
+
+        // Listing expired
+        ListingExpired,
+
+        // Sender is not the seller of the listing
+        NotSeller,
+
+        // Seller can not buy own token
+        BuyerIsSeller,
+
+        // Listing price is more than the buyer accepts
+        PriceTooHigh,
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+        where
+        <T as system::Trait>::AccountId,
+        <T as system::Trait>::Hash,
+        <T as system::Trait>::BlockNumber,
+        Balance = BalanceOf<T> {
+        // Seller listed token for price until expiry block
+        Listed(AccountId, Hash, Balance, BlockNumber),
+
+        // Listing removed by seller, or because token moved or burned
+        ListingCancelled(Hash),
+
+        // Listing price changed
+        PriceUpdated(Hash, Balance),
+
+        // Token sold by seller to buyer for price
+        Sold(AccountId, AccountId, Hash, Balance),
+    }
+);
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Marketplace {
+        // Listing of each token
+        pub Listings get(listings): map T::Hash => Option<ListingOf<T>>;
+
+        // Tokens listed in each registry
+        pub ListingsByRegistry get(listings_by_registry): map hasher(blake2_256) RegistryUid => Vec<T::Hash>;
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin  {
+        fn deposit_event() = default;
+
+        // List owned token for a fixed price until expiry block
+        fn list(origin, token_id: T::Hash, price: BalanceOf<T>, expiry: T::BlockNumber) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_list(&sender, &token_id, price, expiry)?;
+
+            Ok(())
+        }
+
+        // Remove listing, by seller or by anyone once it expired
+        fn cancel_listing(origin, token_id: T::Hash) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let listing = Self::listings(&token_id).ok_or(Error::<T>::NotListed)?;
+            if listing.seller != sender && !Self::is_expired(&listing) {
+                return Err(Error::<T>::NotSeller.into());
+            }
+
+            Self::remove_listing(&token_id);
+            Self::deposit_event(RawEvent::ListingCancelled(token_id));
+
+            Ok(())
+        }
+
+        // Buy listed token, fails if listing price is more than max price
+        fn buy(origin, token_id: T::Hash, max_price: BalanceOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            Self::_buy(&sender, &token_id, max_price)?;
+
+            Ok(())
+        }
+
+        // Change price of a listing
+        fn update_price(origin, token_id: T::Hash, price: BalanceOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            if price.is_zero() {
+                return Err(Error::<T>::ZeroPrice.into());
+            }
+
+            let mut listing = Self::listings(&token_id).ok_or(Error::<T>::NotListed)?;
+            if listing.seller != sender {
+                return Err(Error::<T>::NotSeller.into());
+            }
+
+            listing.price = price;
+            <Listings<T>>::insert(&token_id, listing);
+
+            Self::deposit_event(RawEvent::PriceUpdated(token_id, price));
+
+            Ok(())
+        }
+    }
+}
+
+// Listings are cleared when the token leaves its seller
+impl<T: Trait> erc721::OnTokenTransfer<T::CollectionId, T::AccountId, T::Hash> for Module<T> {
+    fn on_transfer(
+        collection_id: &T::CollectionId,
+        _from: &T::AccountId,
+        _to: &T::AccountId,
+        token_id: &T::Hash,
+    ) {
+        Self::clear_listing(collection_id, token_id);
+    }
+}
+
+impl<T: Trait> erc721::OnMint<T::CollectionId, T::AccountId, T::Hash> for Module<T> {}
+
+impl<T: Trait> erc721::OnBurn<T::CollectionId, T::AccountId, T::Hash> for Module<T> {
+    fn on_burn(collection_id: &T::CollectionId, _owner: &T::AccountId, token_id: &T::Hash) {
+        Self::clear_listing(collection_id, token_id);
+    }
+}
+
+impl<T: Trait> Module<T> {
+    // List a registry token owned by seller
+    pub fn _list(
+        seller: &T::AccountId,
+        token_id: &T::Hash,
+        price: BalanceOf<T>,
+        expiry: T::BlockNumber,
+    ) -> DispatchResult {
+        if price.is_zero() {
+            return Err(Error::<T>::ZeroPrice.into());
+        }
+
+        if expiry <= <system::Module<T>>::block_number() {
+            return Err(Error::<T>::InvalidExpiry.into());
+        }
+
+        if <Listings<T>>::exists(token_id) {
+            return Err(Error::<T>::AlreadyListed.into());
+        }
+
+        let uid = Self::ensure_registry_uid(token_id)?;
+
+        // Only owner lists, and the token must be able to move
+        <erc721::Module<T>>::ensure_token_exists(token_id)?;
+        if <erc721::Module<T>>::owner_of(token_id).as_ref() != Some(seller) {
+            return Err(erc721::Error::<T>::NotTokenOwner.into());
+        }
+        if <erc721::Module<T>>::is_locked(token_id) {
+            return Err(erc721::Error::<T>::TokenLocked.into());
+        }
+
+        <Listings<T>>::insert(
+            token_id,
+            Listing {
+                registry_uid: uid,
+                seller: seller.clone(),
+                price,
+                expiry,
+            },
+        );
+        <ListingsByRegistry<T>>::mutate(uid, |tokens| tokens.push(*token_id));
+
+        Self::deposit_event(RawEvent::Listed(seller.clone(), *token_id, price, expiry));
+
+        Ok(())
+    }
+
+    // Pay listing price with royalty and transfer token to buyer, all or nothing
+    pub fn _buy(
+        buyer: &T::AccountId,
+        token_id: &T::Hash,
+        max_price: BalanceOf<T>,
+    ) -> DispatchResult {
+        let listing = Self::listings(token_id).ok_or(Error::<T>::NotListed)?;
+
+        if Self::is_expired(&listing) {
+            return Err(Error::<T>::ListingExpired.into());
+        }
+
+        if listing.seller == *buyer {
+            return Err(Error::<T>::BuyerIsSeller.into());
+        }
+
+        if listing.price > max_price {
+            return Err(Error::<T>::PriceTooHigh.into());
+        }
+
+        // No transactional storage, check the transfer before moving currency.
+        // Marketplace transfers on behalf of the seller listed the token.
+        let seller = &listing.seller;
+        <erc721::Module<T>>::ensure_can_transfer(seller, seller, buyer, token_id)?;
+
+        <crate::Module<T>>::pay_with_royalty(buyer, seller, token_id, listing.price)?;
+
+        Self::remove_listing(token_id);
+        <erc721::Module<T>>::_transfer_from(seller, seller, buyer, token_id)?;
+
+        Self::deposit_event(RawEvent::Sold(
+            seller.clone(),
+            buyer.clone(),
+            *token_id,
+            listing.price,
+        ));
+
+        Ok(())
+    }
+
+    // Listing can not be bought after its expiry block
+    fn is_expired(listing: &ListingOf<T>) -> bool {
+        <system::Module<T>>::block_number() >= listing.expiry
+    }
+
+    // Ensure token is minted by a registry, and get the registry uid
+    fn ensure_registry_uid(token_id: &T::Hash) -> Result<RegistryUid, DispatchError> {
+        if !<RegistryUidForTokenId<T>>::exists(token_id) {
+            return Err(crate::Error::<T>::TokenNotInRegistry.into());
+        }
+
+        Ok(<crate::Module<T>>::registry_uid_for_token_id(token_id))
+    }
+
+    // Remove listing of token from storage and registry index
+    fn remove_listing(token_id: &T::Hash) {
+        if let Some(listing) = <Listings<T>>::take(token_id) {
+            <ListingsByRegistry<T>>::mutate(listing.registry_uid, |tokens| {
+                tokens.retain(|t| t != token_id)
+            });
+        }
+    }
+
+    // Remove listing of a token moved or burned in the default collection
+    fn clear_listing(collection_id: &T::CollectionId, token_id: &T::Hash) {
+        if *collection_id != <erc721::Module<T>>::default_collection()
+            || !<Listings<T>>::exists(token_id)
+        {
+            return;
+        }
+
+        Self::remove_listing(token_id);
+        Self::deposit_event(RawEvent::ListingCancelled(*token_id));
+    }
+}
@@ -0,0 +1,206 @@
+#![cfg(test)]
+
+use super::*;
+use crate::mock::*;
+use crate::{RegistryOwner, RegistryUidForTokenId};
+use sp_core::H256;
+use support::assert_ok;
+
+const REGISTRY_ID: u64 = 0;
+const PRICE: u64 = 1_000;
+const EXPIRY: u64 = 10;
+
+// Mint a token as if it was minted by the registry
+fn registry_token(owner: u64, byte: u8) -> H256 {
+    let token_id = mint_test(owner, byte);
+    <RegistryUidForTokenId<NftRegistryTest>>::insert(&token_id, REGISTRY_ID);
+
+    token_id
+}
+
+fn list_test(seller: u64, token_id: H256, price: u64, expiry: u64, result: DispatchResult) {
+    assert_eq!(
+        Marketplace::list(Origin::signed(seller), token_id, price, expiry),
+        result
+    );
+
+    if result.is_ok() {
+        let listing = Marketplace::listings(token_id).unwrap();
+        assert_eq!(listing.seller, seller);
+        assert_eq!(listing.price, price);
+        assert!(Marketplace::listings_by_registry(REGISTRY_ID).contains(&token_id));
+    }
+}
+
+fn listing_removed(token_id: H256) -> bool {
+    Marketplace::listings(token_id).is_none()
+        && !Marketplace::listings_by_registry(REGISTRY_ID).contains(&token_id)
+}
+
+#[test]
+fn list_and_buy() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = registry_token(ALICE, 1);
+        create_account_test(BOB);
+        list_test(ALICE, token_id, PRICE, EXPIRY, Ok(()));
+
+        assert_eq!(
+            Marketplace::buy(Origin::signed(BOB), token_id, PRICE - 1),
+            Err(Error::<NftRegistryTest>::PriceTooHigh.into())
+        );
+        assert_eq!(
+            Marketplace::buy(Origin::signed(ALICE), token_id, PRICE),
+            Err(Error::<NftRegistryTest>::BuyerIsSeller.into())
+        );
+
+        let seller_balance = Balances::free_balance(ALICE);
+        let buyer_balance = Balances::free_balance(BOB);
+
+        assert_ok!(Marketplace::buy(Origin::signed(BOB), token_id, PRICE));
+        assert_eq!(ERC721::owner_of(token_id), Some(BOB));
+        assert_eq!(Balances::free_balance(ALICE), seller_balance + PRICE);
+        assert_eq!(Balances::free_balance(BOB), buyer_balance - PRICE);
+        assert!(listing_removed(token_id));
+        assert!(<system::Module<NftRegistryTest>>::events()
+            .iter()
+            .find(|e| match e.event {
+                MetaEvent::marketplace(RawEvent::Sold(ALICE, BOB, _, PRICE)) => true,
+                _ => false,
+            })
+            .is_some());
+
+        assert_eq!(
+            Marketplace::buy(Origin::signed(CHARLIE), token_id, PRICE),
+            Err(Error::<NftRegistryTest>::NotListed.into())
+        );
+    });
+}
+
+#[test]
+fn list_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = registry_token(ALICE, 1);
+
+        list_test(
+            BOB,
+            token_id,
+            PRICE,
+            EXPIRY,
+            Err(erc721::Error::<NftRegistryTest>::NotTokenOwner.into()),
+        );
+        list_test(
+            ALICE,
+            token_id,
+            0,
+            EXPIRY,
+            Err(Error::<NftRegistryTest>::ZeroPrice.into()),
+        );
+        list_test(
+            ALICE,
+            token_id,
+            PRICE,
+            0,
+            Err(Error::<NftRegistryTest>::InvalidExpiry.into()),
+        );
+
+        // Only tokens minted by a registry can be listed
+        let other_token_id = H256::repeat_byte(2);
+        assert_ok!(ERC721::_mint(&ALICE, &other_token_id));
+        list_test(
+            ALICE,
+            other_token_id,
+            PRICE,
+            EXPIRY,
+            Err(crate::Error::<NftRegistryTest>::TokenNotInRegistry.into()),
+        );
+
+        list_test(ALICE, token_id, PRICE, EXPIRY, Ok(()));
+        list_test(
+            ALICE,
+            token_id,
+            PRICE,
+            EXPIRY,
+            Err(Error::<NftRegistryTest>::AlreadyListed.into()),
+        );
+    });
+}
+
+#[test]
+fn update_price_and_cancel() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = registry_token(ALICE, 1);
+        create_account_test(BOB);
+        list_test(ALICE, token_id, PRICE, EXPIRY, Ok(()));
+
+        assert_eq!(
+            Marketplace::update_price(Origin::signed(BOB), token_id, 1),
+            Err(Error::<NftRegistryTest>::NotSeller.into())
+        );
+        assert_ok!(Marketplace::update_price(
+            Origin::signed(ALICE),
+            token_id,
+            PRICE * 2
+        ));
+        assert_eq!(Marketplace::listings(token_id).unwrap().price, PRICE * 2);
+        assert_eq!(
+            Marketplace::buy(Origin::signed(BOB), token_id, PRICE),
+            Err(Error::<NftRegistryTest>::PriceTooHigh.into())
+        );
+
+        // Only seller cancels before expiry, anyone after it
+        assert_eq!(
+            Marketplace::cancel_listing(Origin::signed(BOB), token_id),
+            Err(Error::<NftRegistryTest>::NotSeller.into())
+        );
+        <system::Module<NftRegistryTest>>::set_block_number(EXPIRY);
+        assert_eq!(
+            Marketplace::buy(Origin::signed(BOB), token_id, PRICE * 2),
+            Err(Error::<NftRegistryTest>::ListingExpired.into())
+        );
+        assert_ok!(Marketplace::cancel_listing(Origin::signed(BOB), token_id));
+        assert!(listing_removed(token_id));
+        assert_eq!(ERC721::owner_of(token_id), Some(ALICE));
+    });
+}
+
+#[test]
+fn listing_cleared_on_transfer_and_burn() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = registry_token(ALICE, 1);
+        list_test(ALICE, token_id, PRICE, EXPIRY, Ok(()));
+
+        transfer_token_test(token_id, ALICE, ALICE, CHARLIE, Ok(()));
+        assert!(listing_removed(token_id));
+
+        list_test(CHARLIE, token_id, PRICE, EXPIRY, Ok(()));
+        burn_token_test(CHARLIE, token_id, Ok(()));
+        assert!(listing_removed(token_id));
+    });
+}
+
+#[test]
+fn buy_pays_royalty() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = registry_token(ALICE, 1);
+        create_account_test(BOB);
+        create_account_test(DJANGO);
+
+        // 10% of the price to the registry owner
+        <RegistryOwner<NftRegistryTest>>::insert(REGISTRY_ID, DJANGO);
+        assert_ok!(NftReg::set_registry_royalty(
+            Origin::signed(DJANGO),
+            REGISTRY_ID,
+            DJANGO,
+            1_000
+        ));
+
+        list_test(ALICE, token_id, PRICE, EXPIRY, Ok(()));
+
+        let seller_balance = Balances::free_balance(ALICE);
+        let recipient_balance = Balances::free_balance(DJANGO);
+
+        assert_ok!(Marketplace::buy(Origin::signed(BOB), token_id, PRICE));
+        assert_eq!(Balances::free_balance(ALICE), seller_balance + PRICE - 100);
+        assert_eq!(Balances::free_balance(DJANGO), recipient_balance + 100);
+    });
+}
@@ -6,7 +6,7 @@ use ink_core::env::*;
 use node_runtime::constants::currency::*;
 use sp_core::H256;
 use sp_runtime::{
-    testing::Header,
+    testing::{Header, TestSignature, UintAuthorityId},
     traits::{BlakeTwo256, Hash, IdentityLookup},
     BuildStorage, Perbill,
 };
@@ -40,7 +40,8 @@ impl_outer_dispatch! {
 
 impl_outer_event! {
     pub enum MetaEvent for NftRegistryTest {
-        balances<T>, contracts<T>, nftregistry<T>, erc721<T>,
+        balances<T>, contracts<T>, nftregistry<T>, erc721<T>, erc1155<T>, marketplace<T>,
+        auction<T>, lending<T>,
     }
 }
 
@@ -100,14 +101,41 @@ impl pallet_timestamp::Trait for NftRegistryTest {
     type MinimumPeriod = MinimumPeriod;
 }
 
+parameter_types! {
+    pub const MaxNameLength: u32 = 64;
+    pub const MaxSymbolLength: u32 = 16;
+    pub const MaxUriLength: u32 = 256;
+    pub const MaxBatchSize: u32 = 100;
+    pub const MaxNestingDepth: u32 = 8;
+    pub const MaxApprovalsExpiring: u32 = 100;
+}
+
 impl erc721::Trait for NftRegistryTest {
     type Event = MetaEvent;
     type Randomness = RandomnessCollectiveFlip;
     type TokenIndex = u64;
+    type CollectionId = u32;
+    type MetadataOrigin = system::EnsureRoot<u64>;
+    type MaxNameLength = MaxNameLength;
+    type MaxSymbolLength = MaxSymbolLength;
+    type MaxUriLength = MaxUriLength;
+    type MaxBatchSize = MaxBatchSize;
+    type MaxNestingDepth = MaxNestingDepth;
+    type MaxApprovalsExpiring = MaxApprovalsExpiring;
+    type TokenReceiver = ContractReceiver<NftRegistryTest>;
+    type TokenHooks = (NftRegistry, Marketplace);
+    type Signature = TestSignature;
+    type Signer = UintAuthorityId;
 }
 
 impl anchor::Trait for NftRegistryTest {}
 
+impl erc1155::Trait for NftRegistryTest {
+    type Event = MetaEvent;
+    type TokenBalance = u64;
+    type TokenHooks = NftRegistry;
+}
+
 parameter_types! {
     pub const SignedClaimHandicap: u32 = 2;
     pub const TombstoneDeposit: u64 = 16;
@@ -189,6 +217,10 @@ pub type Balances = balances::Module<NftRegistryTest>;
 pub type Contract = contracts::Module<NftRegistryTest>;
 pub type NftRegistry = super::Module<NftRegistryTest>;
 pub type ERC721 = erc721::Module<NftRegistryTest>;
+pub type ERC1155 = erc1155::Module<NftRegistryTest>;
+pub type Marketplace = marketplace::Module<NftRegistryTest>;
+pub type Auction = auction::Module<NftRegistryTest>;
+pub type Lending = lending::Module<NftRegistryTest>;
 
 impl contracts::Trait for NftRegistryTest {
     type Currency = Balances;
@@ -223,6 +255,7 @@ parameter_types! {
     pub const NFTDepositBase: u64 = 1_000 * CENTS as u64;
     pub const NFTDepositPerByte: u64 = 1_000 * CENTS as u64;
     pub const NFTValidationRegistryDeposit: u64 = 1_000 * CENTS as u64;
+    pub const ReceiverGasLimit: u64 = 500_000;
 
 }
 
@@ -233,6 +266,26 @@ impl super::Trait for NftRegistryTest {
     type NFTDepositPerByte = NFTDepositPerByte;
     type NFTValidationRegistryDeposit = NFTValidationRegistryDeposit;
     type Currency = Balances;
+    type ReceiverGasLimit = ReceiverGasLimit;
+}
+
+impl marketplace::Trait for NftRegistryTest {
+    type Event = MetaEvent;
+}
+
+parameter_types! {
+    pub const ExtensionPeriod: u64 = 5;
+    pub const MaxAuctionsEnding: u32 = 2;
+}
+
+impl auction::Trait for NftRegistryTest {
+    type Event = MetaEvent;
+    type ExtensionPeriod = ExtensionPeriod;
+    type MaxAuctionsEnding = MaxAuctionsEnding;
+}
+
+impl lending::Trait for NftRegistryTest {
+    type Event = MetaEvent;
 }
 
 pub struct DummyContractAddressFor;
@@ -318,6 +371,16 @@ pub fn create_account_test(account_id: u64) {
     let _ = Balances::deposit_creating(&account_id, 100_000_000_000_000_000);
 }
 
+// Mint a token outside of any registry to a funded owner
+pub fn mint_test(owner: u64, byte: u8) -> H256 {
+    let token_id = H256::repeat_byte(byte);
+
+    create_account_test(owner);
+    assert_ok!(ERC721::_mint(&owner, &token_id));
+
+    token_id
+}
+
 // Compile smart contract from string
 pub fn compile_smart_contract<T>() -> (Vec<u8>, H256)
 where
@@ -372,6 +435,27 @@ pub fn register_validation_test(account_id: u64, contract_address: u64, result:
     }
 }
 
+pub fn register_fungible_validation_test(
+    account_id: u64,
+    contract_address: u64,
+    shares: u64,
+    result: DispatchResult,
+) {
+    let origin = Origin::signed(account_id);
+
+    assert_eq!(
+        NftReg::new_fungible_registry(origin, contract_address, shares),
+        result
+    );
+
+    if result.is_ok() {
+        assert_eq!(
+            NftReg::registry_shares(NextRegistryId::get() - 1),
+            Some(shares)
+        );
+    }
+}
+
 pub fn insert_anchor_test(anchor_id: H256, doc_root: H256) {
     <anchor::Module<NftRegistryTest>>::insert_anchor_data(anchor_id, doc_root);
 }
@@ -2,6 +2,8 @@
 
 use super::*;
 use crate::mock::*;
+use sp_runtime::testing::TestSignature;
+use support::{assert_ok, weights::GetDispatchInfo};
 
 #[test]
 fn mint_nft_from_basic_contract() {
@@ -244,6 +246,539 @@ fn burn_token_successful() {
     });
 }
 
+#[test]
+fn batch_transfer_and_burn_tokens() {
+    ExtBuilder::default().build().execute_with(|| {
+        // define all ids
+        let account_id = ALICE;
+        let token_ids: Vec<H256> = (0..2).map(H256::from_low_u64_be).collect();
+        let anchor_id: H256 = H256::from_low_u64_be(0);
+        let registry_id = 0;
+
+        let triple = get_valid_proof();
+        let doc_root = triple.1;
+
+        create_account_test(account_id);
+        create_account_test(DJANGO);
+        insert_anchor_test(anchor_id, doc_root);
+
+        let (bytecode, codehash) = get_smart_contract(account_id);
+        let contract_address =
+            register_validation_fn_test::<NftRegistryTest>(account_id, &bytecode, &codehash);
+
+        create_account_test(contract_address);
+        register_validation_test(account_id, contract_address, Ok(()));
+        for token_id in token_ids.iter() {
+            create_nft_test(
+                registry_id,
+                account_id,
+                contract_address,
+                *token_id,
+                anchor_id,
+                get_valid_metadata(),
+                get_valid_proof(),
+                Ok(()),
+            );
+        }
+
+        assert_ok!(NftReg::batch_transfer_from(
+            Origin::signed(account_id),
+            account_id,
+            DJANGO,
+            token_ids.clone()
+        ));
+        for token_id in token_ids.iter() {
+            assert_eq!(ERC721::owner_of(token_id), Some(DJANGO));
+        }
+
+        // Only owner can burn
+        assert_eq!(
+            NftReg::batch_burn(Origin::signed(account_id), token_ids.clone()),
+            Err(erc721::Error::<NftRegistryTest>::NotTokenOwner.into())
+        );
+
+        assert_ok!(NftReg::batch_burn(
+            Origin::signed(DJANGO),
+            token_ids.clone()
+        ));
+        for token_id in token_ids.iter() {
+            assert_eq!(ERC721::owner_of(token_id), None);
+            assert_eq!(NftReg::deposit_by_token_id(token_id), 0);
+        }
+    });
+}
+
+#[test]
+fn deposit_follows_token_in_erc721_calls() {
+    ExtBuilder::default().build().execute_with(|| {
+        // define all ids
+        let account_id = ALICE;
+        let token_id: H256 = H256::from_low_u64_be(0);
+        let anchor_id: H256 = H256::from_low_u64_be(0);
+        let registry_id = 0;
+
+        let triple = get_valid_proof();
+        let doc_root = triple.1;
+
+        create_account_test(account_id);
+        create_account_test(DJANGO);
+        insert_anchor_test(anchor_id, doc_root);
+
+        let (bytecode, codehash) = get_smart_contract(account_id);
+        let contract_address =
+            register_validation_fn_test::<NftRegistryTest>(account_id, &bytecode, &codehash);
+
+        create_account_test(contract_address);
+        register_validation_test(account_id, contract_address, Ok(()));
+        create_nft_test(
+            registry_id,
+            account_id,
+            contract_address,
+            token_id,
+            anchor_id,
+            get_valid_metadata(),
+            triple,
+            Ok(()),
+        );
+
+        let deposit = NftReg::deposit_by_token_id(token_id);
+        let reserved = Balances::reserved_balance(account_id);
+        assert_eq!(NftReg::deposit_holder(token_id), Some(account_id));
+
+        // Transfer by ERC721 module moves the deposit as well
+        assert_ok!(ERC721::transfer_from(
+            Origin::signed(account_id),
+            0,
+            account_id,
+            DJANGO,
+            token_id
+        ));
+        assert_eq!(Balances::reserved_balance(account_id), reserved - deposit);
+        assert_eq!(Balances::reserved_balance(DJANGO), deposit);
+        assert_eq!(NftReg::deposit_holder(token_id), Some(DJANGO));
+
+        // Burn by ERC721 module releases the deposit and metadata
+        assert_ok!(ERC721::burn(Origin::signed(DJANGO), 0, token_id));
+        assert_eq!(Balances::reserved_balance(DJANGO), 0);
+        assert_eq!(NftReg::deposit_holder(token_id), None);
+        assert_eq!(NftReg::deposit_by_token_id(token_id), 0);
+        assert!(NftReg::token_metadata(token_id).is_empty());
+    });
+}
+
+#[test]
+fn deposit_kept_when_recipient_can_not_reserve() {
+    ExtBuilder::default().build().execute_with(|| {
+        let account_id = ALICE;
+        let token_id: H256 = H256::from_low_u64_be(0);
+        let anchor_id: H256 = H256::from_low_u64_be(0);
+        let registry_id = 0;
+
+        let triple = get_valid_proof();
+        let doc_root = triple.1;
+
+        create_account_test(account_id);
+        create_account_test(DJANGO);
+        insert_anchor_test(anchor_id, doc_root);
+
+        let (bytecode, codehash) = get_smart_contract(account_id);
+        let contract_address =
+            register_validation_fn_test::<NftRegistryTest>(account_id, &bytecode, &codehash);
+
+        create_account_test(contract_address);
+        register_validation_test(account_id, contract_address, Ok(()));
+        create_nft_test(
+            registry_id,
+            account_id,
+            contract_address,
+            token_id,
+            anchor_id,
+            get_valid_metadata(),
+            triple,
+            Ok(()),
+        );
+
+        let deposit = NftReg::deposit_by_token_id(token_id);
+        let reserved = Balances::reserved_balance(account_id);
+
+        // Recipient with an unrelated reserve and all its funds locked
+        assert_ok!(Balances::reserve(&DJANGO, 1_000));
+        Balances::set_lock(
+            *b"testlock",
+            &DJANGO,
+            u64::max_value(),
+            u64::max_value(),
+            WithdrawReasons::all(),
+        );
+
+        assert_ok!(NftReg::transfer_from(
+            Origin::signed(account_id),
+            account_id,
+            DJANGO,
+            token_id
+        ));
+        assert_eq!(ERC721::owner_of(token_id), Some(DJANGO));
+        assert_eq!(NftReg::deposit_holder(token_id), Some(account_id));
+        assert_eq!(Balances::reserved_balance(account_id), reserved);
+        assert_eq!(Balances::reserved_balance(DJANGO), 1_000);
+        assert!(<system::Module<NftRegistryTest>>::events().iter().any(
+            |e| e.event == MetaEvent::nftregistry(RawEvent::DepositKept(token_id, account_id))
+        ));
+
+        // Recipient without balance can not hold the deposit either
+        assert_ok!(NftReg::transfer_from(
+            Origin::signed(DJANGO),
+            DJANGO,
+            CHARLIE,
+            token_id
+        ));
+        assert_eq!(NftReg::deposit_holder(token_id), Some(account_id));
+
+        // Burn releases the deposit of its holder, the unrelated reserve is kept
+        assert_ok!(NftReg::burn(Origin::signed(CHARLIE), token_id));
+        assert_eq!(Balances::reserved_balance(account_id), reserved - deposit);
+        assert_eq!(Balances::reserved_balance(DJANGO), 1_000);
+        assert_eq!(NftReg::deposit_holder(token_id), None);
+    });
+}
+
+#[test]
+fn transfer_with_payment_pays_royalty() {
+    ExtBuilder::default().build().execute_with(|| {
+        // define all ids
+        let account_id = ALICE;
+        let token_id: H256 = H256::from_low_u64_be(0);
+        let anchor_id: H256 = H256::from_low_u64_be(0);
+        let registry_id = 0;
+        let price = 10_000;
+
+        let triple = get_valid_proof();
+        let doc_root = triple.1;
+
+        create_account_test(account_id);
+        create_account_test(DJANGO);
+        insert_anchor_test(anchor_id, doc_root);
+
+        let (bytecode, codehash) = get_smart_contract(account_id);
+        let contract_address =
+            register_validation_fn_test::<NftRegistryTest>(account_id, &bytecode, &codehash);
+
+        create_account_test(contract_address);
+        register_validation_test(account_id, contract_address, Ok(()));
+        create_nft_test(
+            registry_id,
+            account_id,
+            contract_address,
+            token_id,
+            anchor_id,
+            get_valid_metadata(),
+            triple,
+            Ok(()),
+        );
+
+        // Only registry owner sets royalties, up to the whole price
+        assert_eq!(
+            NftReg::set_registry_royalty(Origin::signed(DJANGO), registry_id, BOB, 1_000),
+            Err(Error::<NftRegistryTest>::NotRegistryOwner.into())
+        );
+        assert_eq!(
+            NftReg::set_registry_royalty(Origin::signed(account_id), registry_id, BOB, 10_001),
+            Err(Error::<NftRegistryTest>::RoyaltyRateTooHigh.into())
+        );
+
+        // Token royalty overrides registry royalty
+        assert_ok!(NftReg::set_registry_royalty(
+            Origin::signed(account_id),
+            registry_id,
+            BOB,
+            1_000
+        ));
+        assert_eq!(NftReg::royalty_info(&token_id, price), Some((BOB, 1_000)));
+        assert_ok!(NftReg::set_token_royalty(
+            Origin::signed(account_id),
+            token_id,
+            CHARLIE,
+            500
+        ));
+        assert_eq!(NftReg::royalty_info(&token_id, price), Some((CHARLIE, 500)));
+
+        // Owner can not pay itself a price to charge royalty on
+        assert_eq!(
+            NftReg::transfer_with_payment(
+                Origin::signed(account_id),
+                account_id,
+                DJANGO,
+                token_id,
+                price
+            ),
+            Err(Error::<NftRegistryTest>::BuyerIsSeller.into())
+        );
+        assert_eq!(ERC721::owner_of(token_id), Some(account_id));
+        assert_eq!(Balances::free_balance(CHARLIE), 0);
+
+        // Buyer must be approved by the owner
+        assert_eq!(
+            NftReg::transfer_with_payment(
+                Origin::signed(DJANGO),
+                account_id,
+                DJANGO,
+                token_id,
+                price
+            ),
+            Err(erc721::Error::<NftRegistryTest>::NotOwnerOrApprover.into())
+        );
+        assert_ok!(NftReg::approve(
+            Origin::signed(account_id),
+            DJANGO,
+            token_id
+        ));
+
+        // Royalty below the existential deposit can not create its recipient,
+        // it is checked before the price is paid
+        let seller_balance = Balances::free_balance(account_id);
+        let buyer_balance = Balances::free_balance(DJANGO);
+        assert_eq!(
+            NftReg::transfer_with_payment(
+                Origin::signed(DJANGO),
+                account_id,
+                DJANGO,
+                token_id,
+                price / 5
+            ),
+            Err(Error::<NftRegistryTest>::RoyaltyNotPayable.into())
+        );
+        assert_eq!(ERC721::owner_of(token_id), Some(account_id));
+        assert_eq!(Balances::free_balance(account_id), seller_balance);
+        assert_eq!(Balances::free_balance(DJANGO), buyer_balance);
+        assert_eq!(Balances::free_balance(CHARLIE), 0);
+
+        let deposit = NftReg::deposit_by_token_id(token_id);
+
+        assert_ok!(NftReg::transfer_with_payment(
+            Origin::signed(DJANGO),
+            account_id,
+            DJANGO,
+            token_id,
+            price
+        ));
+        assert_eq!(ERC721::owner_of(token_id), Some(DJANGO));
+        assert_eq!(
+            Balances::free_balance(account_id),
+            seller_balance + price - 500
+        );
+        assert_eq!(
+            Balances::free_balance(DJANGO),
+            buyer_balance - price - deposit
+        );
+        assert_eq!(Balances::free_balance(CHARLIE), 500);
+        assert!(<system::Module<NftRegistryTest>>::events()
+            .iter()
+            .find(|e| match e.event {
+                MetaEvent::nftregistry(RawEvent::RoyaltyPaid(_, CHARLIE, 500)) => true,
+                _ => false,
+            })
+            .is_some());
+
+        // Royalty of a burned token is removed
+        burn_token_test(DJANGO, token_id, Ok(()));
+        assert_eq!(NftReg::token_royalty(token_id), None);
+    });
+}
+
+fn propose_swap_test(
+    proposer: u64,
+    offer_tokens: Vec<H256>,
+    offer_amount: u64,
+    want_tokens: Vec<H256>,
+    counterparty: u64,
+    result: DispatchResult,
+) {
+    assert_eq!(
+        NftReg::propose_swap(
+            Origin::signed(proposer),
+            offer_tokens.clone(),
+            offer_amount,
+            want_tokens,
+            0,
+            counterparty,
+            10
+        ),
+        result
+    );
+
+    if result.is_ok() {
+        for token_id in offer_tokens.iter() {
+            assert_eq!(ERC721::owner_of(token_id), Some(NftReg::swap_account_id()));
+        }
+        assert_eq!(NftReg::swap_locked(proposer), offer_amount);
+        assert_eq!(Balances::reserved_balance(proposer), 0);
+    }
+}
+
+#[test]
+fn swap_tokens_and_currency() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_a = H256::repeat_byte(1);
+        let token_b = H256::repeat_byte(2);
+        create_account_test(ALICE);
+        create_account_test(BOB);
+        assert_ok!(ERC721::_mint(&ALICE, &token_a));
+        assert_ok!(ERC721::_mint(&BOB, &token_b));
+
+        // Token A plus 100 units for token B
+        propose_swap_test(ALICE, vec![token_a], 100, vec![token_b], BOB, Ok(()));
+
+        assert_eq!(
+            NftReg::accept_swap(Origin::signed(CHARLIE), 0),
+            Err(Error::<NftRegistryTest>::NotSwapCounterparty.into())
+        );
+
+        let counterparty_balance = Balances::free_balance(BOB);
+        assert_ok!(NftReg::accept_swap(Origin::signed(BOB), 0));
+        assert_eq!(ERC721::owner_of(token_a), Some(BOB));
+        assert_eq!(ERC721::owner_of(token_b), Some(ALICE));
+        assert_eq!(NftReg::swap_locked(ALICE), 0);
+        assert_eq!(Balances::free_balance(BOB), counterparty_balance + 100);
+        assert_eq!(NftReg::swaps(0), None);
+
+        assert_eq!(
+            NftReg::accept_swap(Origin::signed(BOB), 0),
+            Err(Error::<NftRegistryTest>::SwapNotFound.into())
+        );
+    });
+}
+
+#[test]
+fn failed_swap_changes_nothing() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_a = H256::repeat_byte(1);
+        let token_b = H256::repeat_byte(2);
+        create_account_test(ALICE);
+        create_account_test(BOB);
+        assert_ok!(ERC721::_mint(&ALICE, &token_a));
+        assert_ok!(ERC721::_mint(&CHARLIE, &token_b));
+
+        propose_swap_test(
+            ALICE,
+            vec![token_a],
+            100,
+            vec![token_b],
+            ALICE,
+            Err(Error::<NftRegistryTest>::SwapWithSelf.into()),
+        );
+        propose_swap_test(
+            ALICE,
+            vec![],
+            0,
+            vec![],
+            BOB,
+            Err(Error::<NftRegistryTest>::EmptySwap.into()),
+        );
+        propose_swap_test(
+            ALICE,
+            vec![token_b],
+            100,
+            vec![],
+            BOB,
+            Err(erc721::Error::<NftRegistryTest>::NotTokenOwner.into()),
+        );
+        assert_eq!(NftReg::swap_locked(ALICE), 0);
+
+        // Counterparty does not own the wanted token
+        propose_swap_test(ALICE, vec![token_a], 100, vec![token_b], BOB, Ok(()));
+        let counterparty_balance = Balances::free_balance(BOB);
+        assert_eq!(
+            NftReg::accept_swap(Origin::signed(BOB), 0),
+            Err(erc721::Error::<NftRegistryTest>::NotTokenOwner.into())
+        );
+        assert_eq!(ERC721::owner_of(token_a), Some(NftReg::swap_account_id()));
+        assert_eq!(ERC721::owner_of(token_b), Some(CHARLIE));
+        assert_eq!(NftReg::swap_locked(ALICE), 100);
+        assert_eq!(Balances::free_balance(BOB), counterparty_balance);
+
+        // Only proposer cancels before expiry, anyone after it
+        assert_eq!(
+            NftReg::cancel_swap(Origin::signed(BOB), 0),
+            Err(Error::<NftRegistryTest>::NotSwapProposer.into())
+        );
+        <system::Module<NftRegistryTest>>::set_block_number(10);
+        assert_eq!(
+            NftReg::accept_swap(Origin::signed(BOB), 0),
+            Err(Error::<NftRegistryTest>::SwapExpired.into())
+        );
+        assert_ok!(NftReg::cancel_swap(Origin::signed(BOB), 0));
+        assert_eq!(ERC721::owner_of(token_a), Some(ALICE));
+        assert_eq!(NftReg::swap_locked(ALICE), 0);
+        assert_eq!(NftReg::swaps(0), None);
+    });
+}
+
+#[test]
+fn swap_offer_is_locked() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_account_test(ALICE);
+        create_account_test(BOB);
+        let balance = Balances::free_balance(ALICE);
+
+        propose_swap_test(
+            ALICE,
+            vec![],
+            balance + 1,
+            vec![],
+            BOB,
+            Err(Error::<NftRegistryTest>::InsufficientSwapBalance.into()),
+        );
+        propose_swap_test(ALICE, vec![], balance / 2, vec![], BOB, Ok(()));
+        propose_swap_test(
+            ALICE,
+            vec![],
+            balance / 2 + 1,
+            vec![],
+            BOB,
+            Err(Error::<NftRegistryTest>::InsufficientSwapBalance.into()),
+        );
+
+        // Offered currency can not be spent or reserved for deposits
+        assert!(<Balances as Currency<u64>>::transfer(
+            &ALICE,
+            &CHARLIE,
+            balance,
+            ExistenceRequirement::AllowDeath
+        )
+        .is_err());
+        assert!(Balances::reserve(&ALICE, balance).is_err());
+    });
+}
+
+#[test]
+fn failed_swap_payment_keeps_swap() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_account_test(ALICE);
+        create_account_test(BOB);
+        let counterparty_balance = Balances::free_balance(BOB);
+
+        // Counterparty can not pay the wanted currency, even out of the offered currency,
+        // checked before any currency moves
+        assert_ok!(NftReg::propose_swap(
+            Origin::signed(ALICE),
+            vec![],
+            100,
+            vec![],
+            counterparty_balance + 101,
+            BOB,
+            10
+        ));
+        assert_eq!(
+            NftReg::accept_swap(Origin::signed(BOB), 0),
+            Err(Error::<NftRegistryTest>::SwapPaymentFailed.into())
+        );
+
+        assert_eq!(NftReg::swap_locked(ALICE), 100);
+        assert_eq!(Balances::free_balance(BOB), counterparty_balance);
+        assert!(NftReg::swaps(0).is_some());
+    });
+}
+
 #[test]
 fn finish_mint_not_from_contract() {
     ExtBuilder::default().build().execute_with(|| {
@@ -277,3 +812,815 @@ fn finish_mint_not_from_contract() {
         );
     });
 }
+
+#[test]
+fn mint_shares_from_fungible_registry() {
+    ExtBuilder::default().build().execute_with(|| {
+        // define all ids
+        let account_id = ALICE;
+        let token_id: H256 = H256::from_low_u64_be(0);
+        let registry_id = 0;
+        let shares = 1_000;
+
+        create_account_test(account_id);
+
+        let (bytecode, codehash) = get_smart_contract(account_id);
+        let contract_address =
+            register_validation_fn_test::<NftRegistryTest>(account_id, &bytecode, &codehash);
+
+        create_account_test(contract_address);
+        register_fungible_validation_test(
+            account_id,
+            contract_address,
+            0,
+            Err(Error::<NftRegistryTest>::ZeroShares.into()),
+        );
+        register_fungible_validation_test(account_id, contract_address, shares, Ok(()));
+
+        // All shares of the document go to the owner, no ERC721 token is minted
+        assert_ok!(NftReg::finish_mint(
+            Origin::signed(contract_address),
+            registry_id,
+            token_id,
+            account_id,
+            get_valid_metadata()
+        ));
+        assert_eq!(ERC1155::balance_of(account_id, token_id), shares);
+        assert_eq!(ERC1155::total_supply(token_id), Some(shares));
+        assert_eq!(NftReg::minted_count(registry_id, account_id), 1);
+        assert_eq!(ERC721::owner_of(&token_id), None);
+        assert!(<system::Module<NftRegistryTest>>::events()
+            .iter()
+            .find(|e| match e.event {
+                MetaEvent::nftregistry(RawEvent::MintShares(0, _, 1_000)) => true,
+                _ => false,
+            })
+            .is_some());
+
+        // Same document can not be minted again
+        assert_eq!(
+            NftReg::finish_mint(
+                Origin::signed(contract_address),
+                registry_id,
+                token_id,
+                account_id,
+                get_valid_metadata()
+            ),
+            Err(erc1155::Error::<NftRegistryTest>::TokenAlreadyExists.into())
+        );
+        assert_eq!(NftReg::minted_count(registry_id, account_id), 1);
+
+        // Deposit is released once all shares are burned
+        let deposit = NftReg::deposit_by_token_id(token_id);
+        let reserved = Balances::reserved_balance(account_id);
+        assert_ok!(ERC1155::burn_batch(
+            Origin::signed(account_id),
+            account_id,
+            vec![token_id],
+            vec![shares - 1]
+        ));
+        assert_eq!(NftReg::deposit_holder(token_id), Some(account_id));
+        assert_ok!(ERC1155::burn_batch(
+            Origin::signed(account_id),
+            account_id,
+            vec![token_id],
+            vec![1]
+        ));
+        assert_eq!(Balances::reserved_balance(account_id), reserved - deposit);
+        assert_eq!(NftReg::deposit_holder(token_id), None);
+        assert_eq!(NftReg::deposit_by_token_id(token_id), 0);
+        assert!(NftReg::token_metadata(token_id).is_empty());
+    });
+}
+
+#[test]
+fn mint_with_user_sets_token_user() {
+    ExtBuilder::default().build().execute_with(|| {
+        let account_id = ALICE;
+        let token_id: H256 = H256::from_low_u64_be(0);
+        let anchor_id: H256 = H256::from_low_u64_be(0);
+        let registry_id = 0;
+
+        let (proof, doc_root, static_proofs) = get_valid_proof();
+
+        create_account_test(account_id);
+        insert_anchor_test(anchor_id, doc_root);
+
+        let (bytecode, codehash) = get_smart_contract(account_id);
+        let contract_address =
+            register_validation_fn_test::<NftRegistryTest>(account_id, &bytecode, &codehash);
+
+        create_account_test(contract_address);
+        register_validation_test(account_id, contract_address, Ok(()));
+        register_fungible_validation_test(account_id, contract_address, 1_000, Ok(()));
+
+        // Shares have no user
+        assert_eq!(
+            NftReg::mint_with_user(
+                Origin::signed(account_id),
+                1,
+                token_id,
+                get_valid_metadata(),
+                anchor_id,
+                vec![proof.clone()],
+                static_proofs,
+                BOB,
+                10,
+                0,
+                100_000
+            ),
+            Err(Error::<NftRegistryTest>::TokenUserNotSupported.into())
+        );
+
+        // Pending user only lives while the contract is called
+        assert_ok!(NftReg::mint_with_user(
+            Origin::signed(account_id),
+            registry_id,
+            token_id,
+            get_valid_metadata(),
+            anchor_id,
+            vec![proof],
+            static_proofs,
+            BOB,
+            10,
+            0,
+            100_000
+        ));
+        assert_eq!(NftReg::pending_user(token_id), None);
+
+        // Contract calls back while the user is pending
+        <PendingUser<NftRegistryTest>>::insert(token_id, (BOB, 10));
+        assert_ok!(NftReg::finish_mint(
+            Origin::signed(contract_address),
+            registry_id,
+            token_id,
+            account_id,
+            get_valid_metadata()
+        ));
+        <PendingUser<NftRegistryTest>>::remove(token_id);
+
+        assert_eq!(ERC721::owner_of(token_id), Some(account_id));
+        assert_eq!(ERC721::user_of(token_id), Some(BOB));
+    });
+}
+
+// Registry whose test contract never mints, with an anchored document
+fn setup_signed_mint() -> (u64, H256) {
+    let anchor_id: H256 = H256::from_low_u64_be(0);
+    let (_, doc_root, _) = get_valid_proof();
+
+    create_account_test(ALICE);
+    create_account_test(BOB);
+    create_account_test(DJANGO);
+    insert_anchor_test(anchor_id, doc_root);
+
+    let (bytecode, codehash) = get_smart_contract(ALICE);
+    let contract_address =
+        register_validation_fn_test::<NftRegistryTest>(ALICE, &bytecode, &codehash);
+    create_account_test(contract_address);
+    register_validation_test(ALICE, contract_address, Ok(()));
+
+    (contract_address, anchor_id)
+}
+
+// Request of CHARLIE, who has no balance, sponsored by DJANGO
+fn mint_request(anchor_id: H256, fee: u64, nonce: u64) -> MintRequestOf<NftRegistryTest> {
+    let (proof, _, static_proofs) = get_valid_proof();
+
+    MintRequest {
+        owner: CHARLIE,
+        sponsor: DJANGO,
+        registry_uid: 0,
+        token_id: H256::from_low_u64_be(1),
+        metadata: get_valid_metadata(),
+        anchor_id,
+        proofs: vec![proof],
+        static_proofs,
+        fee,
+        nonce,
+        deadline: 10,
+    }
+}
+
+fn sign_request(signer: u64, request: &MintRequestOf<NftRegistryTest>) -> TestSignature {
+    TestSignature(signer, NftReg::mint_request_payload(request))
+}
+
+#[test]
+fn mint_signed_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        let (_, anchor_id) = setup_signed_mint();
+        let request = mint_request(anchor_id, 100, 0);
+        let deposit = NFTDepositBase::get() + NFTDepositPerByte::get() * 10;
+
+        // Only the owner signs its requests
+        assert_eq!(
+            NftReg::mint_signed(
+                Origin::signed(BOB),
+                request.clone(),
+                sign_request(BOB, &request),
+                100_000
+            ),
+            Err(Error::<NftRegistryTest>::InvalidMintSignature.into())
+        );
+
+        let mut other_nonce = request.clone();
+        other_nonce.nonce = 1;
+        assert_eq!(
+            NftReg::mint_signed(
+                Origin::signed(BOB),
+                other_nonce.clone(),
+                sign_request(CHARLIE, &other_nonce),
+                100_000
+            ),
+            Err(Error::<NftRegistryTest>::InvalidMintNonce.into())
+        );
+
+        // Sponsor must allow the owner enough for fee and deposit
+        let signature = sign_request(CHARLIE, &request);
+        assert_eq!(
+            NftReg::mint_signed(
+                Origin::signed(BOB),
+                request.clone(),
+                signature.clone(),
+                100_000
+            ),
+            Err(Error::<NftRegistryTest>::SponsorAllowanceExceeded.into())
+        );
+        assert_ok!(NftReg::set_sponsor_allowance(
+            Origin::signed(DJANGO),
+            CHARLIE,
+            1,
+            deposit
+        ));
+        assert_eq!(
+            NftReg::mint_signed(
+                Origin::signed(BOB),
+                request.clone(),
+                signature.clone(),
+                100_000
+            ),
+            Err(Error::<NftRegistryTest>::SponsorAllowanceExceeded.into())
+        );
+
+        <system::Module<NftRegistryTest>>::set_block_number(11);
+        assert_eq!(
+            NftReg::mint_signed(Origin::signed(BOB), request, signature, 100_000),
+            Err(Error::<NftRegistryTest>::MintRequestExpired.into())
+        );
+    });
+}
+
+#[test]
+fn failed_signed_mint_refunds_fee() {
+    ExtBuilder::default().build().execute_with(|| {
+        let (_, anchor_id) = setup_signed_mint();
+        let request = mint_request(anchor_id, 100, 0);
+        assert_ok!(NftReg::set_sponsor_allowance(
+            Origin::signed(DJANGO),
+            CHARLIE,
+            1,
+            NFTDepositBase::get() * 100
+        ));
+
+        let sponsor_balance = Balances::free_balance(DJANGO);
+        let relayer_balance = Balances::free_balance(BOB);
+
+        // Test contract does not mint, nothing is charged or spent
+        assert_eq!(
+            NftReg::mint_signed(
+                Origin::signed(BOB),
+                request.clone(),
+                sign_request(CHARLIE, &request),
+                100_000
+            ),
+            Err(Error::<NftRegistryTest>::SponsoredMintFailed.into())
+        );
+        assert_eq!(Balances::free_balance(DJANGO), sponsor_balance);
+        assert_eq!(Balances::free_balance(BOB), relayer_balance);
+        assert_eq!(NftReg::sponsor_allowance(DJANGO, CHARLIE).unwrap().mints, 1);
+        assert_eq!(NftReg::mint_nonce(CHARLIE), 0);
+        assert_eq!(NftReg::pending_sponsor(request.token_id), None);
+
+        // Zero mints removes the allowance
+        assert_ok!(NftReg::set_sponsor_allowance(
+            Origin::signed(DJANGO),
+            CHARLIE,
+            0,
+            0
+        ));
+        assert_eq!(NftReg::sponsor_allowance(DJANGO, CHARLIE), None);
+    });
+}
+
+#[test]
+fn sponsor_keeps_deposit_until_burn() {
+    ExtBuilder::default().build().execute_with(|| {
+        let (contract_address, _) = setup_signed_mint();
+        let token_id = H256::from_low_u64_be(1);
+
+        // Contract calls back during a signed mint of DJANGO
+        <PendingSponsor<NftRegistryTest>>::insert(&token_id, DJANGO);
+        finish_mint_test(
+            contract_address,
+            0,
+            token_id,
+            CHARLIE,
+            get_valid_metadata(),
+            Ok(()),
+        );
+
+        let deposit = NftReg::deposit_by_token_id(token_id);
+        assert_eq!(Balances::reserved_balance(DJANGO), deposit);
+        assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+        assert_eq!(NftReg::deposit_holder(token_id), Some(DJANGO));
+        assert_eq!(NftReg::sponsor_of(token_id), Some(DJANGO));
+
+        // Deposit does not follow the token
+        assert_ok!(NftReg::transfer_from(
+            Origin::signed(CHARLIE),
+            CHARLIE,
+            BOB,
+            token_id
+        ));
+        assert_eq!(Balances::reserved_balance(DJANGO), deposit);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+
+        assert_ok!(NftReg::burn(Origin::signed(BOB), token_id));
+        assert_eq!(Balances::reserved_balance(DJANGO), 0);
+        assert_eq!(NftReg::sponsor_of(token_id), None);
+    });
+}
+
+fn co_owned_token(threshold: u32) -> (H256, u64) {
+    let token_id = H256::repeat_byte(1);
+    create_account_test(ALICE);
+    assert_ok!(ERC721::_mint(&ALICE, &token_id));
+    assert_ok!(NftReg::set_co_owners(
+        Origin::signed(ALICE),
+        token_id,
+        vec![CHARLIE, ALICE, BOB],
+        threshold
+    ));
+
+    let multi_account = NftReg::multi_account_id(&[ALICE, BOB, CHARLIE], threshold).unwrap();
+    assert_eq!(ERC721::owner_of(token_id), Some(multi_account));
+
+    (token_id, multi_account)
+}
+
+#[test]
+fn multi_account_id_ignores_owner_order() {
+    let multi_account = NftReg::multi_account_id(&[ALICE, BOB, CHARLIE], 2).unwrap();
+
+    assert_eq!(
+        NftReg::multi_account_id(&[CHARLIE, ALICE, BOB, ALICE], 2),
+        Ok(multi_account)
+    );
+    assert_ne!(
+        NftReg::multi_account_id(&[ALICE, BOB, CHARLIE], 3),
+        Ok(multi_account)
+    );
+    assert_ne!(
+        NftReg::multi_account_id(&[ALICE, BOB], 2),
+        Ok(multi_account)
+    );
+}
+
+#[test]
+fn set_co_owners_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        let token_id = H256::repeat_byte(1);
+        create_account_test(ALICE);
+        assert_ok!(ERC721::_mint(&ALICE, &token_id));
+
+        for (owners, threshold) in vec![
+            (vec![ALICE], 1),
+            (vec![ALICE, ALICE], 1),
+            (vec![ALICE, BOB], 0),
+            (vec![ALICE, BOB], 3),
+        ] {
+            assert_eq!(
+                NftReg::set_co_owners(Origin::signed(ALICE), token_id, owners, threshold),
+                Err(Error::<NftRegistryTest>::InvalidCoOwners.into())
+            );
+        }
+        assert_eq!(
+            NftReg::set_co_owners(Origin::signed(BOB), token_id, vec![ALICE, BOB], 2),
+            Err(erc721::Error::<NftRegistryTest>::NotTokenOwner.into())
+        );
+        assert_eq!(ERC721::owner_of(token_id), Some(ALICE));
+
+        // Only co-owners propose actions on co-owned tokens
+        assert_eq!(
+            NftReg::propose_action(Origin::signed(ALICE), token_id, CoOwnerAction::Burn, 10),
+            Err(Error::<NftRegistryTest>::NotCoOwned.into())
+        );
+    });
+}
+
+#[test]
+fn co_owners_transfer_at_threshold() {
+    ExtBuilder::default().build().execute_with(|| {
+        let (token_id, multi_account) = co_owned_token(2);
+
+        // Owners can not move the token directly
+        assert_eq!(
+            NftReg::transfer_from(Origin::signed(ALICE), multi_account, DJANGO, token_id),
+            Err(erc721::Error::<NftRegistryTest>::NotOwnerOrApprover.into())
+        );
+        assert_eq!(
+            NftReg::propose_action(
+                Origin::signed(DJANGO),
+                token_id,
+                CoOwnerAction::TransferFrom(DJANGO),
+                10
+            ),
+            Err(Error::<NftRegistryTest>::NotCoOwner.into())
+        );
+
+        assert_ok!(NftReg::propose_action(
+            Origin::signed(ALICE),
+            token_id,
+            CoOwnerAction::TransferFrom(DJANGO),
+            10
+        ));
+        assert_eq!(
+            NftReg::confirm_proposal(Origin::signed(ALICE), 0),
+            Err(Error::<NftRegistryTest>::AlreadyConfirmed.into())
+        );
+        assert_eq!(ERC721::owner_of(token_id), Some(multi_account));
+
+        assert_ok!(NftReg::confirm_proposal(Origin::signed(BOB), 0));
+        assert_eq!(ERC721::owner_of(token_id), Some(DJANGO));
+        assert_eq!(NftReg::proposals(0), None);
+        assert!(<system::Module<NftRegistryTest>>::events()
+            .iter()
+            .find(|e| match e.event {
+                MetaEvent::nftregistry(RawEvent::ProposalExecuted(0)) => true,
+                _ => false,
+            })
+            .is_some());
+
+        assert_eq!(
+            NftReg::confirm_proposal(Origin::signed(CHARLIE), 0),
+            Err(Error::<NftRegistryTest>::ProposalNotFound.into())
+        );
+    });
+}
+
+#[test]
+fn co_owners_burn_and_approve() {
+    ExtBuilder::default().build().execute_with(|| {
+        let (token_id, multi_account) = co_owned_token(1);
+
+        // Threshold of one executes at once
+        assert_ok!(NftReg::propose_action(
+            Origin::signed(CHARLIE),
+            token_id,
+            CoOwnerAction::Approve(DJANGO),
+            10
+        ));
+        assert_eq!(ERC721::get_approved(token_id), Some(DJANGO));
+        assert_eq!(NftReg::proposals(0), None);
+
+        assert_ok!(NftReg::propose_action(
+            Origin::signed(BOB),
+            token_id,
+            CoOwnerAction::Burn,
+            10
+        ));
+        assert_eq!(ERC721::owner_of(token_id), None);
+        assert_eq!(ERC721::balance_of(multi_account), 0);
+    });
+}
+
+#[test]
+fn proposal_expires_and_cancels() {
+    ExtBuilder::default().build().execute_with(|| {
+        let (token_id, multi_account) = co_owned_token(3);
+
+        assert_ok!(NftReg::propose_action(
+            Origin::signed(ALICE),
+            token_id,
+            CoOwnerAction::Burn,
+            10
+        ));
+        assert_ok!(NftReg::confirm_proposal(Origin::signed(BOB), 0));
+        assert_eq!(
+            NftReg::proposals(0).unwrap().confirmations,
+            vec![ALICE, BOB]
+        );
+
+        // Only proposer cancels before expiry, anyone after it
+        assert_eq!(
+            NftReg::cancel_proposal(Origin::signed(BOB), 0),
+            Err(Error::<NftRegistryTest>::NotProposer.into())
+        );
+        <system::Module<NftRegistryTest>>::set_block_number(10);
+        assert_eq!(
+            NftReg::confirm_proposal(Origin::signed(CHARLIE), 0),
+            Err(Error::<NftRegistryTest>::ProposalExpired.into())
+        );
+        assert_ok!(NftReg::cancel_proposal(Origin::signed(BOB), 0));
+        assert_eq!(NftReg::proposals(0), None);
+        assert_eq!(ERC721::owner_of(token_id), Some(multi_account));
+
+        assert_eq!(
+            NftReg::propose_action(Origin::signed(ALICE), token_id, CoOwnerAction::Burn, 10),
+            Err(Error::<NftRegistryTest>::ProposalExpired.into())
+        );
+    });
+}
+
+#[test]
+fn safe_transfer_weight_includes_receiver_gas() {
+    let call = erc721::Call::<NftRegistryTest>::safe_transfer_from(
+        0,
+        ALICE,
+        BOB,
+        H256::from_low_u64_be(1),
+        vec![],
+    );
+
+    assert_eq!(
+        call.get_dispatch_info().weight,
+        10_000 + ReceiverGasLimit::get() as Weight
+    );
+}
+
+// End-to-end tests running the compiled contracts from `fixtures/`.
+// They are ignored until the Wasm files are built by `scripts/build_fixtures.sh`
+// and committed, run them by `cargo test -- --ignored`.
+mod e2e {
+    use crate::e2e_mock::*;
+    use crate::{Encode, H256};
+    use sp_core::{crypto::AccountId32, sr25519, Pair};
+    use sp_runtime::{traits::IdentifyAccount, MultiSignature, MultiSigner};
+    use support::{assert_ok, traits::Get};
+
+    const REGISTRY_ID: u64 = 0;
+
+    fn setup_registry() -> (AccountId32, H256, H256) {
+        let account_id = alice();
+        let anchor_id: H256 = H256::from_low_u64_be(0);
+        let (proof, doc_root, _) = crate::mock::get_valid_proof();
+
+        create_account_test(&account_id);
+        crate::anchor::Module::<E2eTest>::insert_anchor_data(anchor_id, doc_root);
+
+        let contract_address = deploy_validation_contract(&account_id);
+        register_validation_test(&account_id, &contract_address);
+
+        (contract_address, anchor_id, proof.leaf_hash)
+    }
+
+    #[test]
+    #[ignore]
+    fn mint_nft_via_validation_contract() {
+        ExtBuilder::default().build().execute_with(|| {
+            let account_id = alice();
+            let token_id: H256 = H256::from_low_u64_be(1);
+            let metadata = vec![b'x'; 10];
+            let (contract_address, anchor_id, leaf_hash) = setup_registry();
+
+            call_contract_test(
+                &account_id,
+                &contract_address,
+                "add_required_leaf",
+                leaf_hash.encode(),
+            );
+            call_contract_test(
+                &account_id,
+                &contract_address,
+                "set_max_metadata_length",
+                Some(64 as u32).encode(),
+            );
+
+            mint_test(
+                REGISTRY_ID,
+                &account_id,
+                token_id,
+                anchor_id,
+                metadata.clone(),
+                crate::mock::get_valid_proof(),
+            );
+
+            // Token minted by finish_mint dispatched from contract
+            let deposit = NFTDepositBase::get() + NFTDepositPerByte::get() * 10;
+            assert_eq!(ERC721::owner_of(token_id), Some(account_id.clone()));
+            assert_eq!(ERC721::balance_of(&account_id), 1);
+            assert_eq!(NftReg::token_metadata(token_id), metadata);
+            assert_eq!(NftReg::registry_uid_for_token_id(token_id), REGISTRY_ID);
+            assert_eq!(NftReg::deposit_by_token_id(token_id), deposit);
+            assert_eq!(
+                Balances::reserved_balance(&account_id),
+                NFTValidationRegistryDeposit::get() + deposit
+            );
+            assert!(mint_nft_event_exists());
+        });
+    }
+
+    #[test]
+    #[ignore]
+    fn validation_contract_rejects_missing_leaf() {
+        ExtBuilder::default().build().execute_with(|| {
+            let account_id = alice();
+            let token_id: H256 = H256::from_low_u64_be(1);
+            let (contract_address, anchor_id, _) = setup_registry();
+
+            call_contract_test(
+                &account_id,
+                &contract_address,
+                "add_required_leaf",
+                H256::repeat_byte(9).encode(),
+            );
+
+            mint_test(
+                REGISTRY_ID,
+                &account_id,
+                token_id,
+                anchor_id,
+                vec![b'x'; 10],
+                crate::mock::get_valid_proof(),
+            );
+
+            assert_eq!(ERC721::owner_of(token_id), None);
+            assert_eq!(NftReg::deposit_by_token_id(token_id), 0);
+            assert!(!mint_nft_event_exists());
+        });
+    }
+
+    #[test]
+    #[ignore]
+    fn validation_contract_rejects_not_allowed_owner() {
+        ExtBuilder::default().build().execute_with(|| {
+            let account_id = alice();
+            let token_id: H256 = H256::from_low_u64_be(1);
+            let (contract_address, anchor_id, _) = setup_registry();
+
+            call_contract_test(
+                &account_id,
+                &contract_address,
+                "allow_owner",
+                bob().encode(),
+            );
+
+            mint_test(
+                REGISTRY_ID,
+                &account_id,
+                token_id,
+                anchor_id,
+                vec![b'x'; 10],
+                crate::mock::get_valid_proof(),
+            );
+
+            assert_eq!(ERC721::owner_of(token_id), None);
+            assert!(!mint_nft_event_exists());
+        });
+    }
+
+    #[test]
+    #[ignore]
+    fn safe_transfer_to_receiver_contract() {
+        ExtBuilder::default().build().execute_with(|| {
+            let account_id = alice();
+            let token_id: H256 = H256::from_low_u64_be(1);
+
+            create_account_test(&account_id);
+            let receiver_address = deploy_receiver_contract(&account_id);
+            assert_ok!(ERC721::_mint(&account_id, &token_id));
+
+            // Contract not accepting tokens reverts the transfer
+            call_contract_test(
+                &account_id,
+                &receiver_address,
+                "set_accepting",
+                false.encode(),
+            );
+            assert_eq!(
+                ERC721::_safe_transfer_from(
+                    &account_id,
+                    &account_id,
+                    &receiver_address,
+                    &token_id,
+                    vec![]
+                ),
+                Err(crate::erc721::Error::<E2eTest>::TransferRejected.into())
+            );
+            assert_eq!(ERC721::owner_of(token_id), Some(account_id.clone()));
+
+            call_contract_test(
+                &account_id,
+                &receiver_address,
+                "set_accepting",
+                true.encode(),
+            );
+            assert_ok!(ERC721::_safe_transfer_from(
+                &account_id,
+                &account_id,
+                &receiver_address,
+                &token_id,
+                vec![]
+            ));
+            assert_eq!(ERC721::owner_of(token_id), Some(receiver_address));
+        });
+    }
+
+    // Calls encoded by contracts use the module indices of the test runtime
+    #[test]
+    fn module_indices_match_test_runtime() {
+        let balances_call = Call::Balances(balances::Call::transfer(bob(), 10_000));
+        let nft_call = Call::NftRegistry(crate::Call::burn(H256::from_low_u64_be(1)));
+        let erc721_call = Call::ERC721(crate::erc721::Call::burn(0, H256::from_low_u64_be(1)));
+
+        assert_eq!(balances_call.encode()[0], indices::BALANCES);
+        assert_eq!(nft_call.encode()[0], indices::NFT);
+        assert_eq!(erc721_call.encode()[0], indices::ERC721);
+    }
+
+    // Permits are signed with the same keys as extrinsics, it needs no fixture
+    #[test]
+    fn permit_signed_with_sr25519() {
+        ExtBuilder::default().build().execute_with(|| {
+            let pair = sr25519::Pair::from_seed(&[7; 32]);
+            let owner = MultiSigner::from(pair.public()).into_account();
+            let token_id = H256::from_low_u64_be(1);
+            assert_ok!(ERC721::_mint(&owner, &token_id));
+
+            let payload = ERC721::permit_payload(&0, &owner, &bob(), &token_id, 10, 0);
+            let signature = MultiSignature::from(pair.sign(&payload));
+            assert_ok!(ERC721::permit(
+                Origin::signed(alice()),
+                0,
+                owner,
+                bob(),
+                token_id,
+                10,
+                0,
+                signature
+            ));
+            assert_eq!(ERC721::get_approved(token_id), Some(bob()));
+        });
+    }
+
+    #[test]
+    #[ignore]
+    fn mint_signed_via_validation_contract() {
+        ExtBuilder::default().build().execute_with(|| {
+            let sponsor = alice();
+            let relayer = bob();
+            let pair = sr25519::Pair::from_seed(&[7; 32]);
+            let owner = MultiSigner::from(pair.public()).into_account();
+            let (contract_address, anchor_id, leaf_hash) = setup_registry();
+            create_account_test(&relayer);
+
+            call_contract_test(
+                &sponsor,
+                &contract_address,
+                "add_required_leaf",
+                leaf_hash.encode(),
+            );
+
+            let deposit = NFTDepositBase::get() + NFTDepositPerByte::get() * 10;
+            assert_ok!(NftReg::set_sponsor_allowance(
+                Origin::signed(sponsor.clone()),
+                owner.clone(),
+                1,
+                deposit + 100
+            ));
+
+            // Owner without balance signs, relayer submits, sponsor pays
+            let (proof, _, static_proofs) = crate::mock::get_valid_proof();
+            let request = crate::MintRequest {
+                owner: owner.clone(),
+                sponsor: sponsor.clone(),
+                registry_uid: REGISTRY_ID,
+                token_id: H256::from_low_u64_be(1),
+                metadata: vec![b'x'; 10],
+                anchor_id,
+                proofs: vec![proof],
+                static_proofs,
+                fee: 100,
+                nonce: 0,
+                deadline: 10,
+            };
+            let signature =
+                MultiSignature::from(pair.sign(&NftReg::mint_request_payload(&request)));
+            let relayer_balance = Balances::free_balance(&relayer);
+            let reserved = Balances::reserved_balance(&sponsor);
+
+            assert_ok!(NftReg::mint_signed(
+                Origin::signed(relayer.clone()),
+                request.clone(),
+                signature,
+                GAS_LIMIT
+            ));
+            assert_eq!(ERC721::owner_of(request.token_id), Some(owner.clone()));
+            assert_eq!(Balances::free_balance(&relayer), relayer_balance + 100);
+            assert_eq!(Balances::reserved_balance(&sponsor), reserved + deposit);
+            assert_eq!(NftReg::sponsor_allowance(&sponsor, &owner), None);
+            assert_eq!(NftReg::mint_nonce(&owner), 1);
+        });
+    }
+}
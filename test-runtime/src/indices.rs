@@ -0,0 +1,7 @@
+// Module indices of the calls in the runtime, the position of each module in `construct_runtime!`.
+// The contract `call` crate can not depend on the runtime, it includes this file so contracts
+// encode calls with the same indices. The indices are checked against the encoded runtime calls.
+
+pub const NFT: u8 = 2;
+pub const ERC721: u8 = 3;
+pub const BALANCES: u8 = 6;
@@ -0,0 +1,232 @@
+//! Minimal runtime including the NFT registry pallet.
+//!
+//! It is the reference runtime for calls dispatched from contracts. The order of
+//! modules in `construct_runtime!` defines the module indices in the encoded
+//! `Call`, exported in `indices`. The `call` crate uses these indices and checks
+//! its encodings against this runtime.
+
+use nftregistry::{anchor, auction, erc1155, erc721, fractional, lending, marketplace};
+use sp_core::{crypto::AccountId32, H256};
+use sp_runtime::{
+    generic,
+    traits::{BlakeTwo256, IdentityLookup},
+    MultiSignature, MultiSigner, Perbill,
+};
+use support::{construct_runtime, parameter_types, weights::Weight};
+
+pub use balances::Call as BalancesCall;
+pub use nftregistry::erc721::Call as ERC721Call;
+pub use nftregistry::Call as NftRegistryCall;
+pub use nftregistry::CoOwnerAction;
+
+pub mod indices;
+
+pub type AccountId = AccountId32;
+pub type Balance = u128;
+pub type BlockNumber = u64;
+pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
+pub type Block = generic::Block<Header, UncheckedExtrinsic>;
+pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<AccountId, Call, MultiSignature, ()>;
+
+parameter_types! {
+    pub const BlockHashCount: BlockNumber = 250;
+    pub const MaximumBlockWeight: Weight = 1_000_000;
+    pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    pub const MaximumBlockLength: u32 = 5 * 1024 * 1024;
+}
+
+impl system::Trait for Runtime {
+    type Call = Call;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Index = u64;
+    type BlockNumber = BlockNumber;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type Header = Header;
+    type Event = Event;
+    type Origin = Origin;
+    type BlockHashCount = BlockHashCount;
+    type MaximumBlockWeight = MaximumBlockWeight;
+    type MaximumBlockLength = MaximumBlockLength;
+    type AvailableBlockRatio = AvailableBlockRatio;
+    type Version = ();
+    type ModuleToIndex = ModuleToIndex;
+}
+
+parameter_types! {
+    pub const MinimumPeriod: u64 = 1;
+}
+
+impl pallet_timestamp::Trait for Runtime {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: Balance = 500;
+    pub const TransferFee: Balance = 0;
+    pub const CreationFee: Balance = 0;
+}
+
+impl balances::Trait for Runtime {
+    type Balance = Balance;
+    type OnFreeBalanceZero = Contracts;
+    type OnNewAccount = ();
+    type Event = Event;
+    type DustRemoval = ();
+    type TransferPayment = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type TransferFee = TransferFee;
+    type CreationFee = CreationFee;
+}
+
+parameter_types! {
+    pub const SignedClaimHandicap: u32 = 2;
+    pub const TombstoneDeposit: Balance = 16;
+    pub const StorageSizeOffset: u32 = 8;
+    pub const RentByteFee: Balance = 4;
+    pub const RentDepositOffset: Balance = 10_000;
+    pub const SurchargeReward: Balance = 150;
+    pub const TransactionBaseFee: Balance = 2;
+    pub const TransactionByteFee: Balance = 6;
+    pub const ContractFee: Balance = 21;
+    pub const CallBaseFee: u64 = 135;
+    pub const InstantiateBaseFee: u64 = 175;
+    pub const MaxDepth: u32 = 100;
+    pub const MaxValueSize: u32 = 16_384;
+    pub const BlockGasLimit: u64 = 100_000;
+}
+
+impl contracts::Trait for Runtime {
+    type Currency = Balances;
+    type Time = Timestamp;
+    type Randomness = RandomnessCollectiveFlip;
+    type Call = Call;
+    type Event = Event;
+    type DetermineContractAddress = contracts::SimpleAddressDeterminator<Runtime>;
+    type ComputeDispatchFee = contracts::DefaultDispatchFeeComputor<Runtime>;
+    type TrieIdGenerator = contracts::TrieIdFromParentCounter<Runtime>;
+    type GasPayment = ();
+    type RentPayment = ();
+    type SignedClaimHandicap = SignedClaimHandicap;
+    type TombstoneDeposit = TombstoneDeposit;
+    type StorageSizeOffset = StorageSizeOffset;
+    type RentByteFee = RentByteFee;
+    type RentDepositOffset = RentDepositOffset;
+    type SurchargeReward = SurchargeReward;
+    type TransferFee = TransferFee;
+    type CreationFee = CreationFee;
+    type TransactionBaseFee = TransactionBaseFee;
+    type TransactionByteFee = TransactionByteFee;
+    type ContractFee = ContractFee;
+    type CallBaseFee = CallBaseFee;
+    type InstantiateBaseFee = InstantiateBaseFee;
+    type MaxDepth = MaxDepth;
+    type MaxValueSize = MaxValueSize;
+    type BlockGasLimit = BlockGasLimit;
+}
+
+impl anchor::Trait for Runtime {}
+
+impl erc1155::Trait for Runtime {
+    type Event = Event;
+    type TokenBalance = u128;
+    type TokenHooks = NftRegistry;
+}
+
+parameter_types! {
+    pub const MaxNameLength: u32 = 64;
+    pub const MaxSymbolLength: u32 = 16;
+    pub const MaxUriLength: u32 = 256;
+    pub const MaxBatchSize: u32 = 100;
+    pub const MaxNestingDepth: u32 = 8;
+    pub const MaxApprovalsExpiring: u32 = 100;
+}
+
+impl erc721::Trait for Runtime {
+    type Event = Event;
+    type Randomness = RandomnessCollectiveFlip;
+    type TokenIndex = u64;
+    type CollectionId = u32;
+    type MetadataOrigin = system::EnsureRoot<AccountId>;
+    type MaxNameLength = MaxNameLength;
+    type MaxSymbolLength = MaxSymbolLength;
+    type MaxUriLength = MaxUriLength;
+    type MaxBatchSize = MaxBatchSize;
+    type MaxNestingDepth = MaxNestingDepth;
+    type MaxApprovalsExpiring = MaxApprovalsExpiring;
+    type TokenReceiver = nftregistry::ContractReceiver<Runtime>;
+    type TokenHooks = (NftRegistry, Marketplace);
+    type Signature = MultiSignature;
+    type Signer = MultiSigner;
+}
+
+impl fractional::Trait for Runtime {
+    type Event = Event;
+}
+
+parameter_types! {
+    pub const NFTDepositBase: Balance = 1_000;
+    pub const NFTDepositPerByte: Balance = 1_000;
+    pub const NFTValidationRegistryDeposit: Balance = 1_000;
+    pub const ReceiverGasLimit: u64 = 500_000;
+}
+
+impl nftregistry::Trait for Runtime {
+    type Event = Event;
+    type Randomness = RandomnessCollectiveFlip;
+    type NFTDepositBase = NFTDepositBase;
+    type NFTDepositPerByte = NFTDepositPerByte;
+    type NFTValidationRegistryDeposit = NFTValidationRegistryDeposit;
+    type Currency = Balances;
+    type ReceiverGasLimit = ReceiverGasLimit;
+}
+
+impl marketplace::Trait for Runtime {
+    type Event = Event;
+}
+
+parameter_types! {
+    pub const ExtensionPeriod: BlockNumber = 10;
+}
+
+impl auction::Trait for Runtime {
+    type Event = Event;
+    type ExtensionPeriod = ExtensionPeriod;
+    type MaxAuctionsEnding = MaxAuctionsEnding;
+}
+
+impl lending::Trait for Runtime {
+    type Event = Event;
+}
+
+// The position of each module is its index in the encoded `Call`,
+// Balances is kept at the same index with the substrate node runtime.
+construct_runtime!(
+    pub enum Runtime where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic
+    {
+        System: system::{Module, Call, Storage, Config, Event},
+        Timestamp: pallet_timestamp::{Module, Call, Storage, Inherent},
+        NftRegistry: nftregistry::{Module, Call, Storage, Event<T>},
+        ERC721: erc721::{Module, Call, Storage, Config, Event<T>},
+        Anchor: anchor::{Module, Call, Storage},
+        Contracts: contracts::{Module, Call, Storage, Config<T>, Event<T>},
+        Balances: balances::{Module, Call, Storage, Config<T>, Event<T>},
+        RandomnessCollectiveFlip: randomness_collective_flip::{Module, Call, Storage},
+        ERC1155: erc1155::{Module, Call, Storage, Event<T>},
+        Fractional: fractional::{Module, Call, Storage, Event<T>},
+        Marketplace: marketplace::{Module, Call, Storage, Event<T>},
+        Auction: auction::{Module, Call, Storage, Event<T>},
+        Lending: lending::{Module, Call, Storage, Event<T>},
+    }
+);
+
+/// Index of the module dispatching the call
+pub fn module_index(call: &Call) -> u8 {
+    codec::Encode::encode(call)[0]
+}